@@ -0,0 +1,90 @@
+use std::time::{Duration, Instant};
+
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use erc1155_wasm::ERC1155;
+
+const OWNER: &str = "owner";
+const MINTER: &str = "minter";
+const TOKEN: u32 = 1;
+const HOLDER_COUNTS: [usize; 3] = [10_000, 100_000, 1_000_000];
+const BATCH_SIZE: usize = 100;
+
+fn holder(index: usize) -> String {
+    format!("0x{:040x}", index)
+}
+
+/// Contract with `holders` accounts each holding 1_000 of `TOKEN`; `MINTER` keeps an
+/// effectively unlimited float of `TOKEN` and of `BATCH_SIZE` extra IDs.
+fn populated(holders: usize) -> ERC1155 {
+    let mut contract = ERC1155::new(OWNER).unwrap();
+    contract.add_admin(OWNER, MINTER).unwrap();
+    contract.mint(MINTER, TOKEN, u64::MAX / 2).unwrap();
+    for index in 0..holders {
+        contract.transfer(MINTER, &holder(index), TOKEN, 1_000).unwrap();
+    }
+    for token_id in 0..BATCH_SIZE as u32 {
+        contract.mint(MINTER, 100 + token_id, u64::MAX / 2).unwrap();
+    }
+    contract
+}
+
+/// Times `iters` calls of `op` on a fresh copy of `contract`, so the event log of
+/// one sample never carries over into the next.
+fn time_mutations(contract: &ERC1155, iters: u64, mut op: impl FnMut(&mut ERC1155)) -> Duration {
+    let mut working = contract.clone();
+    let start = Instant::now();
+    for _ in 0..iters {
+        op(&mut working);
+    }
+    start.elapsed()
+}
+
+fn bench_operations(c: &mut Criterion) {
+    let batch_ids: Vec<u32> = (0..BATCH_SIZE as u32).map(|offset| 100 + offset).collect();
+    let batch_amounts = vec![1u64; BATCH_SIZE];
+
+    for &holders in &HOLDER_COUNTS {
+        let contract = populated(holders);
+        let probe = holder(holders / 2);
+        let recipient = holder(holders - 1);
+        let owners: Vec<String> = (0..BATCH_SIZE).map(|index| holder(index * (holders / BATCH_SIZE))).collect();
+
+        let mut group = c.benchmark_group(format!("{}_holders", holders));
+        group.sample_size(20).measurement_time(Duration::from_secs(2));
+
+        group.bench_function(BenchmarkId::new("balance_of", "hit"), |b| {
+            b.iter(|| black_box(contract.balance_of(black_box(&probe), TOKEN)))
+        });
+        group.bench_function(BenchmarkId::new("balance_of", "miss"), |b| {
+            b.iter(|| black_box(contract.balance_of(black_box("0xmissing"), TOKEN)))
+        });
+        group.bench_function(BenchmarkId::new("balance_of_batch", BATCH_SIZE), |b| {
+            b.iter_batched(
+                || owners.clone(),
+                |owners| black_box(contract.balance_of_batch(owners, vec![TOKEN; BATCH_SIZE]).unwrap()),
+                BatchSize::SmallInput,
+            )
+        });
+
+        group.bench_function(BenchmarkId::new("transfer", "existing_holders"), |b| {
+            b.iter_custom(|iters| {
+                time_mutations(&contract, iters, |working| {
+                    working.transfer(MINTER, &recipient, TOKEN, 1).unwrap();
+                })
+            })
+        });
+        group.bench_function(BenchmarkId::new("transfer_batch", BATCH_SIZE), |b| {
+            b.iter_custom(|iters| {
+                time_mutations(&contract, iters, |working| {
+                    working
+                        .transfer_batch(MINTER, &recipient, batch_ids.clone(), batch_amounts.clone())
+                        .unwrap();
+                })
+            })
+        });
+        group.finish();
+    }
+}
+
+criterion_group!(benches, bench_operations);
+criterion_main!(benches);
@@ -0,0 +1,116 @@
+//! Replays arbitrary operation sequences against the contract and checks state-machine invariants.
+//!
+//! Run with `cargo +nightly fuzz run operations`.
+#![no_main]
+
+use arbitrary::Arbitrary;
+use erc1155_wasm::{ErrorKind, ERC1155};
+use libfuzzer_sys::fuzz_target;
+
+const OWNER: &str = "owner";
+const ACCOUNTS: [&str; 4] = ["a0", "a1", "a2", "a3"];
+// The largest fungible ID: IDs with the top bit set are NFTs and cannot be minted freely.
+const TOKENS: [u32; 4] = [0, 1, 2, u32::MAX >> 1];
+
+#[derive(Arbitrary, Debug)]
+enum Op {
+    AddAdmin { caller: u8, admin: u8 },
+    Mint { caller: u8, token: u8, amount: u64 },
+    Transfer { caller: u8, to: u8, token: u8, amount: u64 },
+    TransferFrom { caller: u8, from: u8, to: u8, token: u8, amount: u64 },
+    TransferBatch { caller: u8, to: u8, items: Vec<(u8, u64)> },
+    Burn { caller: u8, token: u8, amount: u64 },
+    Approve { caller: u8, operator: u8 },
+    RevokeApproval { caller: u8, operator: u8 },
+    RevokeAllApprovals { caller: u8 },
+    TransferOwnership { caller: u8, new_owner: u8 },
+    Pause { caller: u8 },
+    Unpause { caller: u8 },
+    SetBlacklisted { caller: u8, account: u8, blacklisted: bool },
+}
+
+/// Maps a fuzzer byte to a caller; index 0 is the original contract owner.
+fn account(index: u8) -> &'static str {
+    match index as usize % (ACCOUNTS.len() + 1) {
+        0 => OWNER,
+        n => ACCOUNTS[n - 1],
+    }
+}
+
+fn token(index: u8) -> u32 {
+    TOKENS[index as usize % TOKENS.len()]
+}
+
+fn balances(contract: &ERC1155) -> Vec<u64> {
+    let mut holders = vec![OWNER];
+    holders.extend_from_slice(&ACCOUNTS);
+    holders
+        .iter()
+        .flat_map(|holder| TOKENS.iter().map(move |token| contract.balance_of(holder, *token)))
+        .collect()
+}
+
+fn supply(contract: &ERC1155, token: u32) -> u128 {
+    std::iter::once(OWNER)
+        .chain(ACCOUNTS.iter().copied())
+        .map(|holder| contract.balance_of(holder, token) as u128)
+        .sum()
+}
+
+fuzz_target!(|ops: Vec<Op>| {
+    let mut contract = ERC1155::new(OWNER).unwrap();
+    let mut minted = [0u128; TOKENS.len()];
+
+    for op in ops {
+        let before = balances(&contract);
+        let result = match &op {
+            Op::AddAdmin { caller, admin } => contract.add_admin(account(*caller), account(*admin)).map(|_| ()),
+            Op::Mint { caller, token: index, amount } => {
+                let result = contract.mint(account(*caller), token(*index), *amount).map(|_| ());
+                if result.is_ok() {
+                    minted[*index as usize % TOKENS.len()] += *amount as u128;
+                }
+                result
+            }
+            Op::Transfer { caller, to, token: index, amount } => contract
+                .transfer(account(*caller), account(*to), token(*index), *amount)
+                .map(|_| ()),
+            Op::TransferFrom { caller, from, to, token: index, amount } => contract
+                .transfer_from(account(*caller), account(*from), account(*to), token(*index), *amount)
+                .map(|_| ()),
+            Op::TransferBatch { caller, to, items } => {
+                let (ids, amounts) = items.iter().map(|(index, amount)| (token(*index), *amount)).unzip();
+                contract.transfer_batch(account(*caller), account(*to), ids, amounts).map(|_| ())
+            }
+            Op::Burn { caller, token: index, amount } => {
+                let result = contract.burn(account(*caller), token(*index), *amount).map(|_| ());
+                if result.is_ok() {
+                    minted[*index as usize % TOKENS.len()] -= *amount as u128;
+                }
+                result
+            }
+            Op::Approve { caller, operator } => contract.approve(account(*caller), account(*operator), 0).map(|_| ()),
+            Op::RevokeApproval { caller, operator } => {
+                contract.revoke_approval(account(*caller), account(*operator)).map(|_| ())
+            }
+            Op::RevokeAllApprovals { caller } => contract.revoke_all_approvals(account(*caller)).map(|_| ()),
+            Op::TransferOwnership { caller, new_owner } => {
+                contract.transfer_ownership(account(*caller), account(*new_owner)).map(|_| ())
+            }
+            Op::Pause { caller } => contract.pause(account(*caller)).map(|_| ()),
+            Op::Unpause { caller } => contract.unpause(account(*caller)).map(|_| ()),
+            Op::SetBlacklisted { caller, account: target, blacklisted } => {
+                contract.set_blacklisted(account(*caller), account(*target), *blacklisted).map(|_| ())
+            }
+        };
+
+        if let Err(err) = &result {
+            // Nothing re-enters the contract here, so a reentrancy error means a guard was left locked.
+            assert_ne!(err.kind(), ErrorKind::Reentrancy, "guard not released before {:?}", op);
+            assert_eq!(before, balances(&contract), "failed {:?} mutated balances", op);
+        }
+        for (index, token) in TOKENS.iter().enumerate() {
+            assert_eq!(supply(&contract, *token), minted[index], "supply of {} drifted after {:?}", token, op);
+        }
+    }
+});
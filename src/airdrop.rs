@@ -0,0 +1,102 @@
+//! Airdrops: minting one token to many recipients in a single call.
+//!
+//! The whole distribution is validated before the first balance changes, so it
+//! applies fully or not at all, and it runs inside one call instead of one
+//! boundary crossing per recipient.
+
+use std::collections::HashMap;
+
+use wasm_bindgen::prelude::*;
+
+use crate::gas::GasCost;
+use crate::pipeline::CallKind;
+use crate::{ContractError, ErrorKind, Receipt, Result, ERC1155};
+
+impl ERC1155 {
+    /// Body of [`ERC1155::mint_to_many`] and [`ERC1155::mint_to_many_same`].
+    fn airdrop(
+        &mut self,
+        method: &'static str,
+        caller: &str,
+        token_id: u32,
+        recipients: &[String],
+        amounts: &[u64],
+    ) -> Result<Receipt> {
+        let accounts: Vec<&str> = recipients.iter().map(String::as_str).collect();
+        self.enter(method, CallKind::User, caller, &accounts, |this, call| {
+            if call.accounts.is_empty() {
+                return Err(ContractError::new(ErrorKind::InvalidInput, "Recipients must not be empty."));
+            }
+            if call.accounts.len() != amounts.len() {
+                return Err(ContractError::new(ErrorKind::InvalidInput, "Recipients and amounts length mismatch."));
+            }
+            if amounts.contains(&0) {
+                return Err(ContractError::new(ErrorKind::InvalidInput, "Airdrop amounts must be positive."));
+            }
+
+            // A recipient listed twice receives the sum of its amounts.
+            let mut credits: HashMap<&str, u64> = HashMap::new();
+            for (recipient, &amount) in call.accounts.iter().zip(amounts) {
+                let credit = credits.entry(recipient).or_insert(0);
+                *credit = credit
+                    .checked_add(amount)
+                    .ok_or_else(|| ContractError::new(ErrorKind::Overflow, "Airdrop amount overflow."))?;
+            }
+            let total = credits
+                .values()
+                .try_fold(0u64, |total, credit| total.checked_add(*credit))
+                .ok_or_else(|| ContractError::new(ErrorKind::Overflow, "Airdrop amount overflow."))?;
+            this.check_mint(&call.caller, token_id, total)?;
+            if credits
+                .iter()
+                .any(|(recipient, credit)| this.balances.get(recipient, token_id).checked_add(*credit).is_none())
+            {
+                return Err(ContractError::new(ErrorKind::Overflow, "Recipient balance overflow."));
+            }
+            let items = call.accounts.len() as u64;
+            this.charge_gas(GasCost {
+                reads: 2 + items,
+                writes: items,
+                events: items,
+                iterations: items,
+            })?;
+
+            this.tokens.create(token_id, &call.caller);
+            let mut changes = Vec::with_capacity(call.accounts.len());
+            for (recipient, &amount) in call.accounts.iter().zip(amounts) {
+                changes.extend(this.move_tokens(&call.caller, None, Some(recipient), token_id, amount)?);
+            }
+            Ok(this.receipt(changes))
+        })
+    }
+}
+
+#[wasm_bindgen]
+impl ERC1155 {
+    /// Mints `token_id` to many recipients at once, with the same rules as `mint`. Either
+    /// every recipient is credited or, on error, none is.
+    /// # Parameters
+    /// - `recipients`: The accounts to credit; an account may appear more than once.
+    /// - `amounts`: The amount for each recipient, paired index-wise with `recipients`.
+    pub fn mint_to_many(
+        &mut self,
+        caller: &str,
+        token_id: u32,
+        recipients: Vec<String>,
+        amounts: Vec<u64>,
+    ) -> Result<Receipt> {
+        self.airdrop("mint_to_many", caller, token_id, &recipients, &amounts)
+    }
+
+    /// Mints `amount` of `token_id` to each of `recipients`; see `mint_to_many`.
+    pub fn mint_to_many_same(
+        &mut self,
+        caller: &str,
+        token_id: u32,
+        recipients: Vec<String>,
+        amount: u64,
+    ) -> Result<Receipt> {
+        let amounts = vec![amount; recipients.len()];
+        self.airdrop("mint_to_many_same", caller, token_id, &recipients, &amounts)
+    }
+}
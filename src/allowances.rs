@@ -0,0 +1,139 @@
+//! Scoped operator approvals.
+//!
+//! Besides blanket approvals, which let an operator move any amount of every
+//! token, an owner can give an operator allowances limited to given token IDs
+//! and cumulative amounts, as marketplaces need. Transfers by an operator
+//! without a blanket approval spend its allowance for the token, whichever
+//! transfer method makes them: every one checks the caller with `check_spend`
+//! and records the transfer with `spend`. Allowances do not expire and do not
+//! cover burns; setting one to zero revokes it.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::gas::GasCost;
+use crate::pipeline::CallKind;
+use crate::{log, ContractError, ErrorKind, EventKind, EventRecord, Receipt, Result, ERC1155};
+
+/// `Owner -> (Operator -> (TokenID -> amount left))`.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub(crate) struct Allowances {
+    by_owner: HashMap<String, HashMap<String, HashMap<u32, u64>>>,
+}
+
+impl Allowances {
+    /// Returns how much of `token_id` `operator` may still move for `owner`.
+    pub fn get(&self, owner: &str, operator: &str, token_id: u32) -> u64 {
+        self.by_owner
+            .get(owner)
+            .and_then(|operators| operators.get(operator))
+            .and_then(|tokens| tokens.get(&token_id))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Returns the number of stored `(owner, operator, token)` allowances.
+    pub fn len(&self) -> usize {
+        self.by_owner
+            .values()
+            .flat_map(|operators| operators.values())
+            .map(HashMap::len)
+            .sum()
+    }
+
+    /// Sets the allowance, removing entries that drop to zero.
+    pub fn set(&mut self, owner: &str, operator: &str, token_id: u32, amount: u64) {
+        if amount > 0 {
+            let operators = self.by_owner.entry(owner.to_string()).or_default();
+            operators.entry(operator.to_string()).or_default().insert(token_id, amount);
+            return;
+        }
+        let Some(operators) = self.by_owner.get_mut(owner) else { return };
+        if let Some(tokens) = operators.get_mut(operator) {
+            tokens.remove(&token_id);
+            if tokens.is_empty() {
+                operators.remove(operator);
+            }
+        }
+        if operators.is_empty() {
+            self.by_owner.remove(owner);
+        }
+    }
+}
+
+impl ERC1155 {
+    /// Returns whether `caller` moves `from`'s tokens under scoped allowances, rather
+    /// than as `from` itself or one of its approved operators.
+    fn is_scoped_spender(&self, caller: &str, from: &str) -> bool {
+        caller != from && !self.is_approved(from, caller)
+    }
+
+    /// Checks that `caller` may move `amount` of `from`'s `token_id`, in total within one
+    /// call: as `from` itself, as an approved operator, or within its allowance.
+    pub(crate) fn check_spend(&self, caller: &str, from: &str, token_id: u32, amount: u64) -> Result<()> {
+        if self.is_scoped_spender(caller, from) && self.allowances.get(from, caller, token_id) < amount {
+            log(&format!("Transfer failed: {} is not approved by {}.", caller, from));
+            return Err(ContractError::new(ErrorKind::Unauthorized, "Caller is not authorized to transfer."));
+        }
+        Ok(())
+    }
+
+    /// Spends `amount` of `caller`'s allowance for `from`'s `token_id` if it moved them
+    /// under scoped allowances. `check_spend` must have accepted the amount.
+    pub(crate) fn spend(&mut self, caller: &str, from: &str, token_id: u32, amount: u64) {
+        if self.is_scoped_spender(caller, from) {
+            let left = self.allowances.get(from, caller, token_id) - amount;
+            self.allowances.set(from, caller, token_id, left);
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl ERC1155 {
+    /// Lets `operator` transfer up to `amounts[i]` of the caller's `token_ids[i]` in total,
+    /// replacing the allowances given for those tokens before; zero revokes one.
+    pub fn approve_scoped(
+        &mut self,
+        caller: &str,
+        operator: &str,
+        token_ids: Vec<u32>,
+        amounts: Vec<u64>,
+    ) -> Result<Receipt> {
+        self.enter("approve_scoped", CallKind::User, caller, &[operator], |this, call| {
+            let (owner, operator) = (&call.caller, &call.accounts[0]);
+            if token_ids.len() != amounts.len() {
+                return Err(ContractError::new(ErrorKind::InvalidInput, "Token IDs and amounts length mismatch."));
+            }
+            this.check_batch_size(token_ids.len())?;
+            if owner == operator {
+                return Err(ContractError::new(ErrorKind::InvalidInput, "Cannot approve oneself."));
+            }
+            let items = token_ids.len() as u64;
+            this.charge_gas(GasCost {
+                writes: items,
+                events: items,
+                iterations: items,
+                ..GasCost::default()
+            })?;
+
+            for (token_id, amount) in token_ids.into_iter().zip(amounts) {
+                this.allowances.set(owner, operator, token_id, amount);
+                let mut event = EventRecord::new(this.next_event_seq(), EventKind::ScopedApproval, operator);
+                event.from = Some(owner.clone());
+                event.token_id = Some(token_id);
+                event.amount = Some(amount);
+                this.emit(event);
+            }
+            Ok(this.receipt(Vec::new()))
+        })
+    }
+
+    /// Returns how much of `owner`'s `token_id` `operator` may still transfer under a scoped
+    /// approval.
+    pub fn scoped_allowance(&self, owner: &str, operator: &str, token_id: u32) -> u64 {
+        self.allowances
+            .get(&self.resolve_account(owner), &self.resolve_account(operator), token_id)
+    }
+}
@@ -0,0 +1,97 @@
+//! Aggregate views for dashboards.
+//!
+//! Holder counts are maintained by `Balances` on every write, so they are O(1)
+//! to read. Circulating supply leaves out the balances of treasury accounts the
+//! owner or admins designate, such as a treasury wallet or a burn address.
+
+use std::collections::HashSet;
+use std::convert::TryFrom;
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::escrow::RESERVED_PREFIX;
+use crate::pipeline::CallKind;
+use crate::{BalanceEntry, ContractError, ErrorKind, EventKind, EventRecord, Receipt, Result, ERC1155};
+
+/// Accounts whose balances do not count as circulating.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub(crate) struct Analytics {
+    #[serde(serialize_with = "crate::canonical::sorted")]
+    treasury: HashSet<String>,
+}
+
+#[wasm_bindgen]
+impl ERC1155 {
+    /// Marks or unmarks `account` as a treasury account, whose balances are left out of
+    /// `circulating_supply` (owner or admins only).
+    pub fn set_treasury_account(&mut self, caller: &str, account: &str, enabled: bool) -> Result<Receipt> {
+        self.enter("set_treasury_account", CallKind::Admin, caller, &[account], |this, call| {
+            if !this.access_control.is_owner(&call.caller) && !this.access_control.is_admin(&call.caller) {
+                return Err(ContractError::new(
+                    ErrorKind::Unauthorized,
+                    "Caller is not authorized to manage treasury accounts.",
+                ));
+            }
+
+            let account = &call.accounts[0];
+            if enabled {
+                this.analytics.treasury.insert(account.clone());
+            } else {
+                this.analytics.treasury.remove(account);
+            }
+            let mut event = EventRecord::new(this.next_event_seq(), EventKind::TreasuryAccountUpdated, &call.caller);
+            event.to = Some(account.clone());
+            event.approved = Some(enabled);
+            this.emit(event);
+            Ok(this.receipt(Vec::new()))
+        })
+    }
+
+    /// Returns whether `account` is a treasury account.
+    pub fn is_treasury_account(&self, account: &str) -> bool {
+        self.analytics.treasury.contains(self.resolve_account(account).as_ref())
+    }
+
+    /// Returns the number of accounts holding some of `token_id`.
+    pub fn holder_count(&self, token_id: u32) -> u64 {
+        self.balances.holder_count(token_id)
+    }
+
+    /// Returns the number of accounts holding some of any token.
+    pub fn unique_holders_total(&self) -> u64 {
+        self.balances.unique_holders()
+    }
+
+    /// Returns the `n` largest holders of `token_id`, largest first; ties are ordered by account.
+    pub fn top_holders(&self, token_id: u32, n: usize) -> Vec<BalanceEntry> {
+        let mut holders: Vec<(String, u64)> = self
+            .balances
+            .holders(token_id)
+            .filter(|(owner, _)| !owner.starts_with(RESERVED_PREFIX))
+            .map(|(owner, balance)| (owner.into_owned(), balance))
+            .collect();
+        holders.sort_unstable_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        holders
+            .into_iter()
+            .take(n)
+            .map(|(owner, balance)| BalanceEntry {
+                owner,
+                token_id,
+                balance,
+            })
+            .collect()
+    }
+
+    /// Returns the total supply of `token_id` minus the balances of treasury accounts,
+    /// saturating at `u64::MAX`.
+    pub fn circulating_supply(&self, token_id: u32) -> u64 {
+        let held: u128 = self
+            .analytics
+            .treasury
+            .iter()
+            .map(|account| u128::from(self.balances.get(account, token_id)))
+            .sum();
+        u64::try_from(self.balances.total(token_id) - held).unwrap_or(u64::MAX)
+    }
+}
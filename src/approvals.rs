@@ -0,0 +1,177 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::storage::{Backend, Storage, Tables};
+
+/// Operator approvals: `Owner -> (Operator -> Expiry)`.
+///
+/// An entry is an approval; its expiry is a contract timestamp in milliseconds
+/// after which it no longer counts, or `None` for an indefinite approval.
+/// Expired entries are dropped lazily whenever the owner's approvals are written.
+/// The operators of each owner live in a storage [`Backend`]. Both levels are
+/// shared between forks until written; see `fork`.
+#[derive(Clone, Default)]
+pub(crate) struct Approvals {
+    by_owner: Arc<HashMap<String, Arc<Backend<Option<u64>>>>>,
+    /// Where new per-owner tables are opened.
+    tables: Tables,
+}
+
+/// Removes the approvals of `operators` that have expired at `now`.
+fn drop_expired(operators: &mut Backend<Option<u64>>, now: u64) {
+    let expired: Vec<String> = operators
+        .iter()
+        .filter(|(_, expires_at)| !is_live(*expires_at, now))
+        .map(|(operator, _)| operator.into_owned())
+        .collect();
+    for operator in expired {
+        operators.remove(&operator);
+    }
+}
+
+/// Returns whether an approval with `expires_at` still counts at `now`.
+pub(crate) fn is_live(expires_at: Option<u64>, now: u64) -> bool {
+    expires_at.is_none_or(|expires_at| now < expires_at)
+}
+
+impl Approvals {
+    pub fn new(tables: Tables) -> Self {
+        Self {
+            by_owner: Arc::default(),
+            tables,
+        }
+    }
+
+    /// Returns the stored expiry of `owner`'s approval for `operator`, live or not.
+    pub fn get(&self, owner: &str, operator: &str) -> Option<Option<u64>> {
+        self.by_owner
+            .get(owner)
+            .and_then(|operators| operators.get(operator))
+    }
+
+    /// Returns whether `operator` holds a live approval from `owner` at `now`.
+    pub fn is_approved(&self, owner: &str, operator: &str, now: u64) -> bool {
+        self.get(owner, operator)
+            .is_some_and(|expires_at| is_live(expires_at, now))
+    }
+
+    /// Approves `operator` for `owner` until `expires_at`, dropping `owner`'s expired entries.
+    pub fn set(&mut self, owner: &str, operator: &str, expires_at: Option<u64>, now: u64) {
+        let tables = &self.tables;
+        let operators = Arc::make_mut(
+            Arc::make_mut(&mut self.by_owner)
+                .entry(owner.to_string())
+                .or_insert_with(|| Arc::new(tables.open())),
+        );
+        drop_expired(operators, now);
+        operators.set(operator, expires_at);
+    }
+
+    /// Stores `expires_at` as `owner`'s approval for `operator`, or removes the approval when
+    /// `None`, leaving expired entries alone; for installing approvals copied from another
+    /// contract.
+    pub fn restore(&mut self, owner: &str, operator: &str, expires_at: Option<Option<u64>>) {
+        let by_owner = Arc::make_mut(&mut self.by_owner);
+        match expires_at {
+            Some(expires_at) => {
+                let tables = &self.tables;
+                let operators = by_owner.entry(owner.to_string()).or_insert_with(|| Arc::new(tables.open()));
+                Arc::make_mut(operators).set(operator, expires_at);
+            }
+            None => {
+                if let Some(operators) = by_owner.get_mut(owner) {
+                    let operators = Arc::make_mut(operators);
+                    operators.remove(operator);
+                    if operators.is_empty() {
+                        by_owner.remove(owner);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Removes `owner`'s approval for `operator`, dropping `owner`'s expired entries.
+    pub fn remove(&mut self, owner: &str, operator: &str, now: u64) {
+        if !self.by_owner.contains_key(owner) {
+            return;
+        }
+        let by_owner = Arc::make_mut(&mut self.by_owner);
+        if let Some(operators) = by_owner.get_mut(owner) {
+            let operators = Arc::make_mut(operators);
+            operators.remove(operator);
+            drop_expired(operators, now);
+            if operators.is_empty() {
+                by_owner.remove(owner);
+            }
+        }
+    }
+
+    /// Removes up to `limit` expired approvals of owners whose approvals have not been
+    /// written since they expired, along with owners left without approvals, and releases
+    /// the spare capacity of the tables it touches. Returns the number of approvals removed.
+    pub fn compact(&mut self, now: u64, limit: usize) -> usize {
+        let stale: Vec<String> = self
+            .by_owner
+            .iter()
+            .filter(|(_, operators)| operators.is_empty() || operators.iter().any(|(_, e)| !is_live(e, now)))
+            .map(|(owner, _)| owner.clone())
+            .collect();
+        if stale.is_empty() || limit == 0 {
+            return 0;
+        }
+        let by_owner = Arc::make_mut(&mut self.by_owner);
+        let mut removed = 0;
+        for owner in stale {
+            let operators = Arc::make_mut(by_owner.get_mut(&owner).expect("stale owners exist"));
+            let expired: Vec<String> = operators
+                .iter()
+                .filter(|(_, expires_at)| !is_live(*expires_at, now))
+                .map(|(operator, _)| operator.into_owned())
+                .take(limit - removed)
+                .collect();
+            for operator in &expired {
+                operators.remove(operator);
+            }
+            removed += expired.len();
+            operators.shrink_to_fit();
+            if operators.is_empty() {
+                by_owner.remove(&owner);
+            }
+            if removed == limit {
+                break;
+            }
+        }
+        by_owner.shrink_to_fit();
+        removed
+    }
+
+    /// Returns the number of stored approvals and how many of them have expired at `now`.
+    pub fn entry_counts(&self, now: u64) -> (u64, u64) {
+        self.by_owner
+            .values()
+            .flat_map(|operators| operators.iter())
+            .fold((0, 0), |(entries, expired), (_, expires_at)| {
+                (entries + 1, expired + u64::from(!is_live(expires_at, now)))
+            })
+    }
+
+    /// Iterates over `owner`'s live `(operator, expiry)` approvals in no particular order.
+    pub fn of_owner<'a>(&'a self, owner: &str, now: u64) -> impl Iterator<Item = (String, Option<u64>)> + 'a {
+        self.by_owner
+            .get(owner)
+            .into_iter()
+            .flat_map(|operators| operators.iter())
+            .filter(move |(_, expires_at)| is_live(*expires_at, now))
+            .map(|(operator, expires_at)| (operator.into_owned(), expires_at))
+    }
+
+    /// Iterates over every stored `(owner, operator, expiry)` approval, live or not, in no
+    /// particular order; filter with `is_live` for the approvals that count.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, String, Option<u64>)> + '_ {
+        self.by_owner.iter().flat_map(|(owner, operators)| {
+            operators
+                .iter()
+                .map(move |(operator, expires_at)| (owner.as_str(), operator.into_owned(), expires_at))
+        })
+    }
+}
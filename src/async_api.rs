@@ -0,0 +1,217 @@
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use js_sys::{Array, Function, Promise};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::{future_to_promise, JsFuture};
+
+use crate::pipeline::{Call, CallKind};
+use crate::{ContractError, ErrorKind, ERC1155};
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_name = setTimeout)]
+    fn set_timeout(handler: &Function, timeout: i32) -> JsValue;
+}
+
+#[wasm_bindgen(typescript_custom_section)]
+const TS_RECEIVER_HOOK: &'static str = r#"
+/**
+ * Called before tokens are credited to `to`. Returning (or resolving to) a
+ * falsy value rejects the transfer.
+ */
+export type ReceiverHook = (
+    operator: string,
+    from: string,
+    to: string,
+    tokenId: number,
+    amount: bigint,
+) => boolean | Promise<boolean>;
+"#;
+
+/// Promise-based facade over an `ERC1155` contract.
+///
+/// Every mutating method returns a `Promise`; batch transfers yield to the event
+/// loop between chunks so large batches don't block the main thread. A batch
+/// runs in a transaction of its own, so it cannot start while the host has one
+/// open.
+#[wasm_bindgen]
+pub struct AsyncERC1155 {
+    inner: Rc<RefCell<ERC1155>>,
+    busy: Rc<Cell<bool>>,
+}
+
+#[wasm_bindgen]
+impl AsyncERC1155 {
+    /// Initializes a new contract with the owner behind an async facade.
+    #[wasm_bindgen(constructor)]
+    pub fn new(owner: &str) -> crate::Result<AsyncERC1155> {
+        ERC1155::new(owner).map(Self::from_contract)
+    }
+
+    /// Wraps an existing contract. The contract object is consumed.
+    pub fn from_contract(contract: ERC1155) -> AsyncERC1155 {
+        AsyncERC1155 {
+            inner: Rc::new(RefCell::new(contract)),
+            busy: Rc::new(Cell::new(false)),
+        }
+    }
+
+    /// Returns the balance of tokens for a specific user and token ID.
+    pub fn balance_of(&self, owner: &str, token_id: u32) -> u64 {
+        self.inner.borrow().balance_of(owner, token_id)
+    }
+
+    /// Mints new tokens; resolves to a `TransferReceipt`.
+    pub fn mint_async(&self, caller: String, token_id: u32, amount: u64) -> Promise {
+        let inner = self.inner.clone();
+        let busy = self.busy.clone();
+        future_to_promise(async move {
+            let _busy = BusyLock::acquire(&busy)?;
+            let receipt = inner.borrow_mut().mint(&caller, token_id, amount)?;
+            Ok(receipt.into())
+        })
+    }
+
+    /// Transfers tokens; resolves to a `TransferReceipt`.
+    ///
+    /// If `receiver_hook` is given it is invoked (and awaited if it returns a
+    /// promise) before any balance changes; a falsy result rejects the transfer.
+    pub fn transfer_async(
+        &self,
+        caller: String,
+        to: String,
+        token_id: u32,
+        amount: u64,
+        receiver_hook: Option<Function>,
+    ) -> Promise {
+        let inner = self.inner.clone();
+        let busy = self.busy.clone();
+        future_to_promise(async move {
+            let _busy = BusyLock::acquire(&busy)?;
+            let call = precheck(&inner.borrow(), &caller, &to, &[token_id], &[amount])?;
+            if let Some(hook) = receiver_hook {
+                check_receiver(&hook, &call.caller, &call.accounts[0], token_id, amount).await?;
+            }
+            let receipt = inner.borrow_mut().transfer(&caller, &to, token_id, amount)?;
+            Ok(receipt.into())
+        })
+    }
+
+    /// Transfers several token IDs, applying `chunk_size` items per event-loop turn.
+    /// Resolves to an array of `TransferReceipt`.
+    ///
+    /// The whole batch is validated up front and applied in a transaction, while the
+    /// facade rejects other mutations, so it either applies fully or not at all: if a
+    /// later chunk fails, for instance because a token expired in the meantime, the
+    /// earlier chunks are rolled back. Fails if the contract has a transaction open.
+    pub fn transfer_batch_async(
+        &self,
+        caller: String,
+        to: String,
+        token_ids: Vec<u32>,
+        amounts: Vec<u64>,
+        chunk_size: usize,
+    ) -> Promise {
+        let inner = self.inner.clone();
+        let busy = self.busy.clone();
+        future_to_promise(async move {
+            let _busy = BusyLock::acquire(&busy)?;
+            precheck(&inner.borrow(), &caller, &to, &token_ids, &amounts)?;
+
+            inner.borrow_mut().begin_transaction()?;
+            let chunk_size = chunk_size.max(1);
+            let applied: Result<Array, JsValue> = async {
+                let receipts = Array::new();
+                for (ids, values) in token_ids.chunks(chunk_size).zip(amounts.chunks(chunk_size)) {
+                    yield_now().await?;
+                    let chunk = inner
+                        .borrow_mut()
+                        .transfer_batch(&caller, &to, ids.to_vec(), values.to_vec())?;
+                    for receipt in chunk {
+                        receipts.push(&receipt.into());
+                    }
+                }
+                Ok(receipts)
+            }
+            .await;
+            match applied {
+                Ok(receipts) => {
+                    inner.borrow_mut().commit()?;
+                    Ok(receipts.into())
+                }
+                Err(err) => {
+                    inner.borrow_mut().rollback()?;
+                    Err(err)
+                }
+            }
+        })
+    }
+
+    /// Approves another user to transfer tokens on behalf of the caller; resolves to a `Receipt`.
+    pub fn approve_async(&self, caller: String, approved: String, token_id: u32) -> Promise {
+        let inner = self.inner.clone();
+        let busy = self.busy.clone();
+        future_to_promise(async move {
+            let _busy = BusyLock::acquire(&busy)?;
+            let receipt = inner.borrow_mut().approve(&caller, &approved, token_id)?;
+            Ok(receipt.into())
+        })
+    }
+}
+
+/// Checks that the caller can send `amounts` of `token_ids` to `to`, with the accounts
+/// normalized and resolved as the call itself will have them.
+fn precheck(contract: &ERC1155, caller: &str, to: &str, token_ids: &[u32], amounts: &[u64]) -> crate::Result<Call> {
+    let call = contract.check_call(CallKind::User, caller, &[to])?;
+    contract.check_transfer_batch(&call.caller, &call.caller, &call.accounts[0], token_ids, amounts)?;
+    Ok(call)
+}
+
+/// Marks the facade busy for as long as it is held.
+struct BusyLock {
+    flag: Rc<Cell<bool>>,
+}
+
+impl BusyLock {
+    fn acquire(flag: &Rc<Cell<bool>>) -> Result<Self, JsValue> {
+        if flag.replace(true) {
+            return Err(ContractError::new(ErrorKind::Reentrancy, "Another async operation is in progress.").into());
+        }
+        Ok(Self { flag: flag.clone() })
+    }
+}
+
+impl Drop for BusyLock {
+    fn drop(&mut self) {
+        self.flag.set(false);
+    }
+}
+
+/// Resolves on the next macrotask, letting the host render and handle input.
+async fn yield_now() -> Result<(), JsValue> {
+    let promise = Promise::new(&mut |resolve, _reject| {
+        set_timeout(&resolve, 0);
+    });
+    JsFuture::from(promise).await.map(|_| ())
+}
+
+/// Runs a receiver hook and fails unless it returns or resolves to a truthy value.
+/// Transfers always move the caller's own tokens, so it is both operator and sender.
+async fn check_receiver(hook: &Function, caller: &str, to: &str, token_id: u32, amount: u64) -> Result<(), JsValue> {
+    let args = Array::of5(
+        &JsValue::from_str(caller),
+        &JsValue::from_str(caller),
+        &JsValue::from_str(to),
+        &JsValue::from(token_id),
+        &JsValue::from(amount),
+    );
+    let mut accepted = hook.apply(&JsValue::NULL, &args)?;
+    if accepted.is_instance_of::<Promise>() {
+        accepted = JsFuture::from(Promise::from(accepted)).await?;
+    }
+    if !accepted.is_truthy() {
+        return Err(ContractError::new(ErrorKind::TransferRejected, "Recipient rejected the transfer.").into());
+    }
+    Ok(())
+}
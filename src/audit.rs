@@ -0,0 +1,190 @@
+//! Append-only log of the mutating calls that changed contract state.
+//!
+//! The pipeline opens a pending operation in `ERC1155::enter` and records it
+//! once the method returns, if it returned `Ok`. Failed calls never reach the
+//! log, and any events they emitted before failing are discarded with them, so
+//! the event log only holds events of logged operations. Operations made through
+//! `call` also keep their request and the `receipt_hash` of their result, so
+//! `export_replay_log` can turn the log into the input of `replay`.
+
+use std::ops::Range;
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::guard::Guarded;
+use crate::{BalanceChange, EventRecord, Receipt, ERC1155};
+
+/// One successful mutating call.
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OperationRecord {
+    /// Position of the operation in the log, starting at zero.
+    #[wasm_bindgen(readonly)]
+    pub id: u64,
+    /// Name of the public method that was called.
+    #[wasm_bindgen(readonly)]
+    pub method: String,
+    #[wasm_bindgen(readonly)]
+    pub caller: String,
+    /// The other account arguments, normalized, in the order the method takes them.
+    /// Token IDs and amounts are carried by the operation's events and by `request`.
+    #[wasm_bindgen(readonly)]
+    pub accounts: Vec<String>,
+    /// Contract time when the call was made, in milliseconds since the Unix epoch.
+    #[wasm_bindgen(readonly)]
+    pub timestamp: u64,
+    /// Sequence number of the first event the call emitted.
+    #[wasm_bindgen(readonly)]
+    pub first_event_seq: u64,
+    #[wasm_bindgen(readonly)]
+    pub event_count: u64,
+    /// The `call` request `{method, params}` the operation was made with, as JSON;
+    /// `undefined` for direct method calls.
+    #[wasm_bindgen(readonly)]
+    #[serde(default)]
+    pub request: Option<String>,
+    /// `receipt_hash` of the result returned to `call`; `undefined` for direct method calls.
+    #[wasm_bindgen(readonly)]
+    #[serde(default)]
+    pub receipt_hash: Option<String>,
+}
+
+impl OperationRecord {
+    fn event_range(&self) -> Range<usize> {
+        self.first_event_seq as usize..(self.first_event_seq + self.event_count) as usize
+    }
+}
+
+/// Criteria for `ERC1155::get_operations`; unset fields match everything.
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OperationFilter {
+    /// Matches operations where the account is the caller, an argument, or a party to an event.
+    pub account: Option<String>,
+    /// Matches operations with an event about this token ID.
+    pub token_id: Option<u32>,
+    /// Earliest timestamp to include, in milliseconds.
+    pub since: Option<u64>,
+    /// Latest timestamp to include, in milliseconds.
+    pub until: Option<u64>,
+    /// First operation ID to consider, for paging.
+    pub from_id: Option<u64>,
+    /// Maximum number of operations to return.
+    pub limit: Option<u32>,
+}
+
+#[wasm_bindgen]
+impl OperationFilter {
+    /// Creates a filter that matches every operation.
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> OperationFilter {
+        OperationFilter::default()
+    }
+}
+
+/// An operation opened by the pipeline and not yet recorded.
+pub(crate) struct PendingOperation {
+    pub method: &'static str,
+    pub caller: String,
+    pub accounts: Vec<String>,
+    pub timestamp: u64,
+    pub first_event_seq: u64,
+    pub request: Option<String>,
+}
+
+impl ERC1155 {
+    /// Returns the ID the operation in progress will be logged under.
+    pub(crate) fn next_operation_id(&self) -> u64 {
+        self.operations.len() as u64
+    }
+
+    /// Records `pending`, which succeeded.
+    pub(crate) fn finish_operation(&mut self, pending: PendingOperation) {
+        let event_count = self.next_event_seq() - pending.first_event_seq;
+        let record = OperationRecord {
+            id: self.next_operation_id(),
+            method: pending.method.to_string(),
+            caller: pending.caller,
+            accounts: pending.accounts,
+            timestamp: pending.timestamp,
+            first_event_seq: pending.first_event_seq,
+            event_count,
+            request: pending.request,
+            receipt_hash: None,
+        };
+        self.operations.push(record);
+    }
+
+    fn operation_matches(&self, operation: &OperationRecord, filter: &OperationFilter) -> bool {
+        if filter.since.is_some_and(|since| operation.timestamp < since)
+            || filter.until.is_some_and(|until| operation.timestamp > until)
+        {
+            return false;
+        }
+        let mut events = self.events.range(operation.event_range());
+        if let Some(token_id) = filter.token_id {
+            if !events.clone().any(|event| event.token_id == Some(token_id)) {
+                return false;
+            }
+        }
+        if let Some(account) = &filter.account {
+            let account = self.resolve_account(account);
+            let account = account.as_ref();
+            let in_call = operation.caller == account || operation.accounts.iter().any(|arg| arg == account);
+            if !in_call && !events.any(|event| event_involves(event, account)) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+impl Guarded<'_> {
+    /// Builds the receipt of the operation in progress from the events it emitted so far.
+    pub(crate) fn receipt(&self, balances: Vec<BalanceChange>) -> Receipt {
+        let (method, first_event_seq) = self
+            .operation
+            .as_ref()
+            .map_or(("", self.next_event_seq()), |operation| (operation.method, operation.first_event_seq));
+        Receipt {
+            operation_id: self.next_operation_id(),
+            method: method.to_string(),
+            balances,
+            events: self.events.iter_from(first_event_seq as usize).cloned().collect(),
+            gas_used: self.gas.used(),
+        }
+    }
+}
+
+fn event_involves(event: &EventRecord, account: &str) -> bool {
+    event.operator == account || event.from.as_deref() == Some(account) || event.to.as_deref() == Some(account)
+}
+
+#[wasm_bindgen]
+impl ERC1155 {
+    /// Returns the logged operations matching `filter`, oldest first.
+    pub fn get_operations(&self, filter: &OperationFilter) -> Vec<OperationRecord> {
+        let start = filter.from_id.unwrap_or(0) as usize;
+        let limit = filter.limit.map_or(usize::MAX, |limit| limit as usize);
+        self.operations
+            .iter_from(start)
+            .filter(|operation| self.operation_matches(operation, filter))
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the events emitted by operation `id`.
+    pub fn operation_events(&self, id: u64) -> Vec<EventRecord> {
+        self.operations
+            .get(id as usize)
+            .map(|operation| self.events.range(operation.event_range()).cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Returns the total number of logged operations.
+    pub fn operation_count(&self) -> u64 {
+        self.operations.len() as u64
+    }
+}
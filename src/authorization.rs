@@ -0,0 +1,170 @@
+//! Mints authorized by an off-contract signer.
+//!
+//! The owner registers the Ed25519 public key of an authorizer, such as a game
+//! backend. The backend signs `mint_authorization_message` for a mint, and
+//! whoever holds the signature, typically the recipient's browser, submits it
+//! with `mint_with_authorization` before it expires. Each authorized message is
+//! honored once; to authorize the same mint twice, the backend signs it with a
+//! different expiry. Messages name the contract's `instance_id`, drawn at random
+//! when the contract is created, so a signature only mints on the contract it
+//! was made for, and one authorizer key can serve several contracts. Forks keep
+//! the ID of their original, and so do contracts rebuilt with `replay`.
+
+use std::collections::HashSet;
+
+use ring::rand::{SecureRandom, SystemRandom};
+use ring::signature::{UnparsedPublicKey, ED25519};
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::gas::GasCost;
+use crate::pipeline::CallKind;
+use crate::{ContractError, ErrorKind, EventKind, EventRecord, Receipt, Result, ERC1155};
+
+/// Prefixes every signed message so it cannot be mistaken for another signed payload.
+const MESSAGE_DOMAIN: &[u8] = b"erc1155-wasm/mint-authorization/v2";
+
+/// The authorizer key and the authorizations already used.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub(crate) struct Authorizations {
+    authorizer: Option<Vec<u8>>,
+    /// BLAKE3 hashes of the messages that were minted, with their expiry. Entries are
+    /// pruned once expired, since an expired message is rejected anyway.
+    #[serde(serialize_with = "crate::canonical::sorted")]
+    used: HashSet<([u8; 32], u64)>,
+}
+
+/// Draws the ID a new contract's authorization messages are bound to.
+pub(crate) fn new_instance_id() -> Result<[u8; 16]> {
+    let mut id = [0; 16];
+    SystemRandom::new()
+        .fill(&mut id)
+        .map_err(|_| ContractError::new(ErrorKind::InvalidInput, "The host provides no source of randomness."))?;
+    Ok(id)
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Decodes a hex string of exactly `len` bytes.
+pub(crate) fn decode_hex(hex: &str, len: usize, what: &str) -> Result<Vec<u8>> {
+    let hex = hex.trim();
+    let hex = hex.strip_prefix("0x").unwrap_or(hex);
+    let invalid = || {
+        ContractError::new(ErrorKind::InvalidInput, format!("{} must be {} hex-encoded bytes.", what, len))
+    };
+    if hex.len() != 2 * len {
+        return Err(invalid());
+    }
+    (0..len)
+        .map(|index| u8::from_str_radix(hex.get(2 * index..2 * index + 2)?, 16).ok())
+        .collect::<Option<Vec<u8>>>()
+        .ok_or_else(invalid)
+}
+
+fn authorization_message(instance_id: &[u8; 16], to: &str, token_id: u32, amount: u64, expiry: u64) -> Vec<u8> {
+    let mut message = MESSAGE_DOMAIN.to_vec();
+    message.extend_from_slice(instance_id);
+    message.extend_from_slice(&(to.len() as u64).to_le_bytes());
+    message.extend_from_slice(to.as_bytes());
+    message.extend_from_slice(&token_id.to_le_bytes());
+    message.extend_from_slice(&amount.to_le_bytes());
+    message.extend_from_slice(&expiry.to_le_bytes());
+    message
+}
+
+#[wasm_bindgen]
+impl ERC1155 {
+    /// Sets the hex-encoded Ed25519 public key whose signatures authorize mints, or removes
+    /// it when `undefined` (only the owner can set it).
+    pub fn set_mint_authorizer(&mut self, caller: &str, public_key: Option<String>) -> Result<Receipt> {
+        self.enter("set_mint_authorizer", CallKind::Admin, caller, &[], |this, call| {
+            if !this.access_control.is_owner(&call.caller) {
+                return Err(ContractError::new(ErrorKind::Unauthorized, "Only the owner can set the mint authorizer."));
+            }
+            let key = public_key
+                .as_deref()
+                .map(|key| decode_hex(key, 32, "Public key"))
+                .transpose()?;
+
+            this.authorizations.authorizer = key;
+            let mut event = EventRecord::new(this.next_event_seq(), EventKind::MintAuthorizerUpdated, &call.caller);
+            event.value = this.mint_authorizer();
+            this.emit(event);
+            Ok(this.receipt(Vec::new()))
+        })
+    }
+
+    /// Returns the hex-encoded public key of the mint authorizer, if any.
+    pub fn mint_authorizer(&self) -> Option<String> {
+        let key = self.authorizations.authorizer.as_ref()?;
+        Some(encode_hex(key))
+    }
+
+    /// Returns the hex-encoded ID that binds authorization messages to this contract.
+    pub fn instance_id(&self) -> String {
+        encode_hex(&self.instance_id)
+    }
+
+    /// Returns the bytes the authorizer signs to let `to` receive `amount` of `token_id`
+    /// on this contract until `expiry`, a contract time in milliseconds.
+    pub fn mint_authorization_message(&self, to: &str, token_id: u32, amount: u64, expiry: u64) -> Vec<u8> {
+        authorization_message(&self.instance_id, &self.resolve_account(to), token_id, amount, expiry)
+    }
+
+    /// Mints `amount` of an existing `token_id` to `to` on the strength of the authorizer's
+    /// hex-encoded Ed25519 `signature` of the matching message. Anyone may submit it, once,
+    /// before `expiry`; the token's maximum supply still applies.
+    pub fn mint_with_authorization(
+        &mut self,
+        caller: &str,
+        to: &str,
+        token_id: u32,
+        amount: u64,
+        expiry: u64,
+        signature: &str,
+    ) -> Result<Receipt> {
+        self.enter("mint_with_authorization", CallKind::User, caller, &[to], |this, call| {
+            this.check_configurable()?;
+            let to = call.accounts[0].clone();
+            let authorizer = this
+                .authorizations
+                .authorizer
+                .as_ref()
+                .ok_or_else(|| ContractError::new(ErrorKind::Unauthorized, "No mint authorizer is set."))?;
+            let signature = decode_hex(signature, 64, "Signature")?;
+            let message = authorization_message(&this.instance_id, &to, token_id, amount, expiry);
+            if UnparsedPublicKey::new(&ED25519, authorizer)
+                .verify(&message, &signature)
+                .is_err()
+            {
+                return Err(ContractError::new(ErrorKind::Unauthorized, "Invalid mint authorization signature."));
+            }
+            let now = this.clock.now();
+            if now >= expiry {
+                return Err(ContractError::new(ErrorKind::InvalidInput, "Mint authorization has expired."));
+            }
+            let digest = *blake3::hash(&message).as_bytes();
+            if this.authorizations.used.contains(&(digest, expiry)) {
+                return Err(ContractError::new(ErrorKind::InvalidInput, "Mint authorization was already used."));
+            }
+            if this.tokens.get(token_id).is_none() {
+                return Err(ContractError::new(ErrorKind::InvalidInput, "Token does not exist."));
+            }
+            this.check_mintable(token_id, amount)?;
+            this.check_max_supply(token_id, amount)?;
+            this.charge_gas(GasCost {
+                reads: 3,
+                writes: 2,
+                events: 1,
+                ..GasCost::default()
+            })?;
+
+            let changes = this.move_tokens(&call.caller, None, Some(&to), token_id, amount)?;
+            this.authorizations.used.retain(|&(_, used_until)| used_until > now);
+            this.authorizations.used.insert((digest, expiry));
+            Ok(this.receipt(changes))
+        })
+    }
+}
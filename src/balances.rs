@@ -0,0 +1,335 @@
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::escrow::RESERVED_PREFIX;
+use crate::storage::{Backend, Storage, Tables};
+
+/// Token-major balance table: `TokenID -> (User -> Balance)`.
+///
+/// The holders of each token live in a storage [`Backend`]. Lookups borrow the
+/// owner as `&str`, so reads from the in-memory backend never allocate. The total of
+/// every token is kept alongside, so supply queries do not scan holders.
+///
+/// Snapshots are recorded lazily: taking one only bumps a counter, and the first
+/// write to a balance or total after it saves the value it replaces.
+///
+/// Holder counts are kept up to date on every write as well. Reserved accounts,
+/// such as the escrow, are not holders.
+///
+/// The per-token tables are shared between forks until written; see `fork`.
+#[derive(Clone, Default)]
+pub(crate) struct Balances {
+    by_token: HashMap<u32, Arc<Backend<u64>>>,
+    /// Sum of all balances per token. Wider than a balance: uncapped tokens may be
+    /// minted past `u64::MAX` in total as long as no single balance overflows.
+    totals: HashMap<u32, u128>,
+    /// ID of the latest snapshot; zero before the first one.
+    snapshot: u64,
+    /// `TokenID -> (User -> [(snapshot_id, balance at that snapshot)])`, ascending.
+    history: HashMap<u32, Arc<TokenHistory>>,
+    /// `token_id -> [(snapshot_id, total at that snapshot)]`, ascending.
+    total_history: HashMap<u32, Vec<(u64, u128)>>,
+    /// Number of accounts with a non-zero balance, per token.
+    holder_counts: HashMap<u32, u64>,
+    /// Account -> number of tokens it holds a non-zero balance of.
+    held_tokens: Arc<HashMap<String, u32>>,
+    /// Where new per-token tables are opened.
+    tables: Tables,
+}
+
+/// `User -> [(snapshot_id, balance at that snapshot)]` of one token.
+type TokenHistory = HashMap<String, Vec<(u64, u64)>>;
+
+/// The snapshot counter and saved snapshot values, in the canonical form of their state
+/// section.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct SnapshotHistory {
+    last_snapshot_id: u64,
+    /// `TokenID -> (User -> [(snapshot_id, balance)])`.
+    balances: BTreeMap<u32, BTreeMap<String, Vec<(u64, u64)>>>,
+    /// `TokenID -> [(snapshot_id, total)]`.
+    totals: BTreeMap<u32, Vec<(u64, Total)>>,
+}
+
+/// A token total, written in decimal since it may not fit in a JSON number.
+#[derive(Clone, Copy)]
+struct Total(u128);
+
+impl Serialize for Total {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Total {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let total = String::deserialize(deserializer)?;
+        total.parse().map(Total).map_err(D::Error::custom)
+    }
+}
+
+/// Saves `previous` as the value at `snapshot` unless a later write already did.
+fn record<T>(entries: &mut Vec<(u64, T)>, snapshot: u64, previous: T) {
+    if entries.last().is_none_or(|(id, _)| *id < snapshot) {
+        entries.push((snapshot, previous));
+    }
+}
+
+/// Returns the value saved for `snapshot`, or `None` if it has not changed since.
+fn recorded<T: Copy>(entries: Option<&Vec<(u64, T)>>, snapshot: u64) -> Option<T> {
+    let entries = entries?;
+    let index = entries.partition_point(|(id, _)| *id < snapshot);
+    entries.get(index).map(|(_, value)| *value)
+}
+
+impl Balances {
+    pub fn new(tables: Tables) -> Self {
+        Self {
+            tables,
+            ..Self::default()
+        }
+    }
+
+    /// Returns the balance of `owner` for `token_id`, or zero.
+    pub fn get(&self, owner: &str, token_id: u32) -> u64 {
+        self.by_token
+            .get(&token_id)
+            .and_then(|holders| holders.get(owner))
+            .unwrap_or(0)
+    }
+
+    /// Overwrites the balance of `owner` for `token_id`. A zero balance removes the entry,
+    /// and the token's table with its last entry.
+    pub fn set(&mut self, owner: &str, token_id: u32, balance: u64) {
+        self.write(owner, token_id, balance, true);
+    }
+
+    /// Overwrites a balance like `set`, without saving the value it replaces as a snapshot
+    /// value; for installing balances copied from another contract with its history.
+    pub fn restore(&mut self, owner: &str, token_id: u32, balance: u64) {
+        self.write(owner, token_id, balance, false);
+    }
+
+    fn write(&mut self, owner: &str, token_id: u32, balance: u64, track: bool) {
+        let previous = self.get(owner, token_id);
+        if previous == balance {
+            return;
+        }
+        let tables = &self.tables;
+        let holders = Arc::make_mut(self.by_token.entry(token_id).or_insert_with(|| Arc::new(tables.open())));
+        if balance > 0 {
+            holders.set(owner, balance);
+        } else {
+            holders.remove(owner);
+            if holders.is_empty() {
+                self.by_token.remove(&token_id);
+            }
+        }
+        if (previous == 0) != (balance == 0) && !owner.starts_with(RESERVED_PREFIX) {
+            self.count_holder(owner, token_id, balance > 0);
+        }
+        let previous_total = self.total(token_id);
+        let total = previous_total + u128::from(balance) - u128::from(previous);
+        if total > 0 {
+            self.totals.insert(token_id, total);
+        } else {
+            self.totals.remove(&token_id);
+        }
+        if track && self.snapshot > 0 {
+            let holders = Arc::make_mut(self.history.entry(token_id).or_default());
+            let history = match holders.get_mut(owner) {
+                Some(history) => history,
+                None => holders.entry(owner.to_string()).or_default(),
+            };
+            record(history, self.snapshot, previous);
+            record(self.total_history.entry(token_id).or_default(), self.snapshot, previous_total);
+        }
+    }
+
+    /// Updates the holder counts for `owner` starting or ceasing to hold `token_id`.
+    fn count_holder(&mut self, owner: &str, token_id: u32, holds: bool) {
+        let holders = self.holder_counts.entry(token_id).or_insert(0);
+        let held_tokens = Arc::make_mut(&mut self.held_tokens);
+        let held = match held_tokens.get_mut(owner) {
+            Some(held) => held,
+            None => held_tokens.entry(owner.to_string()).or_insert(0),
+        };
+        if holds {
+            *holders += 1;
+            *held += 1;
+        } else {
+            *held -= 1;
+            if *held == 0 {
+                held_tokens.remove(owner);
+            }
+            *holders -= 1;
+            if *holders == 0 {
+                self.holder_counts.remove(&token_id);
+            }
+        }
+    }
+
+    /// Returns the number of accounts holding a non-zero balance of `token_id`.
+    pub fn holder_count(&self, token_id: u32) -> u64 {
+        self.holder_counts.get(&token_id).copied().unwrap_or(0)
+    }
+
+    /// Returns the number of accounts holding a non-zero balance of any token.
+    pub fn unique_holders(&self) -> u64 {
+        self.held_tokens.len() as u64
+    }
+
+    /// Iterates over the `(owner, balance)` entries stored for `token_id`, in no particular order.
+    pub fn holders(&self, token_id: u32) -> impl Iterator<Item = (Cow<'_, str>, u64)> + '_ {
+        self.by_token
+            .get(&token_id)
+            .into_iter()
+            .flat_map(|holders| holders.iter())
+    }
+
+    /// Takes a snapshot of every balance and total and returns its ID, starting at 1.
+    pub fn take_snapshot(&mut self) -> u64 {
+        self.snapshot += 1;
+        self.snapshot
+    }
+
+    /// Returns the ID of the latest snapshot; zero if none was taken.
+    pub fn last_snapshot(&self) -> u64 {
+        self.snapshot
+    }
+
+    /// Returns the snapshot counter and every saved snapshot value.
+    pub fn history(&self) -> SnapshotHistory {
+        SnapshotHistory {
+            last_snapshot_id: self.snapshot,
+            balances: self
+                .history
+                .iter()
+                .map(|(token_id, holders)| {
+                    let holders = holders.iter().map(|(owner, entries)| (owner.clone(), entries.clone()));
+                    (*token_id, holders.collect())
+                })
+                .collect(),
+            totals: self
+                .total_history
+                .iter()
+                .map(|(token_id, entries)| {
+                    let entries = entries.iter().map(|(snapshot, total)| (*snapshot, Total(*total)));
+                    (*token_id, entries.collect())
+                })
+                .collect(),
+        }
+    }
+
+    /// Replaces the snapshot counter and every saved snapshot value with `history`.
+    pub fn restore_history(&mut self, history: SnapshotHistory) {
+        self.snapshot = history.last_snapshot_id;
+        self.history = history
+            .balances
+            .into_iter()
+            .map(|(token_id, holders)| (token_id, Arc::new(holders.into_iter().collect())))
+            .collect();
+        self.total_history = history
+            .totals
+            .into_iter()
+            .map(|(token_id, entries)| {
+                let entries = entries.into_iter().map(|(snapshot, Total(total))| (snapshot, total));
+                (token_id, entries.collect())
+            })
+            .collect();
+    }
+
+    /// Returns the balance of `owner` for `token_id` when `snapshot` was taken.
+    pub fn get_at(&self, owner: &str, token_id: u32, snapshot: u64) -> u64 {
+        let history = self.history.get(&token_id).and_then(|holders| holders.get(owner));
+        recorded(history, snapshot).unwrap_or_else(|| self.get(owner, token_id))
+    }
+
+    /// Returns the total of `token_id` when `snapshot` was taken.
+    pub fn total_at(&self, token_id: u32, snapshot: u64) -> u128 {
+        recorded(self.total_history.get(&token_id), snapshot).unwrap_or_else(|| self.total(token_id))
+    }
+
+    /// Iterates over every `(owner, balance)` of `token_id` that was non-zero when
+    /// `snapshot` was taken, in no particular order.
+    pub fn holders_at(&self, token_id: u32, snapshot: u64) -> impl Iterator<Item = (String, u64)> + '_ {
+        let current = self.by_token.get(&token_id);
+        // Holders whose entry was removed since the snapshot still have their value in `history`.
+        let gone = self
+            .history
+            .get(&token_id)
+            .into_iter()
+            .flat_map(|holders| holders.keys())
+            .filter(move |owner| current.is_none_or(|holders| holders.get(owner).is_none()))
+            .cloned();
+        current
+            .into_iter()
+            .flat_map(|holders| holders.iter().map(|(owner, _)| owner.into_owned()))
+            .chain(gone)
+            .map(move |owner| {
+                let balance = self.get_at(&owner, token_id, snapshot);
+                (owner, balance)
+            })
+            .filter(|(_, balance)| *balance > 0)
+    }
+
+    /// Iterates over the owners of `token_id` whose balance was written since the first
+    /// snapshot; every other balance is the same at all snapshots.
+    pub fn owners_with_history(&self, token_id: u32) -> impl Iterator<Item = &str> + '_ {
+        self.history
+            .get(&token_id)
+            .into_iter()
+            .flat_map(|holders| holders.keys().map(String::as_str))
+    }
+
+    /// Returns the sum of every balance of `token_id`.
+    pub fn total(&self, token_id: u32) -> u128 {
+        self.totals.get(&token_id).copied().unwrap_or(0)
+    }
+
+    /// Iterates over the `(token_id, balance)` entries stored for `owner`.
+    pub fn of_owner<'a>(&'a self, owner: &'a str) -> impl Iterator<Item = (u32, u64)> + 'a {
+        self.by_token
+            .iter()
+            .filter_map(move |(token_id, holders)| holders.get(owner).map(|balance| (*token_id, balance)))
+    }
+
+    /// Releases the spare capacity that removed tokens leave in the token maps.
+    pub fn shrink_to_fit(&mut self) {
+        self.by_token.shrink_to_fit();
+        self.totals.shrink_to_fit();
+        self.holder_counts.shrink_to_fit();
+    }
+
+    /// Returns the number of stored balance entries.
+    pub fn entry_count(&self) -> u64 {
+        self.by_token.values().map(|holders| holders.len() as u64).sum()
+    }
+
+    /// Returns the number of per-token tables and snapshot history entries.
+    pub fn table_counts(&self) -> (u64, u64) {
+        let history = self
+            .history
+            .values()
+            .flat_map(|holders| holders.values())
+            .map(|entries| entries.len() as u64)
+            .sum::<u64>()
+            + self.total_history.values().map(|entries| entries.len() as u64).sum::<u64>();
+        (self.by_token.len() as u64, history)
+    }
+
+    /// Iterates over every stored `(owner, token_id, balance)` entry in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (String, u32, u64)> + '_ {
+        self.by_token
+            .iter()
+            .flat_map(|(token_id, holders)| {
+                holders
+                    .iter()
+                    .map(move |(owner, balance)| (owner.into_owned(), *token_id, balance))
+            })
+    }
+}
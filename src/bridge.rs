@@ -0,0 +1,224 @@
+//! Lock-and-mint bridge between contract instances.
+//!
+//! `bridge_out` locks tokens in escrow and records a transfer for the relayer to
+//! carry to another instance. There, a bridge operator replays it with
+//! `bridge_in`, which first releases tokens this instance locked earlier and
+//! mints the rest, so the supply across instances stays constant. Each proof ID
+//! is accepted once. Shares and wrapped tokens cannot be bridged, since their
+//! supply is tied to state of this instance.
+
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::escrow::ESCROW_ACCOUNT;
+use crate::gas::GasCost;
+use crate::pipeline::CallKind;
+use crate::{ContractError, ErrorKind, EventKind, EventRecord, Receipt, Result, ERC1155};
+
+/// Bridge operators, locked amounts and processed proofs.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub(crate) struct Bridge {
+    #[serde(serialize_with = "crate::canonical::sorted")]
+    operators: HashSet<String>,
+    /// Token ID -> amount locked in escrow by `bridge_out` and not released yet.
+    locked: HashMap<u32, u64>,
+    /// Outbound transfers, indexed by ID minus one.
+    transfers: Vec<BridgeTransfer>,
+    #[serde(serialize_with = "crate::canonical::sorted")]
+    proofs: HashSet<String>,
+}
+
+/// An outbound transfer recorded by `bridge_out`.
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BridgeTransfer {
+    #[wasm_bindgen(readonly)]
+    pub id: u64,
+    #[wasm_bindgen(readonly)]
+    pub from: String,
+    #[wasm_bindgen(readonly)]
+    pub token_id: u32,
+    #[wasm_bindgen(readonly)]
+    pub amount: u64,
+    /// Where the relayer should deliver the tokens, as given by the sender.
+    #[wasm_bindgen(readonly)]
+    pub destination: String,
+    /// Contract time of the transfer, in milliseconds since the Unix epoch.
+    #[wasm_bindgen(readonly)]
+    pub timestamp: u64,
+}
+
+impl ERC1155 {
+    fn check_bridgeable(&self, token_id: u32, amount: u64) -> Result<()> {
+        if amount == 0 {
+            return Err(ContractError::new(ErrorKind::InvalidInput, "Bridge amount must be positive."));
+        }
+        if self.fractions.is_share(token_id) || self.wrapping.is_wrapped(token_id) {
+            return Err(ContractError::new(
+                ErrorKind::InvalidInput,
+                "Shares and wrapped tokens cannot be bridged.",
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[wasm_bindgen]
+impl ERC1155 {
+    /// Grants or revokes the bridge role, which may call `bridge_in` (owner or admins only).
+    pub fn set_bridge_operator(&mut self, caller: &str, account: &str, enabled: bool) -> Result<Receipt> {
+        self.enter("set_bridge_operator", CallKind::Admin, caller, &[account], |this, call| {
+            if !this.access_control.is_owner(&call.caller) && !this.access_control.is_admin(&call.caller) {
+                return Err(ContractError::new(
+                    ErrorKind::Unauthorized,
+                    "Caller is not authorized to manage the bridge.",
+                ));
+            }
+
+            let account = &call.accounts[0];
+            if enabled {
+                this.bridge.operators.insert(account.clone());
+            } else {
+                this.bridge.operators.remove(account);
+            }
+            let mut event = EventRecord::new(this.next_event_seq(), EventKind::BridgeOperatorUpdated, &call.caller);
+            event.to = Some(account.clone());
+            event.approved = Some(enabled);
+            this.emit(event);
+            Ok(this.receipt(Vec::new()))
+        })
+    }
+
+    /// Returns whether `account` holds the bridge role.
+    pub fn is_bridge_operator(&self, account: &str) -> bool {
+        self.bridge.operators.contains(self.resolve_account(account).as_ref())
+    }
+
+    /// Locks the caller's tokens for delivery to another instance and returns the ID of
+    /// the recorded transfer.
+    /// # Parameters
+    /// - `destination`: The instance and recipient to deliver to, in whatever form the
+    ///   relayer understands.
+    pub fn bridge_out(&mut self, caller: &str, token_id: u32, amount: u64, destination: &str) -> Result<u64> {
+        self.enter("bridge_out", CallKind::User, caller, &[], |this, call| {
+            this.check_bridgeable(token_id, amount)?;
+            let destination = destination.trim();
+            if destination.is_empty() {
+                return Err(ContractError::new(ErrorKind::InvalidInput, "Destination must not be empty."));
+            }
+            this.charge_gas(GasCost {
+                reads: 2,
+                writes: 3,
+                events: 2,
+                ..GasCost::default()
+            })?;
+
+            this.move_tokens(&call.caller, Some(&call.caller), Some(ESCROW_ACCOUNT), token_id, amount)?;
+            // Bounded by the escrow balance, which `move_tokens` just checked.
+            *this.bridge.locked.entry(token_id).or_insert(0) += amount;
+            let transfer_id = this.bridge.transfers.len() as u64 + 1;
+            let timestamp = this.clock.now();
+            this.bridge.transfers.push(BridgeTransfer {
+                id: transfer_id,
+                from: call.caller.clone(),
+                token_id,
+                amount,
+                destination: destination.to_string(),
+                timestamp,
+            });
+            let mut event = EventRecord::new(this.next_event_seq(), EventKind::BridgedOut, &call.caller);
+            event.from = Some(call.caller.clone());
+            event.token_id = Some(token_id);
+            event.amount = Some(amount);
+            event.value = Some(transfer_id.to_string());
+            this.emit(event);
+            Ok(transfer_id)
+        })
+    }
+
+    /// Credits tokens bridged from another instance (bridge operators only). Tokens locked
+    /// here by earlier outbound transfers are released first; the rest is minted.
+    /// # Parameters
+    /// - `proof_id`: Identifies the outbound transfer on the source instance; each proof is
+    ///   accepted at most once.
+    pub fn bridge_in(
+        &mut self,
+        caller: &str,
+        proof_id: &str,
+        to: &str,
+        token_id: u32,
+        amount: u64,
+    ) -> Result<Receipt> {
+        self.enter("bridge_in", CallKind::User, caller, &[to], |this, call| {
+            this.check_configurable()?;
+            if !this.bridge.operators.contains(&call.caller) {
+                return Err(ContractError::new(ErrorKind::Unauthorized, "Caller is not a bridge operator."));
+            }
+            let proof_id = proof_id.trim();
+            if proof_id.is_empty() || this.bridge.proofs.contains(proof_id) {
+                return Err(ContractError::new(
+                    ErrorKind::InvalidInput,
+                    "Proof ID is empty or was already processed.",
+                ));
+            }
+            this.check_bridgeable(token_id, amount)?;
+            let locked = this.bridge.locked.get(&token_id).copied().unwrap_or(0);
+            let released = locked.min(amount);
+            let minted = amount - released;
+            if minted > 0 {
+                this.check_split_id_mint(token_id, minted)?;
+            }
+            let to = call.accounts[0].clone();
+            if this.balances.get(&to, token_id).checked_add(amount).is_none() {
+                return Err(ContractError::new(ErrorKind::Overflow, "Recipient balance overflow."));
+            }
+            this.charge_gas(GasCost {
+                reads: 3,
+                writes: 4,
+                events: 3,
+                ..GasCost::default()
+            })?;
+
+            let mut changes = Vec::new();
+            if released > 0 {
+                changes.extend(this.move_tokens(&call.caller, Some(ESCROW_ACCOUNT), Some(&to), token_id, released)?);
+                this.bridge.locked.insert(token_id, locked - released);
+            }
+            if minted > 0 {
+                this.tokens.create(token_id, ESCROW_ACCOUNT);
+                changes.extend(this.move_tokens(&call.caller, None, Some(&to), token_id, minted)?);
+            }
+            this.bridge.proofs.insert(proof_id.to_string());
+            let mut event = EventRecord::new(this.next_event_seq(), EventKind::BridgedIn, &call.caller);
+            event.to = Some(to);
+            event.token_id = Some(token_id);
+            event.amount = Some(amount);
+            event.value = Some(proof_id.to_string());
+            this.emit(event);
+            Ok(this.receipt(changes))
+        })
+    }
+
+    /// Returns an outbound transfer; `undefined` if it does not exist.
+    pub fn bridge_transfer(&self, transfer_id: u64) -> Option<BridgeTransfer> {
+        let index = transfer_id.checked_sub(1)?;
+        self.bridge.transfers.get(index as usize).cloned()
+    }
+
+    /// Returns the number of outbound transfers recorded so far.
+    pub fn bridge_transfer_count(&self) -> u64 {
+        self.bridge.transfers.len() as u64
+    }
+
+    /// Returns the amount of `token_id` locked by outbound transfers.
+    pub fn bridge_locked(&self, token_id: u32) -> u64 {
+        self.bridge.locked.get(&token_id).copied().unwrap_or(0)
+    }
+
+    /// Returns whether `proof_id` was already credited by `bridge_in`.
+    pub fn is_bridge_proof_processed(&self, proof_id: &str) -> bool {
+        self.bridge.proofs.contains(proof_id.trim())
+    }
+}
@@ -0,0 +1,151 @@
+//! Typed-array bulk endpoints for high-throughput hosts.
+//!
+//! These methods take and return flat numeric slices, which wasm-bindgen
+//! passes as `Uint32Array` / `BigUint64Array` copies instead of converting
+//! every element to a string or a boxed `JsValue`. Accounts are named once per
+//! call in an account table and referenced by index from the packed records.
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+use wasm_bindgen::prelude::*;
+
+use crate::gas::GasCost;
+use crate::pipeline::CallKind;
+use crate::{ContractError, ErrorKind, Result, ERC1155};
+
+/// Number of `u64` words in one record of `apply_transfers_bulk`:
+/// `[from_index, to_index, token_id, amount]`.
+const TRANSFER_WORDS: usize = 4;
+
+/// One decoded record of a packed transfer buffer.
+struct BulkTransfer {
+    from: usize,
+    to: usize,
+    token_id: u32,
+    amount: u64,
+}
+
+fn decode_transfers(accounts: usize, packed: &[u64]) -> Result<Vec<BulkTransfer>> {
+    if !packed.len().is_multiple_of(TRANSFER_WORDS) {
+        return Err(ContractError::new(
+            ErrorKind::InvalidInput,
+            format!("Packed transfers must hold {} words per transfer.", TRANSFER_WORDS),
+        ));
+    }
+    let index = |word: u64| {
+        usize::try_from(word)
+            .ok()
+            .filter(|&index| index < accounts)
+            .ok_or_else(|| ContractError::new(ErrorKind::InvalidInput, "Account index out of range."))
+    };
+    packed
+        .chunks_exact(TRANSFER_WORDS)
+        .map(|record| {
+            let token_id = u32::try_from(record[2])
+                .map_err(|_| ContractError::new(ErrorKind::InvalidInput, "Token ID out of range."))?;
+            Ok(BulkTransfer {
+                from: index(record[0])?,
+                to: index(record[1])?,
+                token_id,
+                amount: record[3],
+            })
+        })
+        .collect()
+}
+
+impl ERC1155 {
+    /// Verifies that `transfers` would all succeed when applied in order, without applying
+    /// any of them.
+    fn check_transfers_bulk(&self, caller: &str, accounts: &[String], transfers: &[BulkTransfer]) -> Result<()> {
+        // Balances as they will be after the transfers checked so far.
+        let mut pending: HashMap<(usize, u32), u64> = HashMap::new();
+        // Amounts moved out of each sender's tokens by the transfers checked so far.
+        let mut spent: HashMap<(usize, u32), u64> = HashMap::new();
+        for transfer in transfers {
+            let from = &accounts[transfer.from];
+            let total = spent.entry((transfer.from, transfer.token_id)).or_insert(0);
+            *total = total.saturating_add(transfer.amount);
+            self.check_spend(caller, from, transfer.token_id, *total)?;
+            if transfer.amount == 0 {
+                return Err(ContractError::new(ErrorKind::InvalidInput, "Transfer amount must be positive."));
+            }
+            self.check_not_expired(transfer.token_id)?;
+
+            let token_id = transfer.token_id;
+            let from_key = (transfer.from, token_id);
+            let from_balance = *pending
+                .entry(from_key)
+                .or_insert_with(|| self.balances.get(from, token_id));
+            let from_after = from_balance
+                .checked_sub(transfer.amount)
+                .ok_or_else(|| ContractError::new(ErrorKind::InsufficientBalance, "Insufficient balance."))?;
+            pending.insert(from_key, from_after);
+            let to_key = (transfer.to, token_id);
+            let to_balance = *pending
+                .entry(to_key)
+                .or_insert_with(|| self.balances.get(&accounts[transfer.to], token_id));
+            let to_after = to_balance
+                .checked_add(transfer.amount)
+                .ok_or_else(|| ContractError::new(ErrorKind::Overflow, "Recipient balance overflow."))?;
+            pending.insert(to_key, to_after);
+        }
+        Ok(())
+    }
+}
+
+#[wasm_bindgen]
+impl ERC1155 {
+    /// Returns the balances `owner` holds of each of `token_ids`, in the same order, as a
+    /// `BigUint64Array`. Expired tokens read as zero, as in `balance_of`.
+    /// # Parameters
+    /// - `token_ids`: A `Uint32Array` of token IDs; subject to the maximum batch size.
+    pub fn balances_bulk(&self, owner: &str, token_ids: &[u32]) -> Result<Vec<u64>> {
+        self.check_batch_size(token_ids.len())?;
+        let owner = self.resolve_account(owner);
+        Ok(token_ids
+            .iter()
+            .map(|&token_id| {
+                if self.is_token_expired(token_id) {
+                    0
+                } else {
+                    self.balances.get(&owner, token_id)
+                }
+            })
+            .collect())
+    }
+
+    /// Applies many transfers in one all-or-nothing call and returns how many were applied.
+    /// Each transfer follows the rules of `transfer_from`, spending scoped allowances alike.
+    /// Transfers apply in order, so a transfer may spend tokens an earlier one delivered.
+    /// Every transfer emits its `TransferSingle` event, but no receipt is built.
+    /// # Parameters
+    /// - `accounts`: The account table the records refer to; subject to the maximum batch size.
+    /// - `packed`: A `BigUint64Array` of `[from_index, to_index, token_id, amount]` records,
+    ///   where the indexes point into `accounts`; subject to the maximum batch size.
+    pub fn apply_transfers_bulk(&mut self, caller: &str, accounts: Vec<String>, packed: &[u64]) -> Result<u32> {
+        let names: Vec<&str> = accounts.iter().map(String::as_str).collect();
+        self.enter("apply_transfers_bulk", CallKind::User, caller, &names, |this, call| {
+            let transfers = decode_transfers(call.accounts.len(), packed)?;
+            if transfers.is_empty() {
+                return Err(ContractError::new(ErrorKind::InvalidInput, "Transfers must not be empty."));
+            }
+            this.check_batch_size(transfers.len())?;
+            let items = transfers.len() as u64;
+            this.charge_gas(GasCost {
+                reads: 1 + 2 * items,
+                writes: 2 * items,
+                events: items,
+                iterations: items,
+            })?;
+            this.check_transfers_bulk(&call.caller, &call.accounts, &transfers)?;
+
+            for transfer in &transfers {
+                let (from, to) = (&call.accounts[transfer.from], &call.accounts[transfer.to]);
+                this.move_tokens(&call.caller, Some(from), Some(to), transfer.token_id, transfer.amount)?;
+                this.spend(&call.caller, from, transfer.token_id, transfer.amount);
+            }
+            Ok(transfers.len() as u32)
+        })
+    }
+}
@@ -0,0 +1,204 @@
+//! Canonical form of the contract state, shared by `state_hash` and `diff`.
+//!
+//! Balances, approvals, the owner and the admins are compared entry by entry.
+//! Everything else a call can change is grouped into sections, one per state
+//! module, each serialized to a JSON value whose maps and sets are sorted, so
+//! equal state encodes equally whatever order it was written in. The snapshot
+//! counter and saved snapshot values form the `snapshots` section, since voting,
+//! reward distributions and snapshot queries read them. The event and operation
+//! logs, error statistics, the clock and the outbox cursor are bookkeeping of
+//! the host rather than state; none of them is part of the canonical form.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+
+use crate::allowances::Allowances;
+use crate::analytics::Analytics;
+use crate::authorization::Authorizations;
+use crate::balances::SnapshotHistory;
+use crate::bridge::Bridge;
+use crate::consumables::Consumables;
+use crate::delegation::Delegations;
+use crate::drops::Drops;
+use crate::fractions::Fractions;
+use crate::gas::GasMeter;
+use crate::governance::Governance;
+use crate::guardians::Guardians;
+use crate::metadata::Metadata;
+use crate::names::Names;
+use crate::pipeline::Policy;
+use crate::rewards::Rewards;
+use crate::search::AttributeIndex;
+use crate::storage::{Backend, Storage};
+use crate::tokens::Tokens;
+use crate::wrapping::Wrapping;
+use crate::{ContractError, ErrorKind, Result, ERC1155};
+
+/// `serialize_with` for hash sets: writes the elements in ascending order.
+pub(crate) fn sorted<T, S>(set: &HashSet<T>, serializer: S) -> std::result::Result<S::Ok, S::Error>
+where
+    T: Ord + Serialize,
+    S: Serializer,
+{
+    let mut elements: Vec<&T> = set.iter().collect();
+    elements.sort_unstable();
+    elements.serialize(serializer)
+}
+
+/// `serialize_with` for per-token storage tables: writes them as maps ordered by token
+/// ID and key.
+pub(crate) fn tables<S: Serializer>(
+    tables: &HashMap<u32, Arc<Backend<String>>>,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    let tables: BTreeMap<u32, BTreeMap<String, String>> = tables
+        .iter()
+        .map(|(token_id, table)| (*token_id, table.iter().map(|(key, value)| (key.into_owned(), value)).collect()))
+        .collect();
+    tables.serialize(serializer)
+}
+
+/// `deserialize_with` counterpart of [`tables`].
+pub(crate) fn load_tables<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<HashMap<u32, Arc<Backend<String>>>, D::Error> {
+    let tables: HashMap<u32, BTreeMap<String, String>> = HashMap::deserialize(deserializer)?;
+    Ok(tables
+        .into_iter()
+        .map(|(token_id, entries)| {
+            let mut table = Backend::default();
+            for (key, value) in entries {
+                table.set(&key, value);
+            }
+            (token_id, Arc::new(table))
+        })
+        .collect())
+}
+
+fn encode<T: Serialize>(section: &T) -> Value {
+    serde_json::to_value(section).expect("state sections are always serializable")
+}
+
+fn decode<T: DeserializeOwned>(name: &str, value: &Value) -> Result<T> {
+    T::deserialize(value)
+        .map_err(|err| ContractError::new(ErrorKind::InvalidInput, format!("Invalid {} section: {}", name, err)))
+}
+
+/// One decoded section, ready to be installed.
+enum Section {
+    Allowances(Allowances),
+    Analytics(Analytics),
+    Authorizations(Authorizations),
+    Bridge(Bridge),
+    Consumables(Consumables),
+    Delegations(Delegations),
+    Drops(Drops),
+    Fractions(Fractions),
+    Gas(GasMeter),
+    Governance(Governance),
+    Guardians(Guardians),
+    Metadata(Metadata),
+    Names(Names),
+    Policy(Policy),
+    Rewards(Rewards),
+    Snapshots(SnapshotHistory),
+    Tokens(Tokens),
+    Wrapping(Wrapping),
+}
+
+impl Section {
+    fn decode(name: &str, value: &Value) -> Result<Section> {
+        Ok(match name {
+            "allowances" => Section::Allowances(decode(name, value)?),
+            "analytics" => Section::Analytics(decode(name, value)?),
+            "authorizations" => Section::Authorizations(decode(name, value)?),
+            "bridge" => Section::Bridge(decode(name, value)?),
+            "consumables" => Section::Consumables(decode(name, value)?),
+            "delegations" => Section::Delegations(decode(name, value)?),
+            "drops" => Section::Drops(decode(name, value)?),
+            "fractions" => Section::Fractions(decode(name, value)?),
+            "gas" => Section::Gas(decode(name, value)?),
+            "governance" => Section::Governance(decode(name, value)?),
+            "guardians" => Section::Guardians(decode(name, value)?),
+            "metadata" => Section::Metadata(decode(name, value)?),
+            "names" => Section::Names(decode(name, value)?),
+            "policy" => Section::Policy(decode(name, value)?),
+            "rewards" => Section::Rewards(decode(name, value)?),
+            "snapshots" => Section::Snapshots(decode(name, value)?),
+            "tokens" => Section::Tokens(decode(name, value)?),
+            "wrapping" => Section::Wrapping(decode(name, value)?),
+            _ => {
+                return Err(ContractError::new(
+                    ErrorKind::InvalidInput,
+                    format!("Unknown state section: {}", name),
+                ))
+            }
+        })
+    }
+}
+
+impl ERC1155 {
+    /// Returns every section of the state in canonical form, by name.
+    pub(crate) fn state_sections(&self) -> BTreeMap<&'static str, Value> {
+        let mut sections = BTreeMap::new();
+        sections.insert("allowances", encode(&self.allowances));
+        sections.insert("analytics", encode(&self.analytics));
+        sections.insert("authorizations", encode(&self.authorizations));
+        sections.insert("bridge", encode(&self.bridge));
+        sections.insert("consumables", encode(&self.consumables));
+        sections.insert("delegations", encode(&self.delegations));
+        sections.insert("drops", encode(&self.drops));
+        sections.insert("fractions", encode(&self.fractions));
+        sections.insert("gas", encode(&self.gas));
+        sections.insert("governance", encode(&self.governance));
+        sections.insert("guardians", encode(&self.guardians));
+        sections.insert("metadata", encode(&self.metadata));
+        sections.insert("names", encode(&self.names));
+        sections.insert("policy", encode(&self.policy));
+        sections.insert("rewards", encode(&self.rewards));
+        sections.insert("snapshots", encode(&self.balances.history()));
+        sections.insert("tokens", encode(&self.tokens));
+        sections.insert("wrapping", encode(&self.wrapping));
+        sections
+    }
+
+    /// Replaces sections with values produced by `state_sections`. Nothing changes if one
+    /// of them does not decode.
+    pub(crate) fn restore_sections(&mut self, sections: &BTreeMap<String, Value>) -> Result<()> {
+        let decoded = sections
+            .iter()
+            .map(|(name, value)| Section::decode(name, value))
+            .collect::<Result<Vec<Section>>>()?;
+        for section in decoded {
+            match section {
+                Section::Allowances(allowances) => self.allowances = allowances,
+                Section::Analytics(analytics) => self.analytics = analytics,
+                Section::Authorizations(authorizations) => self.authorizations = authorizations,
+                Section::Bridge(bridge) => self.bridge = bridge,
+                Section::Consumables(consumables) => self.consumables = consumables,
+                Section::Delegations(delegations) => self.delegations = delegations,
+                Section::Drops(drops) => self.drops = drops,
+                Section::Fractions(fractions) => self.fractions = fractions,
+                Section::Gas(gas) => self.gas = gas,
+                Section::Governance(governance) => self.governance = governance,
+                Section::Guardians(guardians) => self.guardians = guardians,
+                Section::Metadata(mut metadata) => {
+                    metadata.move_to(self.metadata.tables().clone());
+                    self.attribute_index = AttributeIndex::build(&metadata);
+                    self.metadata = metadata;
+                }
+                Section::Names(names) => self.names = names,
+                Section::Policy(policy) => self.policy = policy,
+                Section::Rewards(rewards) => self.rewards = rewards,
+                Section::Snapshots(history) => self.balances.restore_history(history),
+                Section::Tokens(tokens) => self.tokens = tokens,
+                Section::Wrapping(wrapping) => self.wrapping = wrapping,
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,58 @@
+use wasm_bindgen::prelude::*;
+
+use crate::pipeline::CallKind;
+use crate::{ContractError, ErrorKind, Result, ERC1155};
+
+/// Source of timestamps, in milliseconds since the Unix epoch.
+///
+/// Reads the host clock unless pinned with `ERC1155::set_time`, which lets tests
+/// and simulations control time-dependent behavior.
+#[derive(Clone, Default)]
+pub(crate) struct Clock {
+    fixed: Option<u64>,
+}
+
+impl Clock {
+    /// Returns the current time in milliseconds.
+    pub fn now(&self) -> u64 {
+        self.fixed.unwrap_or_else(host_now)
+    }
+
+    /// Pins the clock to `timestamp`, or follows the host clock again when `None`.
+    pub fn pin(&mut self, timestamp: Option<u64>) {
+        self.fixed = timestamp;
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn host_now() -> u64 {
+    js_sys::Date::now() as u64
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn host_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_millis() as u64)
+}
+
+#[wasm_bindgen]
+impl ERC1155 {
+    /// Returns the contract's current time in milliseconds since the Unix epoch.
+    pub fn now(&self) -> u64 {
+        self.clock.now()
+    }
+
+    /// Pins the contract clock to `timestamp_ms`, or follows the host clock again when
+    /// it is `undefined` (only the owner can set the time). The clock is host bookkeeping
+    /// rather than contract state, so the call is not logged as an operation.
+    pub fn set_time(&mut self, caller: &str, timestamp_ms: Option<u64>) -> Result<()> {
+        self.check_not_in_call()?;
+        let call = self.check_call(CallKind::Admin, caller, &[])?;
+        if !self.access_control.is_owner(&call.caller) {
+            return Err(ContractError::new(ErrorKind::Unauthorized, "Only the owner can set the time."));
+        }
+        self.clock.pin(timestamp_ms);
+        Ok(())
+    }
+}
@@ -0,0 +1,488 @@
+//! Many independent collections in one instance.
+//!
+//! A `CollectionManager` owns any number of `ERC1155` contracts, each with its
+//! own owner, roles, policies, metadata, balances and event log, addressed by
+//! the collection ID `create_collection` returns. Every method of a collection
+//! is reachable through `call`, which takes the same JSON requests as
+//! `ERC1155::call`, and every method also has a typed variant taking the
+//! collection ID first, under the same name, generated by `collection_methods!`
+//! from the list at the end of this file; `ERC1155::balances_of` becomes
+//! `collection_balances_of`, since `balances_of` spans every collection at once.
+//! Native hosts can also borrow a collection directly with `collection` /
+//! `collection_mut`.
+
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+use wasm_bindgen::prelude::*;
+
+use crate::rpc::error_response;
+use crate::{
+    BalanceChange, BalanceEntry, BridgeTransfer, ContractError, DistributionInfo, DropInfo, ErrorKind, ErrorStat,
+    EventFilter, EventRecord, Fraction, GasSchedule, MigrationReport, OperationFilter, OperationRecord, ProposalInfo,
+    Receipt, RecoveryStatus, Result, RoyaltyInfo, StateDiff, StateStats, TokenClass, TotalMismatch, TransferReceipt,
+    ERC1155,
+};
+
+/// Settings applied to a new collection on behalf of its owner.
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct CollectionConfig {
+    name: Option<String>,
+    base_uri: Option<String>,
+    admins: Vec<String>,
+    max_batch_size: Option<u32>,
+}
+
+/// A collection and its display name.
+struct Collection {
+    name: Option<String>,
+    contract: ERC1155,
+}
+
+/// A non-zero balance in one collection.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CollectionBalance {
+    #[wasm_bindgen(readonly)]
+    pub collection_id: u64,
+    #[wasm_bindgen(readonly)]
+    pub token_id: u32,
+    #[wasm_bindgen(readonly)]
+    pub balance: u64,
+}
+
+/// Registry of independent collections, addressed by ID.
+#[wasm_bindgen]
+#[derive(Default)]
+pub struct CollectionManager {
+    /// Collection ID -> collection; IDs start at 1 and are never reused.
+    collections: BTreeMap<u64, Collection>,
+    last_id: u64,
+}
+
+fn unknown_collection() -> ContractError {
+    ContractError::new(ErrorKind::InvalidInput, "Collection does not exist.")
+}
+
+impl CollectionManager {
+    /// Returns the contract of a collection.
+    pub fn collection(&self, collection_id: u64) -> Result<&ERC1155> {
+        self.collections
+            .get(&collection_id)
+            .map(|collection| &collection.contract)
+            .ok_or_else(unknown_collection)
+    }
+
+    /// Returns the contract of a collection for calling its mutating methods.
+    pub fn collection_mut(&mut self, collection_id: u64) -> Result<&mut ERC1155> {
+        self.collections
+            .get_mut(&collection_id)
+            .map(|collection| &mut collection.contract)
+            .ok_or_else(unknown_collection)
+    }
+}
+
+/// Generates the collection-scoped variant of each listed `ERC1155` method: the same name
+/// and parameters with the collection ID first, failing if the collection does not exist.
+/// `queries` borrow the collection and `calls` borrow it mutably; `fallible_queries` return
+/// the method's own `Result`, and `infallible_calls` wrap the plain value the method returns.
+macro_rules! collection_methods {
+    (
+        queries { $(fn $query:ident($($query_arg:ident: $query_ty:ty),* $(,)?) -> $query_ret:ty;)* }
+        fallible_queries {
+            $(fn $try_query:ident($($try_query_arg:ident: $try_query_ty:ty),* $(,)?) -> $try_query_ret:ty;)*
+        }
+        calls { $(fn $call:ident($($call_arg:ident: $call_ty:ty),* $(,)?) -> $call_ret:ty;)* }
+        infallible_calls {
+            $(fn $plain_call:ident($($plain_call_arg:ident: $plain_call_ty:ty),* $(,)?) -> $plain_call_ret:ty;)*
+        }
+    ) => {
+        #[wasm_bindgen]
+        impl CollectionManager {
+            $(
+                #[doc = concat!("See [`ERC1155::", stringify!($query), "`].")]
+                pub fn $query(&self, collection_id: u64, $($query_arg: $query_ty),*) -> Result<$query_ret> {
+                    Ok(self.collection(collection_id)?.$query($($query_arg),*))
+                }
+            )*
+            $(
+                #[doc = concat!("See [`ERC1155::", stringify!($try_query), "`].")]
+                pub fn $try_query(
+                    &self,
+                    collection_id: u64,
+                    $($try_query_arg: $try_query_ty),*
+                ) -> Result<$try_query_ret> {
+                    self.collection(collection_id)?.$try_query($($try_query_arg),*)
+                }
+            )*
+            $(
+                #[doc = concat!("See [`ERC1155::", stringify!($call), "`].")]
+                #[allow(clippy::too_many_arguments)]
+                pub fn $call(&mut self, collection_id: u64, $($call_arg: $call_ty),*) -> Result<$call_ret> {
+                    self.collection_mut(collection_id)?.$call($($call_arg),*)
+                }
+            )*
+            $(
+                #[doc = concat!("See [`ERC1155::", stringify!($plain_call), "`].")]
+                pub fn $plain_call(
+                    &mut self,
+                    collection_id: u64,
+                    $($plain_call_arg: $plain_call_ty),*
+                ) -> Result<$plain_call_ret> {
+                    Ok(self.collection_mut(collection_id)?.$plain_call($($plain_call_arg),*))
+                }
+            )*
+        }
+    };
+}
+
+#[wasm_bindgen]
+impl CollectionManager {
+    /// Creates an empty manager.
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> CollectionManager {
+        Self::default()
+    }
+
+    /// Creates a collection owned by `owner` and returns its ID. Nothing is created if the
+    /// configuration cannot be applied.
+    /// # Parameters
+    /// - `config_json`: `{ "name", "base_uri", "admins": [], "max_batch_size" }`, every field
+    ///   optional; `""` for the defaults. The settings are applied as calls by `owner`.
+    pub fn create_collection(&mut self, owner: &str, config_json: &str) -> Result<u64> {
+        let config: CollectionConfig = if config_json.trim().is_empty() {
+            CollectionConfig::default()
+        } else {
+            serde_json::from_str(config_json).map_err(|err| {
+                ContractError::new(ErrorKind::InvalidInput, format!("Invalid collection config: {}", err))
+            })?
+        };
+        let mut contract = ERC1155::new(owner)?;
+        for admin in &config.admins {
+            contract.add_admin(owner, admin)?;
+        }
+        if let Some(base_uri) = config.base_uri {
+            contract.set_base_uri(owner, Some(base_uri))?;
+        }
+        if let Some(max_batch_size) = config.max_batch_size {
+            contract.set_max_batch_size(owner, max_batch_size)?;
+        }
+
+        self.last_id += 1;
+        let collection = Collection {
+            name: config.name,
+            contract,
+        };
+        self.collections.insert(self.last_id, collection);
+        Ok(self.last_id)
+    }
+
+    /// Returns the IDs of every collection, in ascending order.
+    pub fn collection_ids(&self) -> Vec<u64> {
+        self.collections.keys().copied().collect()
+    }
+
+    /// Returns the number of collections.
+    pub fn collection_count(&self) -> u32 {
+        self.collections.len() as u32
+    }
+
+    /// Returns the name a collection was created with; `undefined` if it has none or does
+    /// not exist.
+    pub fn collection_name(&self, collection_id: u64) -> Option<String> {
+        self.collections.get(&collection_id)?.name.clone()
+    }
+
+    /// Returns a copy of a collection's contract, like `ERC1155::fork`.
+    pub fn fork_collection(&self, collection_id: u64) -> Result<ERC1155> {
+        self.collection(collection_id).map(ERC1155::fork)
+    }
+
+    /// Runs a JSON request against a collection; see `ERC1155::call`. An unknown collection
+    /// is reported in the envelope like any other error.
+    pub fn call(&mut self, collection_id: u64, request_json: &str) -> String {
+        match self.collection_mut(collection_id) {
+            Ok(contract) => contract.call(request_json),
+            Err(err) => error_response(request_json, err),
+        }
+    }
+
+    /// Returns every non-zero balance `owner` holds in one collection; see
+    /// [`ERC1155::balances_of`].
+    pub fn collection_balances_of(&self, collection_id: u64, owner: &str) -> Result<Vec<BalanceEntry>> {
+        Ok(self.collection(collection_id)?.balances_of(owner))
+    }
+
+    /// See [`ERC1155::reset_error_stats`].
+    pub fn reset_error_stats(&mut self, collection_id: u64) -> Result<()> {
+        self.collection_mut(collection_id)?.reset_error_stats();
+        Ok(())
+    }
+
+    /// Returns every non-zero balance `owner` holds in any collection, ordered by
+    /// collection and token ID.
+    pub fn balances_of(&self, owner: &str) -> Vec<CollectionBalance> {
+        self.collections
+            .iter()
+            .flat_map(|(collection_id, collection)| {
+                collection
+                    .contract
+                    .balances_of(owner)
+                    .into_iter()
+                    .map(move |entry| CollectionBalance {
+                        collection_id: *collection_id,
+                        token_id: entry.token_id,
+                        balance: entry.balance,
+                    })
+            })
+            .collect()
+    }
+
+    /// Returns the IDs of the collections `account` owns, in ascending order. `account` may
+    /// be a `@name` registered in each collection.
+    pub fn collections_owned_by(&self, account: &str) -> Vec<u64> {
+        self.collections
+            .iter()
+            .filter(|(_, collection)| {
+                let contract = &collection.contract;
+                contract.owner().as_deref() == Some(&*contract.resolve_account(account))
+            })
+            .map(|(collection_id, _)| *collection_id)
+            .collect()
+    }
+}
+
+collection_methods! {
+    queries {
+        fn balance_of(owner: &str, token_id: u32) -> u64;
+        fn total_supply(token_id: u32) -> u64;
+        fn uri(token_id: u32) -> String;
+        fn owner() -> Option<String>;
+        fn get_approvals(owner: &str) -> Vec<String>;
+        fn approval_expiry(owner: &str, operator: &str) -> Option<u64>;
+        fn get_events(from_seq: u64, limit: usize) -> Vec<EventRecord>;
+        fn event_count() -> u64;
+        fn is_approved(owner: &str, operator: &str) -> bool;
+        fn scoped_allowance(owner: &str, operator: &str, token_id: u32) -> u64;
+        fn is_treasury_account(account: &str) -> bool;
+        fn holder_count(token_id: u32) -> u64;
+        fn unique_holders_total() -> u64;
+        fn top_holders(token_id: u32, n: usize) -> Vec<BalanceEntry>;
+        fn circulating_supply(token_id: u32) -> u64;
+        fn get_operations(filter: &OperationFilter) -> Vec<OperationRecord>;
+        fn operation_events(id: u64) -> Vec<EventRecord>;
+        fn operation_count() -> u64;
+        fn mint_authorizer() -> Option<String>;
+        fn instance_id() -> String;
+        fn mint_authorization_message(to: &str, token_id: u32, amount: u64, expiry: u64) -> Vec<u8>;
+        fn is_bridge_operator(account: &str) -> bool;
+        fn bridge_transfer(transfer_id: u64) -> Option<BridgeTransfer>;
+        fn bridge_transfer_count() -> u64;
+        fn bridge_locked(token_id: u32) -> u64;
+        fn is_bridge_proof_processed(proof_id: &str) -> bool;
+        fn now() -> u64;
+        fn state_stats() -> StateStats;
+        fn token_uses(token_id: u32) -> u64;
+        fn uses_remaining(owner: &str, token_id: u32) -> u64;
+        fn delegate_of(account: &str, token_id: u32) -> Option<String>;
+        fn delegators_of(account: &str, token_id: u32) -> Vec<String>;
+        fn delegated_weight_of(account: &str, token_id: u32) -> u64;
+        fn diff(other: &ERC1155) -> StateDiff;
+        fn drop_commitment(seed: &str) -> String;
+        fn drop_info(drop_id: u64) -> Option<DropInfo>;
+        fn drop_count() -> u64;
+        fn is_registered_for_drop(drop_id: u64, account: &str) -> bool;
+        fn drop_assignment(drop_id: u64, account: &str) -> Option<u32>;
+        fn query_events(filter: &EventFilter) -> Vec<EventRecord>;
+        fn token_expiry(token_id: u32) -> Option<u64>;
+        fn is_token_expired(token_id: u32) -> bool;
+        fn fraction_of(nft_id: u32) -> Option<Fraction>;
+        fn gas_schedule() -> Option<GasSchedule>;
+        fn gas_limit() -> Option<u64>;
+        fn gas_used() -> u64;
+        fn proposal(proposal_id: u64) -> Option<ProposalInfo>;
+        fn proposal_count() -> u64;
+        fn vote_of(proposal_id: u64, account: &str) -> Option<bool>;
+        fn guardians() -> Vec<String>;
+        fn guardian_threshold() -> u32;
+        fn recovery_status() -> Option<RecoveryStatus>;
+        fn state_hash() -> String;
+        fn attribute(token_id: u32, key: &str) -> Option<String>;
+        fn attribute_keys(token_id: u32) -> Vec<String>;
+        fn is_generative_metadata(token_id: u32) -> bool;
+        fn base_uri() -> Option<String>;
+        fn metadata_json(token_id: u32) -> String;
+        fn is_metadata_frozen(token_id: u32) -> bool;
+        fn is_collection_metadata_frozen() -> bool;
+        fn resolve(name: &str) -> Option<String>;
+        fn name_of(account: &str) -> Option<String>;
+        fn pending_event_count() -> u64;
+        fn unacknowledged_event_count() -> u64;
+        fn is_paused() -> bool;
+        fn is_immutable() -> bool;
+        fn max_batch_size() -> u32;
+        fn is_blacklisted(account: &str) -> bool;
+        fn distribution(distribution_id: u64) -> Option<DistributionInfo>;
+        fn distribution_count() -> u64;
+        fn claimable_reward(distribution_id: u64, account: &str) -> u64;
+        fn snapshot_json() -> String;
+        fn events_json(from_seq: u64, limit: usize) -> String;
+        fn last_snapshot_id() -> u64;
+        fn is_fungible(token_id: u32) -> bool;
+        fn is_nft(token_id: u32) -> bool;
+        fn token_type(token_id: u32) -> u32;
+        fn token_index(token_id: u32) -> u32;
+        fn get_error_stats() -> Vec<ErrorStat>;
+        fn error_count() -> u64;
+        fn has_creator_role(account: &str) -> bool;
+        fn creator_of(token_id: u32) -> Option<String>;
+        fn has_token_royalty(token_id: u32) -> bool;
+        fn royalty_ceiling() -> u64;
+        fn royalty_info(token_id: u32, sale_price: u64) -> Option<RoyaltyInfo>;
+        fn max_supply(token_id: u32) -> Option<u64>;
+        fn exists(token_id: u32) -> bool;
+        fn token_class(token_id: u32) -> Option<TokenClass>;
+        fn token_name(token_id: u32) -> Option<String>;
+        fn token_symbol(token_id: u32) -> Option<String>;
+        fn token_decimals(token_id: u32) -> Option<u8>;
+        fn in_transaction() -> bool;
+        fn is_wrap_operator(account: &str) -> bool;
+        fn wrapped_asset(token_id: u32) -> Option<String>;
+        fn wrapped_token(external_asset: &str) -> Option<u32>;
+    }
+
+    fallible_queries {
+        fn balance_of_batch(owners: Vec<String>, token_ids: Vec<u32>) -> Vec<BalanceEntry>;
+        fn balances_bulk(owner: &str, token_ids: &[u32]) -> Vec<u64>;
+        fn quorum_reached(proposal_id: u64) -> bool;
+        fn proposal_passed(proposal_id: u64) -> bool;
+        fn verify_totals(expected: &str) -> Vec<TotalMismatch>;
+        fn export_replay_log() -> String;
+        fn find_tokens(query_json: &str, offset: usize, limit: usize) -> Vec<u32>;
+        fn balance_of_at(owner: &str, token_id: u32, snapshot_id: u64) -> u64;
+        fn total_supply_at(token_id: u32, snapshot_id: u64) -> u64;
+        fn balance_changes(
+            snapshot_a: u64,
+            snapshot_b: u64,
+            token_id: u32,
+            offset: usize,
+            limit: usize,
+        ) -> Vec<BalanceChange>;
+    }
+
+    calls {
+        fn mint(caller: &str, token_id: u32, amount: u64) -> TransferReceipt;
+        fn transfer(caller: &str, to: &str, token_id: u32, amount: u64) -> TransferReceipt;
+        fn transfer_from(caller: &str, from: &str, to: &str, token_id: u32, amount: u64) -> TransferReceipt;
+        fn burn(caller: &str, token_id: u32, amount: u64) -> Receipt;
+        fn approve(caller: &str, approved: &str, token_id: u32) -> Receipt;
+        fn add_admin(caller: &str, new_admin: &str) -> Receipt;
+        fn transfer_batch(caller: &str, to: &str, token_ids: Vec<u32>, amounts: Vec<u64>) -> Vec<TransferReceipt>;
+        fn burn_from(caller: &str, from: &str, token_id: u32, amount: u64) -> Receipt;
+        fn approve_until(caller: &str, operator: &str, expires_at: u64) -> Receipt;
+        fn revoke_approval(caller: &str, operator: &str) -> Receipt;
+        fn revoke_all_approvals(caller: &str) -> Receipt;
+        fn transfer_ownership(caller: &str, new_owner: &str) -> Receipt;
+        fn renounce_ownership(caller: &str, freeze_configuration: bool) -> Receipt;
+        fn mint_to_many(caller: &str, token_id: u32, recipients: Vec<String>, amounts: Vec<u64>) -> Receipt;
+        fn mint_to_many_same(caller: &str, token_id: u32, recipients: Vec<String>, amount: u64) -> Receipt;
+        fn approve_scoped(caller: &str, operator: &str, token_ids: Vec<u32>, amounts: Vec<u64>) -> Receipt;
+        fn set_treasury_account(caller: &str, account: &str, enabled: bool) -> Receipt;
+        fn set_mint_authorizer(caller: &str, public_key: Option<String>) -> Receipt;
+        fn mint_with_authorization(
+            caller: &str,
+            to: &str,
+            token_id: u32,
+            amount: u64,
+            expiry: u64,
+            signature: &str,
+        ) -> Receipt;
+        fn set_bridge_operator(caller: &str, account: &str, enabled: bool) -> Receipt;
+        fn bridge_out(caller: &str, token_id: u32, amount: u64, destination: &str) -> u64;
+        fn bridge_in(caller: &str, proof_id: &str, to: &str, token_id: u32, amount: u64) -> Receipt;
+        fn apply_transfers_bulk(caller: &str, accounts: Vec<String>, packed: &[u64]) -> u32;
+        fn set_time(caller: &str, timestamp_ms: Option<u64>) -> ();
+        fn compact(limit: u32) -> u32;
+        fn set_token_uses(caller: &str, token_id: u32, uses: u64) -> Receipt;
+        fn consume(caller: &str, token_id: u32, amount: u64, context: &str) -> Receipt;
+        fn delegate(caller: &str, token_id: u32, delegatee: &str) -> Receipt;
+        fn undelegate(caller: &str, token_id: u32) -> Receipt;
+        fn apply_diff(diff: &StateDiff) -> ();
+        fn create_drop(
+            caller: &str,
+            commitment: &str,
+            token_ids: Vec<u32>,
+            weights: Vec<u64>,
+            registration_period_ms: u64,
+        ) -> u64;
+        fn register_for_drop(caller: &str, drop_id: u64) -> Receipt;
+        fn reveal_drop(caller: &str, drop_id: u64, seed: &str) -> Receipt;
+        fn cancel_drop(caller: &str, drop_id: u64) -> Receipt;
+        fn set_token_expiry(caller: &str, token_id: u32, expires_at: Option<u64>) -> Receipt;
+        fn sweep_expired(caller: &str, token_id: u32, limit: usize) -> Receipt;
+        fn fractionalize(caller: &str, nft_id: u32, share_token_id: u32, total_shares: u64) -> Receipt;
+        fn redeem(caller: &str, nft_id: u32) -> Receipt;
+        fn set_gas_schedule(caller: &str, schedule: Option<GasSchedule>) -> Receipt;
+        fn set_gas_limit(caller: &str, limit: Option<u64>) -> Receipt;
+        fn create_proposal(
+            caller: &str,
+            description: &str,
+            token_id: u32,
+            snapshot_id: u64,
+            quorum: u64,
+            voting_period_ms: u64,
+        ) -> u64;
+        fn vote(caller: &str, proposal_id: u64, support: bool) -> Receipt;
+        fn set_guardians(caller: &str, guardians: Vec<String>, threshold: u32, delay_ms: u64) -> Receipt;
+        fn support_recovery(caller: &str, new_owner: &str) -> Receipt;
+        fn cancel_recovery(caller: &str) -> Receipt;
+        fn execute_recovery(caller: &str) -> Receipt;
+        fn set_attribute(caller: &str, token_id: u32, key: &str, value: &str) -> Receipt;
+        fn remove_attribute(caller: &str, token_id: u32, key: &str) -> Receipt;
+        fn set_generative_metadata(caller: &str, token_id: u32, enabled: bool) -> Receipt;
+        fn set_svg_template(caller: &str, template: Option<String>) -> Receipt;
+        fn set_base_uri(caller: &str, base_uri: Option<String>) -> Receipt;
+        fn freeze_metadata(caller: &str, token_id: u32) -> Receipt;
+        fn freeze_all_metadata(caller: &str) -> Receipt;
+        fn import_state(caller: &str, data: &str, format: &str) -> MigrationReport;
+        fn register_name(caller: &str, name: &str) -> Receipt;
+        fn release_name(caller: &str) -> Receipt;
+        fn ack_events(through_seq: u64) -> ();
+        fn pause(caller: &str) -> Receipt;
+        fn unpause(caller: &str) -> Receipt;
+        fn set_blacklisted(caller: &str, account: &str, blacklisted: bool) -> Receipt;
+        fn set_max_batch_size(caller: &str, max_batch_size: u32) -> Receipt;
+        fn distribute_to_holders(
+            caller: &str,
+            source_token_id: u32,
+            snapshot_id: u64,
+            reward_token_id: u32,
+            total_amount: u64,
+            claimable: bool,
+        ) -> u64;
+        fn claim_reward(caller: &str, distribution_id: u64) -> Receipt;
+        fn take_snapshot(caller: &str) -> u64;
+        fn create_token_type(caller: &str, fungible: bool) -> u32;
+        fn set_creator_role(caller: &str, account: &str, enabled: bool) -> Receipt;
+        fn set_token_uri(caller: &str, token_id: u32, uri: &str) -> Receipt;
+        fn set_royalty(caller: &str, token_id: u32, receiver: &str, basis_points: u64) -> Receipt;
+        fn delete_token_royalty(caller: &str, token_id: u32) -> Receipt;
+        fn set_default_royalty(caller: &str, receiver: Option<String>, basis_points: u64) -> Receipt;
+        fn set_royalty_ceiling(caller: &str, basis_points: u64) -> Receipt;
+        fn set_max_supply(caller: &str, token_id: u32, max_supply: Option<u64>) -> Receipt;
+        fn register_token(caller: &str, token_id: u32, name: &str, symbol: &str, decimals: u8) -> Receipt;
+        fn begin_transaction() -> ();
+        fn commit() -> ();
+        fn rollback() -> ();
+        fn set_wrap_operator(caller: &str, account: &str, enabled: bool) -> Receipt;
+        fn map_wrapped_asset(caller: &str, token_id: u32, external_asset: &str) -> Receipt;
+        fn deposit(caller: &str, token_id: u32, to: &str, amount: u64, external_ref: &str) -> Receipt;
+        fn withdraw(caller: &str, token_id: u32, amount: u64, external_recipient: &str) -> Receipt;
+    }
+
+    infallible_calls {
+        fn drain_pending_events(max: usize) -> String;
+        fn redeliver_unacknowledged() -> u64;
+    }
+}
@@ -0,0 +1,83 @@
+//! State compaction and size statistics.
+//!
+//! Balances that drop to zero are removed as they are written, and so are
+//! allowances. Expired approvals are only dropped when their owner's approvals
+//! are written again, so owners who never come back leave them behind, and hash
+//! tables keep the capacity of removed entries. `compact` sweeps the approvals in
+//! bounded steps and releases the spare capacity; it emits nothing and changes no
+//! observable state except `state_hash`, which covers approvals as stored.
+
+use wasm_bindgen::prelude::*;
+
+use crate::{Result, ERC1155};
+
+/// Entry counts of the contract's state tables.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StateStats {
+    /// Stored balance entries.
+    #[wasm_bindgen(readonly)]
+    pub balance_entries: u64,
+    /// Token IDs with at least one stored balance.
+    #[wasm_bindgen(readonly)]
+    pub balance_tables: u64,
+    /// Saved snapshot values of balances and totals.
+    #[wasm_bindgen(readonly)]
+    pub history_entries: u64,
+    /// Stored operator approvals, live or not.
+    #[wasm_bindgen(readonly)]
+    pub approval_entries: u64,
+    /// Stored operator approvals that have expired; `compact` removes them.
+    #[wasm_bindgen(readonly)]
+    pub expired_approvals: u64,
+    #[wasm_bindgen(readonly)]
+    pub allowance_entries: u64,
+    #[wasm_bindgen(readonly)]
+    pub attribute_entries: u64,
+    #[wasm_bindgen(readonly)]
+    pub events: u64,
+    #[wasm_bindgen(readonly)]
+    pub operations: u64,
+    /// Size of the WASM linear memory in bytes; zero outside WASM.
+    #[wasm_bindgen(readonly)]
+    pub memory_bytes: u64,
+}
+
+fn memory_bytes() -> u64 {
+    #[cfg(target_arch = "wasm32")]
+    return core::arch::wasm32::memory_size(0) as u64 * 65_536;
+    #[cfg(not(target_arch = "wasm32"))]
+    0
+}
+
+#[wasm_bindgen]
+impl ERC1155 {
+    /// Removes up to `limit` expired approvals and releases spare table capacity. Returns
+    /// the number of approvals removed; call it again until it returns zero to compact
+    /// everything.
+    pub fn compact(&mut self, limit: u32) -> Result<u32> {
+        let mut this = self.guarded()?;
+        this.balances.shrink_to_fit();
+        let now = this.clock.now();
+        Ok(this.approvals.compact(now, limit as usize) as u32)
+    }
+
+    /// Returns the entry counts of the contract's state tables.
+    pub fn state_stats(&self) -> StateStats {
+        let balance_entries = self.balances.entry_count();
+        let (balance_tables, history_entries) = self.balances.table_counts();
+        let (approval_entries, expired_approvals) = self.approvals.entry_counts(self.clock.now());
+        StateStats {
+            balance_entries,
+            balance_tables,
+            history_entries,
+            approval_entries,
+            expired_approvals,
+            allowance_entries: self.allowances.len() as u64,
+            attribute_entries: self.metadata.attribute_count(),
+            events: self.event_count(),
+            operations: self.operation_count(),
+            memory_bytes: memory_bytes(),
+        }
+    }
+}
@@ -0,0 +1,148 @@
+//! Consumable tokens.
+//!
+//! Using a token is distinct from transferring or burning it: `consume` spends
+//! uses of the caller's tokens and emits `Consumed` with a context string, such
+//! as the game action or API endpoint the use was for. Each token is worth one
+//! use unless its creator sets more with `set_token_uses`. A token with several
+//! uses is burned when its first use is spent, and its remaining uses stay with
+//! the holder who opened it; they cannot be transferred.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::gas::GasCost;
+use crate::pipeline::CallKind;
+use crate::{ContractError, ErrorKind, EventKind, EventRecord, Receipt, Result, ERC1155};
+
+const MAX_CONTEXT_LEN: usize = 256;
+
+/// Uses per token and the uses left from opened tokens.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub(crate) struct Consumables {
+    /// Uses each token of a token ID is worth, when more than one.
+    uses: HashMap<u32, u64>,
+    /// `TokenID -> (User -> uses left from opened tokens)`.
+    opened: HashMap<u32, HashMap<String, u64>>,
+}
+
+impl Consumables {
+    fn uses(&self, token_id: u32) -> u64 {
+        self.uses.get(&token_id).copied().unwrap_or(1)
+    }
+
+    fn opened(&self, owner: &str, token_id: u32) -> u64 {
+        self.opened
+            .get(&token_id)
+            .and_then(|holders| holders.get(owner))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    fn set_opened(&mut self, owner: &str, token_id: u32, uses: u64) {
+        let holders = self.opened.entry(token_id).or_default();
+        if uses > 0 {
+            holders.insert(owner.to_string(), uses);
+        } else {
+            holders.remove(owner);
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl ERC1155 {
+    /// Sets how many uses each token of `token_id` is worth (creator or admins only).
+    pub fn set_token_uses(&mut self, caller: &str, token_id: u32, uses: u64) -> Result<Receipt> {
+        self.enter("set_token_uses", CallKind::Admin, caller, &[], |this, call| {
+            this.check_token_manager(&call.caller, token_id)?;
+            if uses == 0 {
+                return Err(ContractError::new(ErrorKind::InvalidInput, "A token must be worth at least one use."));
+            }
+            this.charge_gas(GasCost {
+                reads: 2,
+                writes: 1,
+                events: 1,
+                ..GasCost::default()
+            })?;
+
+            if uses == 1 {
+                this.consumables.uses.remove(&token_id);
+            } else {
+                this.consumables.uses.insert(token_id, uses);
+            }
+            let mut event = EventRecord::new(this.next_event_seq(), EventKind::TokenUsesUpdated, &call.caller);
+            event.token_id = Some(token_id);
+            event.amount = Some(uses);
+            this.emit(event);
+            Ok(this.receipt(Vec::new()))
+        })
+    }
+
+    /// Returns how many uses each token of `token_id` is worth.
+    pub fn token_uses(&self, token_id: u32) -> u64 {
+        self.consumables.uses(token_id)
+    }
+
+    /// Returns the uses `owner` has left of `token_id`: those of its tokens plus those left
+    /// from tokens it opened, saturating at `u64::MAX`. Zero once the token has expired.
+    pub fn uses_remaining(&self, owner: &str, token_id: u32) -> u64 {
+        let owner = self.resolve_account(owner);
+        let opened = if self.is_token_expired(token_id) {
+            0
+        } else {
+            self.consumables.opened(&owner, token_id)
+        };
+        self.balance_of(&owner, token_id)
+            .saturating_mul(self.consumables.uses(token_id))
+            .saturating_add(opened)
+    }
+
+    /// Spends `amount` uses of the caller's `token_id`, first from opened tokens and then by
+    /// burning as many tokens as needed, and emits `Consumed` with `context` (at most 256
+    /// characters).
+    pub fn consume(&mut self, caller: &str, token_id: u32, amount: u64, context: &str) -> Result<Receipt> {
+        self.enter("consume", CallKind::User, caller, &[], |this, call| {
+            let caller = &call.caller;
+            if amount == 0 {
+                return Err(ContractError::new(ErrorKind::InvalidInput, "Consume amount must be positive."));
+            }
+            if context.chars().count() > MAX_CONTEXT_LEN {
+                return Err(ContractError::new(ErrorKind::InvalidInput, "Context must be at most 256 characters."));
+            }
+            this.check_not_expired(token_id)?;
+            if this.uses_remaining(caller, token_id) < amount {
+                return Err(ContractError::new(ErrorKind::InsufficientBalance, "Not enough uses remaining."));
+            }
+            let uses = this.consumables.uses(token_id);
+            let opened = this.consumables.opened(caller, token_id);
+            let needed = amount.saturating_sub(opened);
+            let burned = needed / uses + u64::from(needed % uses > 0);
+            if burned > 0 {
+                this.check_burn(token_id, burned)?;
+            }
+            this.charge_gas(GasCost {
+                reads: 3,
+                writes: 2,
+                events: 3,
+                ..GasCost::default()
+            })?;
+
+            let changes = if burned > 0 {
+                this.move_tokens(caller, Some(caller), None, token_id, burned)?
+            } else {
+                Vec::new()
+            };
+            // At most `opened`, or below `uses` once a token was opened, so it fits in a u64.
+            let left = u128::from(opened) + u128::from(burned) * u128::from(uses) - u128::from(amount);
+            this.consumables.set_opened(caller, token_id, left as u64);
+            let mut event = EventRecord::new(this.next_event_seq(), EventKind::Consumed, caller);
+            event.from = Some(caller.clone());
+            event.token_id = Some(token_id);
+            event.amount = Some(amount);
+            event.value = Some(context.to_string());
+            this.emit(event);
+            Ok(this.receipt(changes))
+        })
+    }
+}
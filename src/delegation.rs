@@ -0,0 +1,147 @@
+//! Delegation of a token's utility weight.
+//!
+//! A holder can lend the weight of its balance of one token ID to another
+//! account without moving tokens. An account's delegated weight is its own
+//! balance, unless it delegated it away, plus the current balance of everyone
+//! delegating to it; it is computed from live balances, so it follows every
+//! transfer. Delegation is not transitive: a delegatee that delegates passes on
+//! only its own balance.
+
+use std::collections::{BTreeSet, HashMap};
+use std::convert::TryFrom;
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::gas::GasCost;
+use crate::pipeline::CallKind;
+use crate::{ContractError, ErrorKind, EventKind, EventRecord, Receipt, Result, ERC1155};
+
+/// Delegations per token ID, indexed both ways.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub(crate) struct Delegations {
+    /// TokenID -> (Delegator -> Delegatee).
+    delegates: HashMap<u32, HashMap<String, String>>,
+    /// TokenID -> (Delegatee -> Delegators).
+    delegators: HashMap<u32, HashMap<String, BTreeSet<String>>>,
+}
+
+impl Delegations {
+    fn delegate_of(&self, account: &str, token_id: u32) -> Option<&String> {
+        self.delegates.get(&token_id).and_then(|delegates| delegates.get(account))
+    }
+
+    /// Points `delegator`'s weight of `token_id` at `delegatee`, or back at itself for `None`,
+    /// removing the token's maps once they are empty.
+    fn set(&mut self, token_id: u32, delegator: &str, delegatee: Option<&str>) {
+        let delegates = self.delegates.entry(token_id).or_default();
+        let delegators = self.delegators.entry(token_id).or_default();
+        if let Some(previous) = delegates.remove(delegator) {
+            if let Some(incoming) = delegators.get_mut(&previous) {
+                incoming.remove(delegator);
+                if incoming.is_empty() {
+                    delegators.remove(&previous);
+                }
+            }
+        }
+        if let Some(delegatee) = delegatee {
+            delegates.insert(delegator.to_string(), delegatee.to_string());
+            delegators
+                .entry(delegatee.to_string())
+                .or_default()
+                .insert(delegator.to_string());
+        }
+        if delegates.is_empty() {
+            self.delegates.remove(&token_id);
+        }
+        if delegators.is_empty() {
+            self.delegators.remove(&token_id);
+        }
+    }
+}
+
+impl ERC1155 {
+    /// Body of [`ERC1155::delegate`] and [`ERC1155::undelegate`].
+    fn set_delegate(&mut self, caller: &str, token_id: u32, delegatee: Option<&str>) -> Result<()> {
+        let delegatee = delegatee.filter(|delegatee| *delegatee != caller);
+        if self.delegations.delegate_of(caller, token_id).map(String::as_str) == delegatee {
+            return Err(ContractError::new(ErrorKind::InvalidInput, "Delegation is unchanged."));
+        }
+        self.charge_gas(GasCost {
+            reads: 1,
+            writes: 2,
+            events: 1,
+            ..GasCost::default()
+        })?;
+
+        self.delegations.set(token_id, caller, delegatee);
+        let mut event = EventRecord::new(self.next_event_seq(), EventKind::DelegateChanged, caller);
+        event.from = Some(caller.to_string());
+        event.to = delegatee.map(str::to_string);
+        event.token_id = Some(token_id);
+        self.emit(event);
+        Ok(())
+    }
+}
+
+#[wasm_bindgen]
+impl ERC1155 {
+    /// Delegates the weight of the caller's `token_id` balance to `delegatee`, replacing any
+    /// earlier delegation of that token. Delegating to oneself is the same as `undelegate`.
+    pub fn delegate(&mut self, caller: &str, token_id: u32, delegatee: &str) -> Result<Receipt> {
+        self.enter("delegate", CallKind::User, caller, &[delegatee], |this, call| {
+            let delegatee = call.accounts[0].clone();
+            this.set_delegate(&call.caller, token_id, Some(&delegatee))?;
+            Ok(this.receipt(Vec::new()))
+        })
+    }
+
+    /// Takes back the weight of the caller's `token_id` balance.
+    pub fn undelegate(&mut self, caller: &str, token_id: u32) -> Result<Receipt> {
+        self.enter("undelegate", CallKind::User, caller, &[], |this, call| {
+            this.set_delegate(&call.caller, token_id, None)?;
+            Ok(this.receipt(Vec::new()))
+        })
+    }
+
+    /// Returns who `account` delegated its `token_id` weight to; `undefined` if it keeps it.
+    pub fn delegate_of(&self, account: &str, token_id: u32) -> Option<String> {
+        self.delegations
+            .delegate_of(&self.resolve_account(account), token_id)
+            .cloned()
+    }
+
+    /// Returns the accounts delegating their `token_id` weight to `account`, sorted.
+    pub fn delegators_of(&self, account: &str, token_id: u32) -> Vec<String> {
+        self.delegations
+            .delegators
+            .get(&token_id)
+            .and_then(|delegators| delegators.get(self.resolve_account(account).as_ref()))
+            .map(|incoming| incoming.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Returns the `token_id` weight `account` wields: its own balance unless delegated away,
+    /// plus the balances delegated to it. Saturates at `u64::MAX`; an expired token carries
+    /// no weight, since its balances count as zero.
+    pub fn delegated_weight_of(&self, account: &str, token_id: u32) -> u64 {
+        if self.is_token_expired(token_id) {
+            return 0;
+        }
+        let account = self.resolve_account(account);
+        let own = match self.delegations.delegate_of(&account, token_id) {
+            Some(_) => 0,
+            None => u128::from(self.balances.get(&account, token_id)),
+        };
+        let incoming: u128 = self
+            .delegations
+            .delegators
+            .get(&token_id)
+            .and_then(|delegators| delegators.get(account.as_ref()))
+            .into_iter()
+            .flatten()
+            .map(|delegator| u128::from(self.balances.get(delegator, token_id)))
+            .sum();
+        u64::try_from(own + incoming).unwrap_or(u64::MAX)
+    }
+}
@@ -0,0 +1,184 @@
+//! State diffs for keeping contract replicas in sync without resending full state.
+//!
+//! `a.diff(&b)` describes what has to change for `a` to equal `b`; applying it
+//! with `a.apply_diff(&diff)` brings `a` to `b`'s state as `canonical` defines
+//! it. Balances, approvals and roles change entry by entry; any other section
+//! that differs is replaced whole. Approvals are compared as stored, with their
+//! expiry, whether or not they are still live. The event log is not part of a
+//! diff.
+//!
+//! Applying a diff emits the events a log follower needs to stay in sync: a
+//! `TransferSingle` per balance change, shaped as a mint or a burn of the
+//! difference, `ApprovalForAll` per approval, and `OwnershipTransferred` and
+//! `AdminAdded` for the roles. Removed admins and replaced sections emit
+//! nothing; followers that track them have to re-read the contract.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use wasm_bindgen::prelude::*;
+
+use crate::{ContractError, ErrorKind, EventKind, EventRecord, Result, ERC1155};
+
+/// Operator of the events emitted by `apply_diff`; reserved, so no caller can act as it.
+const SYNC_OPERATOR: &str = "erc1155:sync";
+
+/// Serializable description of the state changes between two contracts.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StateDiff {
+    /// New `(owner, token_id, balance)` values; zero means the balance was cleared.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    balances: Vec<(String, u32, u64)>,
+    /// New `(owner, operator, approved, expires_at)` values.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    approvals: Vec<(String, String, bool, Option<u64>)>,
+    /// New owner, if ownership changed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    owner: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    admins_added: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    admins_removed: Vec<String>,
+    /// New canonical value of each other section that changed, by name.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    sections: BTreeMap<String, Value>,
+}
+
+#[wasm_bindgen]
+impl StateDiff {
+    /// Encodes the diff as compact JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("state diffs are always serializable")
+    }
+
+    /// Decodes a diff produced by `to_json`.
+    pub fn from_json(json: &str) -> Result<StateDiff> {
+        serde_json::from_str(json)
+            .map_err(|err| ContractError::new(ErrorKind::InvalidInput, format!("Invalid state diff: {}", err)))
+    }
+
+    /// Returns whether the two contracts were already equal.
+    pub fn is_empty(&self) -> bool {
+        self == &StateDiff::default()
+    }
+
+    /// Returns the number of balance entries in the diff.
+    pub fn balance_changes(&self) -> usize {
+        self.balances.len()
+    }
+
+    /// Returns the number of approval entries in the diff.
+    pub fn approval_changes(&self) -> usize {
+        self.approvals.len()
+    }
+
+    /// Returns the names of the other state sections the diff replaces, in order.
+    pub fn changed_sections(&self) -> Vec<String> {
+        self.sections.keys().cloned().collect()
+    }
+}
+
+#[wasm_bindgen]
+impl ERC1155 {
+    /// Returns the changes that turn this contract's state into `other`'s.
+    pub fn diff(&self, other: &ERC1155) -> StateDiff {
+        let mut diff = StateDiff::default();
+
+        for (owner, token_id, balance) in other.balances.iter() {
+            if self.balances.get(&owner, token_id) != balance {
+                diff.balances.push((owner, token_id, balance));
+            }
+        }
+        for (owner, token_id, balance) in self.balances.iter() {
+            if balance > 0 && other.balances.get(&owner, token_id) == 0 {
+                diff.balances.push((owner, token_id, 0));
+            }
+        }
+
+        for (owner, operator, expires_at) in other.approvals.iter() {
+            if self.approvals.get(owner, &operator) != Some(expires_at) {
+                diff.approvals.push((owner.to_string(), operator, true, expires_at));
+            }
+        }
+        for (owner, operator, _) in self.approvals.iter() {
+            if other.approvals.get(owner, &operator).is_none() {
+                diff.approvals.push((owner.to_string(), operator, false, None));
+            }
+        }
+
+        if self.access_control.owner != other.access_control.owner {
+            diff.owner = Some(other.access_control.owner.clone());
+        }
+        let (ours, theirs) = (&self.access_control.admins, &other.access_control.admins);
+        diff.admins_added = theirs.difference(ours).cloned().collect();
+        diff.admins_removed = ours.difference(theirs).cloned().collect();
+
+        let ours = self.state_sections();
+        for (name, value) in other.state_sections() {
+            if ours.get(name) != Some(&value) {
+                diff.sections.insert(name.to_string(), value);
+            }
+        }
+
+        // Entries cleared on both sides are found by both passes.
+        diff.balances.sort_unstable();
+        diff.balances.dedup();
+        diff.approvals.sort_unstable();
+        diff.admins_added.sort_unstable();
+        diff.admins_removed.sort_unstable();
+        diff
+    }
+
+    /// Applies a diff produced by `diff`, emitting the events listed in the module
+    /// documentation. This is a replication primitive: the diff is trusted as-is, so only
+    /// feed it diffs from the authoritative contract. Nothing changes if a section of the
+    /// diff does not decode.
+    pub fn apply_diff(&mut self, diff: &StateDiff) -> Result<()> {
+        let mut this = self.guarded()?;
+        this.restore_sections(&diff.sections)?;
+        for (owner, token_id, balance) in &diff.balances {
+            let previous = this.balances.get(owner, *token_id);
+            if previous == *balance {
+                continue;
+            }
+            // The snapshot history arrives with the `snapshots` section, if it changed.
+            this.balances.restore(owner, *token_id, *balance);
+            let mut event = EventRecord::new(this.next_event_seq(), EventKind::TransferSingle, SYNC_OPERATOR);
+            if *balance > previous {
+                event.to = Some(owner.clone());
+            } else {
+                event.from = Some(owner.clone());
+            }
+            event.token_id = Some(*token_id);
+            event.amount = Some(balance.abs_diff(previous));
+            this.emit(event);
+        }
+        for (owner, operator, approved, expires_at) in &diff.approvals {
+            this.approvals.restore(owner, operator, approved.then_some(*expires_at));
+            let mut event = EventRecord::new(this.next_event_seq(), EventKind::ApprovalForAll, operator);
+            event.from = Some(owner.clone());
+            event.approved = Some(*approved);
+            event.expires_at = *expires_at;
+            this.emit(event);
+        }
+        if let Some(owner) = &diff.owner {
+            let previous = std::mem::replace(&mut this.access_control.owner, owner.clone());
+            let mut event = EventRecord::new(this.next_event_seq(), EventKind::OwnershipTransferred, SYNC_OPERATOR);
+            event.from = Some(previous);
+            event.to = Some(owner.clone());
+            this.emit(event);
+        }
+        for admin in &diff.admins_removed {
+            this.access_control.admins.remove(admin);
+        }
+        for admin in &diff.admins_added {
+            this.access_control.admins.insert(admin.clone());
+            let mut event = EventRecord::new(this.next_event_seq(), EventKind::AdminAdded, SYNC_OPERATOR);
+            event.to = Some(admin.clone());
+            this.emit(event);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,367 @@
+//! Commit-reveal randomized drops.
+//!
+//! A drop creator commits to a secret seed by publishing its BLAKE3 hash with
+//! a weighted table of token IDs. Accounts register while registration is
+//! open; once it closes, the creator reveals the seed and every registrant is
+//! minted one token drawn from the table. Each draw hashes the seed with the
+//! drop ID, the registrant's position and its address, so the outcome is fixed
+//! by the commitment and the registrations: registrants cannot predict it
+//! without the seed, and the creator cannot pick a different seed after
+//! committing. The creator does know the seed while registration is open, so it
+//! can tell which positions and addresses draw which tokens; drops only keep
+//! registrants from gaming the draw, not the creator. A creator who never
+//! reveals mints nothing, and the creator or an admin can cancel a drop that
+//! has not been revealed. Seeds should hold at least 32 random bytes, since a
+//! short seed can be guessed from its commitment.
+
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::gas::GasCost;
+use crate::pipeline::CallKind;
+use crate::{ContractError, ErrorKind, EventKind, EventRecord, Receipt, Result, ERC1155};
+
+const DRAW_DOMAIN: &[u8] = b"erc1155-wasm/drop-draw/v1";
+
+/// A drop and its registrations.
+#[derive(Clone, Serialize, Deserialize)]
+pub(crate) struct RandomDrop {
+    creator: String,
+    /// BLAKE3 hash of the seed.
+    commitment: [u8; 32],
+    /// `(token ID, weight)` entries of the table.
+    table: Vec<(u32, u64)>,
+    /// When registration closes, in contract milliseconds.
+    deadline: u64,
+    /// Maximum number of registrants, the maximum batch size when the drop was created.
+    capacity: u32,
+    /// Registrants in registration order.
+    registrants: Vec<String>,
+    #[serde(serialize_with = "crate::canonical::sorted")]
+    registered: HashSet<String>,
+    /// Registrant -> token ID drawn for it, empty until the seed is revealed.
+    assignments: HashMap<String, u32>,
+    seed: Option<String>,
+    cancelled: bool,
+}
+
+impl RandomDrop {
+    /// Picks the token ID for the registrant at `position` from the weighted table.
+    fn draw(&self, seed: &str, drop_id: u64, position: usize, registrant: &str) -> u32 {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(DRAW_DOMAIN);
+        hasher.update(&(seed.len() as u64).to_le_bytes());
+        hasher.update(seed.as_bytes());
+        hasher.update(&drop_id.to_le_bytes());
+        hasher.update(&(position as u64).to_le_bytes());
+        hasher.update(registrant.as_bytes());
+        let mut bytes = [0u8; 16];
+        bytes.copy_from_slice(&hasher.finalize().as_bytes()[..16]);
+
+        // Reducing 128 random bits modulo a total below 2^96 leaves a negligible bias.
+        let total: u128 = self.table.iter().map(|(_, weight)| u128::from(*weight)).sum();
+        let mut ticket = u128::from_le_bytes(bytes) % total;
+        for (token_id, weight) in &self.table {
+            if ticket < u128::from(*weight) {
+                return *token_id;
+            }
+            ticket -= u128::from(*weight);
+        }
+        unreachable!("the ticket is below the total weight")
+    }
+}
+
+/// Drops, indexed by ID minus one.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub(crate) struct Drops {
+    drops: Vec<RandomDrop>,
+}
+
+/// Read-only view of a drop.
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DropInfo {
+    #[wasm_bindgen(readonly)]
+    pub id: u64,
+    #[wasm_bindgen(readonly)]
+    pub creator: String,
+    /// Hex-encoded BLAKE3 hash of the seed.
+    #[wasm_bindgen(readonly)]
+    pub commitment: String,
+    #[wasm_bindgen(readonly)]
+    pub token_ids: Vec<u32>,
+    #[wasm_bindgen(readonly)]
+    pub weights: Vec<u64>,
+    /// When registration closes, in contract milliseconds.
+    #[wasm_bindgen(readonly)]
+    pub deadline: u64,
+    #[wasm_bindgen(readonly)]
+    pub registrants: u32,
+    /// The revealed seed; `undefined` until the drop is revealed.
+    #[wasm_bindgen(readonly)]
+    pub seed: Option<String>,
+    #[wasm_bindgen(readonly)]
+    pub cancelled: bool,
+}
+
+fn parse_commitment(commitment: &str) -> Result<[u8; 32]> {
+    let invalid = || ContractError::new(ErrorKind::InvalidInput, "Commitment must be a hex-encoded BLAKE3 hash.");
+    let hash = blake3::Hash::from_hex(commitment.trim()).map_err(|_| invalid())?;
+    Ok(*hash.as_bytes())
+}
+
+impl ERC1155 {
+    fn drop_entry(&self, drop_id: u64) -> Result<&RandomDrop> {
+        drop_id
+            .checked_sub(1)
+            .and_then(|index| self.drops.drops.get(index as usize))
+            .ok_or_else(|| ContractError::new(ErrorKind::InvalidInput, "Drop does not exist."))
+    }
+}
+
+#[wasm_bindgen]
+impl ERC1155 {
+    /// Returns the commitment to publish for `seed`: its hex-encoded BLAKE3 hash. Compute it
+    /// off-chain, or on a private instance, so the seed stays secret until the reveal.
+    pub fn drop_commitment(&self, seed: &str) -> String {
+        blake3::hash(seed.as_bytes()).to_hex().to_string()
+    }
+
+    /// Opens a drop of `token_ids`, drawn with probabilities proportional to `weights`, and
+    /// returns its ID. The caller must be allowed to mint every token in the table, as many
+    /// times as the drop takes registrants, so NFT items cannot be drawn.
+    /// # Parameters
+    /// - `commitment`: The `drop_commitment` of the secret seed.
+    /// - `token_ids`: The token IDs that can be drawn, each listed once.
+    /// - `weights`: The positive weight of each token ID, paired index-wise with `token_ids`.
+    /// - `registration_period_ms`: How long registration stays open.
+    pub fn create_drop(
+        &mut self,
+        caller: &str,
+        commitment: &str,
+        token_ids: Vec<u32>,
+        weights: Vec<u64>,
+        registration_period_ms: u64,
+    ) -> Result<u64> {
+        self.enter("create_drop", CallKind::User, caller, &[], |this, call| {
+            let commitment = parse_commitment(commitment)?;
+            if token_ids.is_empty() || token_ids.len() != weights.len() {
+                return Err(ContractError::new(
+                    ErrorKind::InvalidInput,
+                    "Drops need token IDs paired with weights.",
+                ));
+            }
+            this.check_batch_size(token_ids.len())?;
+            if weights.contains(&0) || registration_period_ms == 0 {
+                return Err(ContractError::new(
+                    ErrorKind::InvalidInput,
+                    "Drop weights and the registration period must be positive.",
+                ));
+            }
+            let mut sorted = token_ids.clone();
+            sorted.sort_unstable();
+            if sorted.windows(2).any(|pair| pair[0] == pair[1]) {
+                return Err(ContractError::new(ErrorKind::InvalidInput, "Drop token IDs must be unique."));
+            }
+            // Every registrant may draw the same token, so each must be mintable that often.
+            let capacity = this.max_batch_size();
+            for &token_id in &token_ids {
+                this.check_mint(&call.caller, token_id, u64::from(capacity))?;
+            }
+            let items = token_ids.len() as u64;
+            this.charge_gas(GasCost {
+                reads: items,
+                writes: 1,
+                events: 1,
+                iterations: items,
+            })?;
+
+            let deadline = this.clock.now().saturating_add(registration_period_ms);
+            this.drops.drops.push(RandomDrop {
+                creator: call.caller.clone(),
+                commitment,
+                table: token_ids.into_iter().zip(weights).collect(),
+                deadline,
+                capacity,
+                registrants: Vec::new(),
+                registered: HashSet::new(),
+                assignments: HashMap::new(),
+                seed: None,
+                cancelled: false,
+            });
+            let drop_id = this.drops.drops.len() as u64;
+            let mut event = EventRecord::new(this.next_event_seq(), EventKind::DropCreated, &call.caller);
+            event.amount = Some(items);
+            event.expires_at = Some(deadline);
+            event.value = Some(drop_id.to_string());
+            this.emit(event);
+            Ok(drop_id)
+        })
+    }
+
+    /// Registers the caller for a drop while registration is open. Each account registers
+    /// once, and a drop takes at most as many registrants as the maximum batch size when it
+    /// was created.
+    pub fn register_for_drop(&mut self, caller: &str, drop_id: u64) -> Result<Receipt> {
+        self.enter("register_for_drop", CallKind::User, caller, &[], |this, call| {
+            let drop = this.drop_entry(drop_id)?;
+            if drop.cancelled || this.clock.now() >= drop.deadline {
+                return Err(ContractError::new(ErrorKind::InvalidInput, "Drop registration has closed."));
+            }
+            if drop.registered.contains(&call.caller) {
+                return Err(ContractError::new(ErrorKind::InvalidInput, "Caller is already registered."));
+            }
+            if drop.registrants.len() >= drop.capacity as usize {
+                return Err(ContractError::new(ErrorKind::InvalidInput, "Drop is full."));
+            }
+            this.charge_gas(GasCost {
+                reads: 1,
+                writes: 1,
+                events: 1,
+                ..GasCost::default()
+            })?;
+
+            let drop = &mut this.drops.drops[drop_id as usize - 1];
+            drop.registrants.push(call.caller.clone());
+            drop.registered.insert(call.caller.clone());
+            let mut event = EventRecord::new(this.next_event_seq(), EventKind::DropRegistered, &call.caller);
+            event.value = Some(drop_id.to_string());
+            this.emit(event);
+            Ok(this.receipt(Vec::new()))
+        })
+    }
+
+    /// Reveals a drop's seed once registration has closed and mints every registrant the
+    /// token drawn for it. Only the drop creator can reveal, once; either every registrant
+    /// is minted or, on error, none is.
+    pub fn reveal_drop(&mut self, caller: &str, drop_id: u64, seed: &str) -> Result<Receipt> {
+        self.enter("reveal_drop", CallKind::User, caller, &[], |this, call| {
+            let drop = this.drop_entry(drop_id)?;
+            if drop.creator != call.caller {
+                return Err(ContractError::new(ErrorKind::Unauthorized, "Only the drop creator can reveal it."));
+            }
+            if drop.seed.is_some() {
+                return Err(ContractError::new(ErrorKind::InvalidInput, "Drop has already been revealed."));
+            }
+            if drop.cancelled {
+                return Err(ContractError::new(ErrorKind::InvalidInput, "Drop has been cancelled."));
+            }
+            if this.clock.now() < drop.deadline {
+                return Err(ContractError::new(ErrorKind::InvalidInput, "Drop registration is still open."));
+            }
+            if blake3::hash(seed.as_bytes()).as_bytes() != &drop.commitment {
+                return Err(ContractError::new(ErrorKind::InvalidInput, "Seed does not match the commitment."));
+            }
+
+            let draws: Vec<(String, u32)> = drop
+                .registrants
+                .iter()
+                .enumerate()
+                .map(|(position, registrant)| (registrant.clone(), drop.draw(seed, drop_id, position, registrant)))
+                .collect();
+            let mut minted: HashMap<u32, u64> = HashMap::new();
+            for (_, token_id) in &draws {
+                *minted.entry(*token_id).or_insert(0) += 1;
+            }
+            for (&token_id, &amount) in &minted {
+                this.check_mint(&call.caller, token_id, amount)?;
+            }
+            if draws
+                .iter()
+                .any(|(registrant, token_id)| this.balances.get(registrant, *token_id).checked_add(1).is_none())
+            {
+                return Err(ContractError::new(ErrorKind::Overflow, "Recipient balance overflow."));
+            }
+            let items = draws.len() as u64;
+            this.charge_gas(GasCost {
+                reads: 1 + items,
+                writes: 1 + items,
+                events: 1 + items,
+                iterations: items,
+            })?;
+
+            let mut changes = Vec::with_capacity(draws.len());
+            for (registrant, token_id) in &draws {
+                this.tokens.create(*token_id, &call.caller);
+                changes.extend(this.move_tokens(&call.caller, None, Some(registrant), *token_id, 1)?);
+            }
+            let drop = &mut this.drops.drops[drop_id as usize - 1];
+            drop.assignments = draws.into_iter().collect();
+            drop.seed = Some(seed.to_string());
+            let mut event = EventRecord::new(this.next_event_seq(), EventKind::DropRevealed, &call.caller);
+            event.amount = Some(items);
+            event.value = Some(drop_id.to_string());
+            this.emit(event);
+            Ok(this.receipt(changes))
+        })
+    }
+
+    /// Cancels a drop that has not been revealed, for instance because a token drawn from it
+    /// can no longer be minted. Registration closes and nothing is minted. Only the drop
+    /// creator or an admin can cancel.
+    pub fn cancel_drop(&mut self, caller: &str, drop_id: u64) -> Result<Receipt> {
+        self.enter("cancel_drop", CallKind::User, caller, &[], |this, call| {
+            let drop = this.drop_entry(drop_id)?;
+            if drop.creator != call.caller && !this.access_control.is_admin(&call.caller) {
+                return Err(ContractError::new(
+                    ErrorKind::Unauthorized,
+                    "Only the drop creator or an admin can cancel it.",
+                ));
+            }
+            if drop.seed.is_some() {
+                return Err(ContractError::new(ErrorKind::InvalidInput, "Drop has already been revealed."));
+            }
+            if drop.cancelled {
+                return Err(ContractError::new(ErrorKind::InvalidInput, "Drop has been cancelled."));
+            }
+            this.charge_gas(GasCost {
+                reads: 1,
+                writes: 1,
+                events: 1,
+                ..GasCost::default()
+            })?;
+
+            this.drops.drops[drop_id as usize - 1].cancelled = true;
+            let mut event = EventRecord::new(this.next_event_seq(), EventKind::DropCancelled, &call.caller);
+            event.value = Some(drop_id.to_string());
+            this.emit(event);
+            Ok(this.receipt(Vec::new()))
+        })
+    }
+
+    /// Returns a drop; `undefined` if it does not exist.
+    pub fn drop_info(&self, drop_id: u64) -> Option<DropInfo> {
+        let drop = self.drop_entry(drop_id).ok()?;
+        Some(DropInfo {
+            id: drop_id,
+            creator: drop.creator.clone(),
+            commitment: blake3::Hash::from(drop.commitment).to_hex().to_string(),
+            token_ids: drop.table.iter().map(|(token_id, _)| *token_id).collect(),
+            weights: drop.table.iter().map(|(_, weight)| *weight).collect(),
+            deadline: drop.deadline,
+            registrants: drop.registrants.len() as u32,
+            seed: drop.seed.clone(),
+            cancelled: drop.cancelled,
+        })
+    }
+
+    /// Returns the number of drops created so far.
+    pub fn drop_count(&self) -> u64 {
+        self.drops.drops.len() as u64
+    }
+
+    /// Returns whether `account` registered for a drop.
+    pub fn is_registered_for_drop(&self, drop_id: u64, account: &str) -> bool {
+        self.drop_entry(drop_id)
+            .is_ok_and(|drop| drop.registered.contains(self.resolve_account(account).as_ref()))
+    }
+
+    /// Returns the token ID `account` received from a revealed drop; `undefined` if the drop
+    /// is not revealed yet or the account did not register.
+    pub fn drop_assignment(&self, drop_id: u64, account: &str) -> Option<u32> {
+        let drop = self.drop_entry(drop_id).ok()?;
+        drop.assignments.get(self.resolve_account(account).as_ref()).copied()
+    }
+}
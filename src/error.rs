@@ -0,0 +1,82 @@
+use std::fmt;
+use wasm_bindgen::prelude::*;
+
+/// Category of a contract failure, exposed to JS as the `name` of the thrown `Error`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorKind {
+    Unauthorized,
+    InsufficientBalance,
+    Reentrancy,
+    InvalidInput,
+    TransferRejected,
+    Overflow,
+    Paused,
+    Blacklisted,
+    OutOfGas,
+    /// The token has expired; see `set_token_expiry`.
+    Expired,
+}
+
+impl ErrorKind {
+    /// Returns the stable string code for this kind.
+    pub fn code(&self) -> &'static str {
+        match self {
+            ErrorKind::Unauthorized => "Unauthorized",
+            ErrorKind::InsufficientBalance => "InsufficientBalance",
+            ErrorKind::Reentrancy => "Reentrancy",
+            ErrorKind::InvalidInput => "InvalidInput",
+            ErrorKind::TransferRejected => "TransferRejected",
+            ErrorKind::Overflow => "Overflow",
+            ErrorKind::Paused => "Paused",
+            ErrorKind::Blacklisted => "Blacklisted",
+            ErrorKind::OutOfGas => "OutOfGas",
+            ErrorKind::Expired => "Expired",
+        }
+    }
+}
+
+/// Error returned by every fallible contract call.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContractError {
+    kind: ErrorKind,
+    message: String,
+}
+
+impl ContractError {
+    /// Creates a new error of the given kind with a human-readable message.
+    pub fn new(kind: ErrorKind, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            message: message.into(),
+        }
+    }
+
+    /// Returns the category of the error.
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+
+    /// Returns the human-readable message.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for ContractError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for ContractError {}
+
+impl From<ContractError> for JsValue {
+    fn from(err: ContractError) -> JsValue {
+        let js_err = js_sys::Error::new(&err.message);
+        js_err.set_name(err.kind.code());
+        js_err.into()
+    }
+}
+
+/// Shorthand for results returned by contract calls.
+pub type Result<T> = std::result::Result<T, ContractError>;
@@ -0,0 +1,80 @@
+//! Contract-held balances.
+//!
+//! Tokens locked by the contract itself sit in the balance of [`ESCROW_ACCOUNT`],
+//! so supplies stay accurate while they are locked. Addresses starting with
+//! [`RESERVED_PREFIX`] are rejected by every mutating call, which leaves the
+//! escrow account to the contract alone.
+
+use crate::{BalanceChange, ContractError, ErrorKind, EventKind, EventRecord, Result, ERC1155};
+
+/// Prefix of addresses only the contract itself can act as.
+pub(crate) const RESERVED_PREFIX: &str = "erc1155:";
+/// Holder of every token the contract locks.
+pub(crate) const ESCROW_ACCOUNT: &str = "erc1155:escrow";
+/// Owner of a contract whose ownership was renounced; no caller can act as it.
+pub(crate) const RENOUNCED_OWNER: &str = "erc1155:renounced";
+
+impl ERC1155 {
+    /// Moves `amount` of `token_id` between accounts without any authorization check,
+    /// emitting a `TransferSingle` event. `from: None` mints and `to: None` burns; a burn
+    /// that leaves no supply also emits `SupplyDepleted`. Expired tokens can only be burned.
+    /// Returns the changed balances; nothing changes if an error is returned.
+    pub(crate) fn move_tokens(
+        &mut self,
+        operator: &str,
+        from: Option<&str>,
+        to: Option<&str>,
+        token_id: u32,
+        amount: u64,
+    ) -> Result<Vec<BalanceChange>> {
+        if to.is_some() {
+            self.check_not_expired(token_id)?;
+        }
+        let mut changes = Vec::new();
+        if let Some(from) = from {
+            let before = self.balances.get(from, token_id);
+            let after = before
+                .checked_sub(amount)
+                .ok_or_else(|| ContractError::new(ErrorKind::InsufficientBalance, "Insufficient balance."))?;
+            changes.push(BalanceChange {
+                owner: from.to_string(),
+                token_id,
+                before,
+                after,
+            });
+        }
+        if let Some(to) = to {
+            // Moving to oneself leaves the balance unchanged, so never overflows.
+            let before = match changes.first() {
+                Some(change) if change.owner == to => change.after,
+                _ => self.balances.get(to, token_id),
+            };
+            let after = before
+                .checked_add(amount)
+                .ok_or_else(|| ContractError::new(ErrorKind::Overflow, "Recipient balance overflow."))?;
+            changes.push(BalanceChange {
+                owner: to.to_string(),
+                token_id,
+                before,
+                after,
+            });
+        }
+        for change in &changes {
+            self.balances.set(&change.owner, token_id, change.after);
+        }
+
+        let mut event = EventRecord::new(self.next_event_seq(), EventKind::TransferSingle, operator);
+        event.from = from.map(str::to_string);
+        event.to = to.map(str::to_string);
+        event.token_id = Some(token_id);
+        event.amount = Some(amount);
+        self.emit(event);
+
+        if from.is_some() && to.is_none() && self.balances.total(token_id) == 0 {
+            let mut event = EventRecord::new(self.next_event_seq(), EventKind::SupplyDepleted, operator);
+            event.token_id = Some(token_id);
+            self.emit(event);
+        }
+        Ok(changes)
+    }
+}
@@ -0,0 +1,151 @@
+//! Secondary indexes over the event log.
+//!
+//! Every emitted event is indexed by the accounts it involves, its token ID and
+//! its kind. `query_events` walks the shortest posting list that applies to the
+//! filter instead of the whole log, so per-account and per-token feeds stay
+//! cheap however long the log grows.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use wasm_bindgen::prelude::*;
+
+use crate::{EventKind, EventRecord, ERC1155};
+
+/// Sequence numbers of the events matching each key, ascending. The lists, and the
+/// map of accounts, are shared between forks until written; see `fork`.
+#[derive(Clone, Default)]
+pub(crate) struct EventIndex {
+    by_account: Arc<HashMap<String, Arc<Vec<u64>>>>,
+    by_token: HashMap<u32, Arc<Vec<u64>>>,
+    by_kind: HashMap<EventKind, Arc<Vec<u64>>>,
+}
+
+impl EventIndex {
+    /// Adds `event`, which must be newer than every event indexed so far.
+    pub fn insert(&mut self, event: &EventRecord) {
+        let accounts = [Some(&event.operator), event.from.as_ref(), event.to.as_ref()];
+        for (position, account) in accounts.iter().enumerate() {
+            let Some(account) = account else { continue };
+            // An account listed twice in one event is indexed once.
+            if accounts[..position].contains(&Some(account)) {
+                continue;
+            }
+            let by_account = Arc::make_mut(&mut self.by_account);
+            match by_account.get_mut(account.as_str()) {
+                Some(seqs) => Arc::make_mut(seqs).push(event.seq),
+                None => {
+                    by_account.insert(account.to_string(), Arc::new(vec![event.seq]));
+                }
+            }
+        }
+        if let Some(token_id) = event.token_id {
+            Arc::make_mut(self.by_token.entry(token_id).or_default()).push(event.seq);
+        }
+        Arc::make_mut(self.by_kind.entry(event.kind).or_default()).push(event.seq);
+    }
+
+    /// Removes `event`, which must be the newest event indexed.
+    pub fn remove(&mut self, event: &EventRecord) {
+        let accounts = [Some(&event.operator), event.from.as_ref(), event.to.as_ref()];
+        for (position, account) in accounts.iter().enumerate() {
+            let Some(account) = account else { continue };
+            if accounts[..position].contains(&Some(account)) {
+                continue;
+            }
+            let by_account = Arc::make_mut(&mut self.by_account);
+            if let Some(seqs) = by_account.get_mut(account.as_str()) {
+                Arc::make_mut(seqs).pop();
+                if seqs.is_empty() {
+                    by_account.remove(account.as_str());
+                }
+            }
+        }
+        if let Some(token_id) = event.token_id {
+            pop_posting(&mut self.by_token, token_id);
+        }
+        pop_posting(&mut self.by_kind, event.kind);
+    }
+}
+
+/// Drops the newest entry of the list under `key`, and the list once it is empty.
+fn pop_posting<K: Eq + std::hash::Hash>(lists: &mut HashMap<K, Arc<Vec<u64>>>, key: K) {
+    if let Some(seqs) = lists.get_mut(&key) {
+        Arc::make_mut(seqs).pop();
+        if seqs.is_empty() {
+            lists.remove(&key);
+        }
+    }
+}
+
+/// Criteria for `ERC1155::query_events`; unset fields match everything.
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EventFilter {
+    /// Matches events where the account is the operator, the sender or the recipient.
+    pub account: Option<String>,
+    pub token_id: Option<u32>,
+    pub kind: Option<EventKind>,
+    /// First sequence number to include.
+    pub from_seq: Option<u64>,
+    /// Last sequence number to include.
+    pub to_seq: Option<u64>,
+    /// Maximum number of events to return.
+    pub limit: Option<u32>,
+}
+
+#[wasm_bindgen]
+impl EventFilter {
+    /// Creates a filter that matches every event.
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> EventFilter {
+        EventFilter::default()
+    }
+}
+
+fn event_matches(event: &EventRecord, filter: &EventFilter, account: Option<&str>) -> bool {
+    filter.token_id.is_none_or(|token_id| event.token_id == Some(token_id))
+        && filter.kind.is_none_or(|kind| event.kind == kind)
+        && account.is_none_or(|account| {
+            event.operator == account || event.from.as_deref() == Some(account) || event.to.as_deref() == Some(account)
+        })
+}
+
+#[wasm_bindgen]
+impl ERC1155 {
+    /// Returns the events matching `filter`, oldest first.
+    pub fn query_events(&self, filter: &EventFilter) -> Vec<EventRecord> {
+        let account = filter.account.as_deref().map(|account| self.resolve_account(account));
+        let account = account.as_deref();
+        let from_seq = filter.from_seq.unwrap_or(0);
+        let to_seq = filter.to_seq.unwrap_or(u64::MAX);
+        let limit = filter.limit.map_or(usize::MAX, |limit| limit as usize);
+
+        let index = &self.event_index;
+        let lists = [
+            account.map(|account| index.by_account.get(account)),
+            filter.token_id.map(|token_id| index.by_token.get(&token_id)),
+            filter.kind.map(|kind| index.by_kind.get(&kind)),
+        ];
+        let candidates: Box<dyn Iterator<Item = u64> + '_> = match lists
+            .iter()
+            .flatten()
+            .min_by_key(|seqs| seqs.map_or(0, |seqs| seqs.len()))
+        {
+            // A criterion without any posting list matches nothing.
+            Some(None) => return Vec::new(),
+            Some(Some(seqs)) => {
+                let start = seqs.partition_point(|seq| *seq < from_seq);
+                Box::new(seqs[start..].iter().copied())
+            }
+            None => Box::new(from_seq..self.next_event_seq()),
+        };
+        candidates
+            .take_while(|seq| *seq <= to_seq)
+            .map(|seq| &self.events[seq as usize])
+            .filter(|event| event_matches(event, filter, account))
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+}
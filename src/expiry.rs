@@ -0,0 +1,97 @@
+//! Expiring tokens.
+//!
+//! A token ID can be given an expiry time, after which every balance of it
+//! counts as zero: `balance_of` reports nothing, and it can no longer be
+//! minted, transferred, locked or burned by its holders. The stored balances
+//! keep counting toward the total supply until `sweep_expired` burns them,
+//! which anyone may call; tokens held in escrow, such as a fractionalized NFT,
+//! are left to the feature that holds them. An expired token's expiry can no
+//! longer be changed.
+
+use wasm_bindgen::prelude::*;
+
+use crate::escrow::RESERVED_PREFIX;
+use crate::gas::GasCost;
+use crate::pipeline::CallKind;
+use crate::{ContractError, ErrorKind, EventKind, EventRecord, Receipt, Result, ERC1155};
+
+impl ERC1155 {
+    /// Checks that `token_id` has not expired.
+    pub(crate) fn check_not_expired(&self, token_id: u32) -> Result<()> {
+        if self.is_token_expired(token_id) {
+            return Err(ContractError::new(ErrorKind::Expired, "Token has expired."));
+        }
+        Ok(())
+    }
+}
+
+#[wasm_bindgen]
+impl ERC1155 {
+    /// Makes every balance of `token_id` count as zero from `expires_at`, a contract time in
+    /// milliseconds, or removes the expiry when it is `undefined` (creator or admins only).
+    pub fn set_token_expiry(&mut self, caller: &str, token_id: u32, expires_at: Option<u64>) -> Result<Receipt> {
+        self.enter("set_token_expiry", CallKind::Admin, caller, &[], |this, call| {
+            this.check_token_manager(&call.caller, token_id)?;
+            if this.is_token_expired(token_id) {
+                return Err(ContractError::new(ErrorKind::InvalidInput, "Token has already expired."));
+            }
+            this.charge_gas(GasCost {
+                reads: 2,
+                writes: 1,
+                events: 1,
+                ..GasCost::default()
+            })?;
+
+            this.token_config_mut(token_id).expires_at = expires_at;
+            let mut event = EventRecord::new(this.next_event_seq(), EventKind::TokenExpiryUpdated, &call.caller);
+            event.token_id = Some(token_id);
+            event.expires_at = expires_at;
+            this.emit(event);
+            Ok(this.receipt(Vec::new()))
+        })
+    }
+
+    /// Returns the expiry of `token_id` in milliseconds, if any.
+    pub fn token_expiry(&self, token_id: u32) -> Option<u64> {
+        self.tokens.get(token_id).and_then(|config| config.expires_at)
+    }
+
+    /// Returns whether `token_id` has expired.
+    pub fn is_token_expired(&self, token_id: u32) -> bool {
+        self.token_expiry(token_id)
+            .is_some_and(|expires_at| self.clock.now() >= expires_at)
+    }
+
+    /// Burns the remaining balances of expired `token_id`, up to `limit` holders at a
+    /// time in account order, except those of reserved accounts such as the escrow;
+    /// call again until it reports no balance changes.
+    pub fn sweep_expired(&mut self, caller: &str, token_id: u32, limit: usize) -> Result<Receipt> {
+        self.enter("sweep_expired", CallKind::User, caller, &[], |this, call| {
+            if !this.is_token_expired(token_id) {
+                return Err(ContractError::new(ErrorKind::InvalidInput, "Token has not expired."));
+            }
+            // Reserved accounts, such as the escrow, hold tokens on behalf of others.
+            let mut holders: Vec<(String, u64)> = this
+                .balances
+                .holders(token_id)
+                .filter(|(owner, _)| !owner.starts_with(RESERVED_PREFIX))
+                .map(|(owner, balance)| (owner.into_owned(), balance))
+                .collect();
+            holders.sort_unstable();
+            holders.truncate(limit);
+            let count = holders.len() as u64;
+            this.charge_gas(GasCost {
+                reads: 1 + count,
+                writes: count,
+                events: count + 1,
+                iterations: count,
+            })?;
+
+            let mut changes = Vec::new();
+            for (owner, balance) in holders {
+                changes.extend(this.move_tokens(&call.caller, Some(&owner), None, token_id, balance)?);
+            }
+            Ok(this.receipt(changes))
+        })
+    }
+}
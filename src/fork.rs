@@ -0,0 +1,101 @@
+//! Cheap state forking.
+//!
+//! The large state tables are reference counted: the holders and history of
+//! each token, the approvals of each owner, the attributes of each token, the
+//! event index posting lists, and the event and operation logs, which are
+//! stored in fixed-size chunks. Cloning the contract only bumps those counts,
+//! and the first write to a shared table copies that table alone, so a fork
+//! costs about as much as the tables it later modifies. The same applies to
+//! the checkpoint taken by `begin_transaction`.
+
+use std::ops::{Index, Range};
+use std::sync::Arc;
+
+use wasm_bindgen::prelude::*;
+
+use crate::ERC1155;
+
+/// Number of entries per chunk of a [`Log`].
+const CHUNK_LEN: usize = 1024;
+
+/// Append-only log whose full chunks are shared between clones.
+#[derive(Clone)]
+pub(crate) struct Log<T> {
+    chunks: Vec<Arc<Vec<T>>>,
+}
+
+impl<T> Default for Log<T> {
+    fn default() -> Self {
+        Self { chunks: Vec::new() }
+    }
+}
+
+impl<T: Clone> Log<T> {
+    pub fn len(&self) -> usize {
+        self.chunks
+            .last()
+            .map_or(0, |last| (self.chunks.len() - 1) * CHUNK_LEN + last.len())
+    }
+
+    /// Appends `entry`; only the last, partly filled chunk is ever copied.
+    pub fn push(&mut self, entry: T) {
+        match self.chunks.last_mut() {
+            Some(last) if last.len() < CHUNK_LEN => Arc::make_mut(last).push(entry),
+            _ => {
+                let mut chunk = Vec::with_capacity(CHUNK_LEN);
+                chunk.push(entry);
+                self.chunks.push(Arc::new(chunk));
+            }
+        }
+    }
+
+    /// Drops the entries from `len` on; only the chunk cut in two is copied.
+    pub fn truncate(&mut self, len: usize) {
+        self.chunks.truncate(len.div_ceil(CHUNK_LEN));
+        if let Some(last) = self.chunks.last_mut() {
+            if !len.is_multiple_of(CHUNK_LEN) {
+                Arc::make_mut(last).truncate(len % CHUNK_LEN);
+            }
+        }
+    }
+
+    pub fn last_mut(&mut self) -> Option<&mut T> {
+        self.chunks.last_mut().and_then(|last| Arc::make_mut(last).last_mut())
+    }
+
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.chunks.get(index / CHUNK_LEN)?.get(index % CHUNK_LEN)
+    }
+
+    /// Iterates over the entries from `start` on.
+    pub fn iter_from(&self, start: usize) -> impl Iterator<Item = &T> + Clone + '_ {
+        self.chunks
+            .iter()
+            .skip(start / CHUNK_LEN)
+            .flat_map(|chunk| chunk.iter())
+            .skip(start % CHUNK_LEN)
+    }
+
+    /// Iterates over the entries in `range`, which must be within the log.
+    pub fn range(&self, range: Range<usize>) -> impl Iterator<Item = &T> + Clone + '_ {
+        self.iter_from(range.start).take(range.len())
+    }
+}
+
+impl<T> Index<usize> for Log<T> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        &self.chunks[index / CHUNK_LEN][index % CHUNK_LEN]
+    }
+}
+
+#[wasm_bindgen]
+impl ERC1155 {
+    /// Returns an independent copy of the contract that shares every table neither side
+    /// has written since, for what-if simulations. Events, operations, failure counters
+    /// and any open transaction are copied as well.
+    pub fn fork(&self) -> ERC1155 {
+        self.clone()
+    }
+}
@@ -0,0 +1,148 @@
+//! NFT fractionalization.
+//!
+//! `fractionalize` locks an NFT in escrow and mints a fixed number of fungible
+//! shares to its holder. The shares trade like any other token; whoever gathers
+//! all of them can `redeem` the NFT, which burns the shares. An NFT that expires
+//! in escrow is burned by `redeem` instead of released, so its shares are never
+//! left tied to a token no one can receive.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::escrow::ESCROW_ACCOUNT;
+use crate::gas::GasCost;
+use crate::pipeline::CallKind;
+use crate::split_id::is_nft_item;
+use crate::{ContractError, ErrorKind, Receipt, Result, ERC1155};
+
+/// An escrowed NFT and the shares it was split into.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Fraction {
+    #[wasm_bindgen(readonly)]
+    pub share_token_id: u32,
+    #[wasm_bindgen(readonly)]
+    pub total_shares: u64,
+}
+
+/// Fractionalized NFTs, indexed both ways.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub(crate) struct Fractions {
+    by_nft: HashMap<u32, Fraction>,
+    /// Share token ID -> NFT ID.
+    by_share: HashMap<u32, u32>,
+}
+
+impl Fractions {
+    /// Returns whether `token_id` is the share token of an escrowed NFT.
+    pub fn is_share(&self, token_id: u32) -> bool {
+        self.by_share.contains_key(&token_id)
+    }
+}
+
+#[wasm_bindgen]
+impl ERC1155 {
+    /// Locks the caller's NFT in escrow and mints `total_shares` of a new fungible token to them.
+    /// # Parameters
+    /// - `nft_id`: An NFT held by the caller.
+    /// - `share_token_id`: An unused fungible token ID for the shares. No one can mint more
+    ///   of it while the NFT is escrowed.
+    /// - `total_shares`: How many shares to mint; all of them are needed to redeem the NFT.
+    pub fn fractionalize(
+        &mut self,
+        caller: &str,
+        nft_id: u32,
+        share_token_id: u32,
+        total_shares: u64,
+    ) -> Result<Receipt> {
+        self.enter("fractionalize", CallKind::User, caller, &[], |this, call| {
+            if !is_nft_item(nft_id) {
+                return Err(ContractError::new(ErrorKind::InvalidInput, "Only NFTs can be fractionalized."));
+            }
+            if this.balances.get(&call.caller, nft_id) == 0 {
+                return Err(ContractError::new(ErrorKind::InsufficientBalance, "Caller does not hold the NFT."));
+            }
+            if !this.is_fungible(share_token_id) || this.tokens.get(share_token_id).is_some() {
+                return Err(ContractError::new(
+                    ErrorKind::InvalidInput,
+                    "Shares need an unused fungible token ID.",
+                ));
+            }
+            if total_shares == 0 {
+                return Err(ContractError::new(ErrorKind::InvalidInput, "Total shares must be positive."));
+            }
+            this.charge_gas(GasCost {
+                reads: 3,
+                writes: 4,
+                events: 2,
+                ..GasCost::default()
+            })?;
+
+            let mut changes = this.move_tokens(&call.caller, Some(&call.caller), Some(ESCROW_ACCOUNT), nft_id, 1)?;
+            this.tokens.create(share_token_id, ESCROW_ACCOUNT);
+            changes.extend(this.move_tokens(&call.caller, None, Some(&call.caller), share_token_id, total_shares)?);
+            this.fractions.by_nft.insert(
+                nft_id,
+                Fraction {
+                    share_token_id,
+                    total_shares,
+                },
+            );
+            this.fractions.by_share.insert(share_token_id, nft_id);
+            Ok(this.receipt(changes))
+        })
+    }
+
+    /// Burns all shares of an escrowed NFT held by the caller and releases the NFT to them,
+    /// or burns the NFT as well if it has expired.
+    pub fn redeem(&mut self, caller: &str, nft_id: u32) -> Result<Receipt> {
+        self.enter("redeem", CallKind::User, caller, &[], |this, call| {
+            let fraction = this
+                .fractions
+                .by_nft
+                .get(&nft_id)
+                .copied()
+                .ok_or_else(|| ContractError::new(ErrorKind::InvalidInput, "NFT is not fractionalized."))?;
+            if this.balances.get(&call.caller, fraction.share_token_id) < fraction.total_shares {
+                return Err(ContractError::new(
+                    ErrorKind::InsufficientBalance,
+                    "Redeeming requires every share of the NFT.",
+                ));
+            }
+            // An expired NFT can only be burned.
+            let recipient = (!this.is_token_expired(nft_id)).then(|| call.caller.clone());
+            // Check what could fail when releasing the NFT before burning any share.
+            if let Some(recipient) = &recipient {
+                this.balances
+                    .get(recipient, nft_id)
+                    .checked_add(1)
+                    .ok_or_else(|| ContractError::new(ErrorKind::Overflow, "Recipient balance overflow."))?;
+            }
+            this.charge_gas(GasCost {
+                reads: 2,
+                writes: 4,
+                events: 2,
+                ..GasCost::default()
+            })?;
+
+            let mut changes = this.move_tokens(
+                &call.caller,
+                Some(&call.caller),
+                None,
+                fraction.share_token_id,
+                fraction.total_shares,
+            )?;
+            changes.extend(this.move_tokens(&call.caller, Some(ESCROW_ACCOUNT), recipient.as_deref(), nft_id, 1)?);
+            this.fractions.by_nft.remove(&nft_id);
+            this.fractions.by_share.remove(&fraction.share_token_id);
+            Ok(this.receipt(changes))
+        })
+    }
+
+    /// Returns the shares an escrowed NFT was split into; `undefined` if it is not fractionalized.
+    pub fn fraction_of(&self, nft_id: u32) -> Option<Fraction> {
+        self.fractions.by_nft.get(&nft_id).copied()
+    }
+}
@@ -0,0 +1,179 @@
+//! Optional simulated gas metering.
+//!
+//! When a schedule is set, every call through the pipeline starts at `base` gas
+//! and each method charges its storage reads, writes, emitted events and loop
+//! iterations before it changes any state, so running out of gas never leaves a
+//! call half-applied. Costs depend only on the shape of the call (how many
+//! items, accounts, ...), which keeps them deterministic across replicas.
+//! Configuration calls, by the owner, admins or token creators, are never
+//! metered, so a limit that is too low can always be raised again.
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::pipeline::CallKind;
+use crate::{ContractError, ErrorKind, EventKind, EventRecord, Receipt, Result, ERC1155};
+
+/// Gas units charged per unit of work.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GasSchedule {
+    /// Charged once per call.
+    pub base: u64,
+    /// Per storage read.
+    pub read: u64,
+    /// Per storage write.
+    pub write: u64,
+    /// Per emitted event.
+    pub event: u64,
+    /// Per loop iteration over call inputs.
+    pub iteration: u64,
+}
+
+#[wasm_bindgen]
+impl GasSchedule {
+    /// Creates a schedule loosely modeled on EVM costs.
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> GasSchedule {
+        GasSchedule::default()
+    }
+}
+
+impl Default for GasSchedule {
+    fn default() -> Self {
+        Self {
+            base: 21_000,
+            read: 2_100,
+            write: 20_000,
+            event: 1_500,
+            iteration: 100,
+        }
+    }
+}
+
+/// Work done by a call, converted to gas with the active schedule.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct GasCost {
+    pub reads: u64,
+    pub writes: u64,
+    pub events: u64,
+    pub iterations: u64,
+}
+
+/// Gas configuration and the usage of the current (or last) call.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub(crate) struct GasMeter {
+    schedule: Option<GasSchedule>,
+    limit: Option<u64>,
+    /// Whether the current call is metered.
+    #[serde(skip)]
+    active: bool,
+    #[serde(skip)]
+    used: u64,
+}
+
+impl GasMeter {
+    /// Starts metering a new call.
+    pub fn start(&mut self, kind: CallKind) -> Result<()> {
+        self.used = 0;
+        self.active = kind == CallKind::User && self.schedule.is_some();
+        let base = self.schedule.map_or(0, |schedule| schedule.base);
+        self.consume(base)
+    }
+
+    /// Charges `cost`, failing without consuming it if the limit would be exceeded.
+    pub fn charge(&mut self, cost: GasCost) -> Result<()> {
+        let schedule = match self.schedule {
+            Some(schedule) if self.active => schedule,
+            _ => return Ok(()),
+        };
+        let gas = [
+            (cost.reads, schedule.read),
+            (cost.writes, schedule.write),
+            (cost.events, schedule.event),
+            (cost.iterations, schedule.iteration),
+        ]
+        .iter()
+        .fold(0u64, |total, (units, price)| total.saturating_add(units.saturating_mul(*price)));
+        self.consume(gas)
+    }
+
+    fn consume(&mut self, gas: u64) -> Result<()> {
+        if !self.active {
+            return Ok(());
+        }
+        let used = self.used.saturating_add(gas);
+        if let Some(limit) = self.limit {
+            if used > limit {
+                return Err(ContractError::new(
+                    ErrorKind::OutOfGas,
+                    format!("Out of gas: {} needed, limit is {}.", used, limit),
+                ));
+            }
+        }
+        self.used = used;
+        Ok(())
+    }
+
+    /// Gas consumed by the current call so far.
+    pub fn used(&self) -> u64 {
+        self.used
+    }
+}
+
+#[wasm_bindgen]
+impl ERC1155 {
+    /// Enables gas metering with `schedule`, or disables it when `undefined` (owner only).
+    pub fn set_gas_schedule(&mut self, caller: &str, schedule: Option<GasSchedule>) -> Result<Receipt> {
+        self.enter("set_gas_schedule", CallKind::Admin, caller, &[], |this, call| {
+            if !this.access_control.is_owner(&call.caller) {
+                return Err(ContractError::new(ErrorKind::Unauthorized, "Only the owner can configure gas."));
+            }
+
+            this.gas.schedule = schedule;
+            let mut event = EventRecord::new(this.next_event_seq(), EventKind::GasScheduleUpdated, &call.caller);
+            event.value = schedule.map(|schedule| {
+                serde_json::to_string(&schedule).expect("gas schedules are always serializable")
+            });
+            this.emit(event);
+            Ok(this.receipt(Vec::new()))
+        })
+    }
+
+    /// Sets the per-call gas limit, or removes it when `undefined` (owner only).
+    pub fn set_gas_limit(&mut self, caller: &str, limit: Option<u64>) -> Result<Receipt> {
+        self.enter("set_gas_limit", CallKind::Admin, caller, &[], |this, call| {
+            if !this.access_control.is_owner(&call.caller) {
+                return Err(ContractError::new(ErrorKind::Unauthorized, "Only the owner can configure gas."));
+            }
+
+            this.gas.limit = limit;
+            let mut event = EventRecord::new(this.next_event_seq(), EventKind::GasLimitUpdated, &call.caller);
+            event.amount = limit;
+            this.emit(event);
+            Ok(this.receipt(Vec::new()))
+        })
+    }
+
+    /// Returns the active gas schedule, if metering is enabled.
+    pub fn gas_schedule(&self) -> Option<GasSchedule> {
+        self.gas.schedule
+    }
+
+    /// Returns the per-call gas limit, if any.
+    pub fn gas_limit(&self) -> Option<u64> {
+        self.gas.limit
+    }
+
+    /// Returns the gas consumed by the most recent call, including failed ones.
+    pub fn gas_used(&self) -> u64 {
+        self.gas.used()
+    }
+}
+
+impl ERC1155 {
+    /// Charges the work a call is about to do; must run before the call changes state.
+    pub(crate) fn charge_gas(&mut self, cost: GasCost) -> Result<()> {
+        self.gas.charge(cost)
+    }
+}
@@ -0,0 +1,223 @@
+//! Snapshot-weighted polls.
+//!
+//! A proposal names a weight token and a snapshot; each account votes once with
+//! the balance it held when the snapshot was taken, so tokens moved after it
+//! cannot vote twice. A proposal passes once voting has closed, the votes cast
+//! reach its quorum, and more weight supports it than opposes it.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::gas::GasCost;
+use crate::pipeline::CallKind;
+use crate::{ContractError, ErrorKind, EventKind, EventRecord, Receipt, Result, ERC1155};
+
+/// A proposal and its tallies.
+#[derive(Clone, Serialize, Deserialize)]
+pub(crate) struct Proposal {
+    proposer: String,
+    description: String,
+    token_id: u32,
+    snapshot_id: u64,
+    quorum: u64,
+    deadline: u64,
+    votes_for: u64,
+    votes_against: u64,
+    /// Voter -> whether it supports the proposal.
+    voters: HashMap<String, bool>,
+}
+
+/// Proposals, indexed by ID minus one.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub(crate) struct Governance {
+    proposals: Vec<Proposal>,
+}
+
+/// Read-only view of a proposal.
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProposalInfo {
+    #[wasm_bindgen(readonly)]
+    pub id: u64,
+    #[wasm_bindgen(readonly)]
+    pub proposer: String,
+    #[wasm_bindgen(readonly)]
+    pub description: String,
+    /// Token whose snapshot balances weigh the votes.
+    #[wasm_bindgen(readonly)]
+    pub token_id: u32,
+    #[wasm_bindgen(readonly)]
+    pub snapshot_id: u64,
+    /// Total weight that must be cast, for or against, for the proposal to pass.
+    #[wasm_bindgen(readonly)]
+    pub quorum: u64,
+    /// When voting closes, in contract milliseconds.
+    #[wasm_bindgen(readonly)]
+    pub deadline: u64,
+    #[wasm_bindgen(readonly)]
+    pub votes_for: u64,
+    #[wasm_bindgen(readonly)]
+    pub votes_against: u64,
+    #[wasm_bindgen(readonly)]
+    pub voters: u32,
+}
+
+impl ERC1155 {
+    fn proposal_entry(&self, proposal_id: u64) -> Result<&Proposal> {
+        proposal_id
+            .checked_sub(1)
+            .and_then(|index| self.governance.proposals.get(index as usize))
+            .ok_or_else(|| ContractError::new(ErrorKind::InvalidInput, "Proposal does not exist."))
+    }
+}
+
+#[wasm_bindgen]
+impl ERC1155 {
+    /// Opens a poll weighted by balances of `token_id` at `snapshot_id` and returns its ID.
+    /// Anyone with weight in the snapshot can propose, as can the owner and admins.
+    /// # Parameters
+    /// - `quorum`: Total weight that must be cast for the proposal to pass.
+    /// - `voting_period_ms`: How long voting stays open.
+    pub fn create_proposal(
+        &mut self,
+        caller: &str,
+        description: &str,
+        token_id: u32,
+        snapshot_id: u64,
+        quorum: u64,
+        voting_period_ms: u64,
+    ) -> Result<u64> {
+        self.enter("create_proposal", CallKind::User, caller, &[], |this, call| {
+            this.check_snapshot(snapshot_id)?;
+            let privileged = this.access_control.is_owner(&call.caller) || this.access_control.is_admin(&call.caller);
+            if !privileged && this.balances.get_at(&call.caller, token_id, snapshot_id) == 0 {
+                return Err(ContractError::new(
+                    ErrorKind::Unauthorized,
+                    "Caller has no voting weight in the snapshot.",
+                ));
+            }
+            if description.trim().is_empty() || voting_period_ms == 0 {
+                return Err(ContractError::new(
+                    ErrorKind::InvalidInput,
+                    "Proposals need a description and a voting period.",
+                ));
+            }
+            this.charge_gas(GasCost {
+                reads: 1,
+                writes: 1,
+                events: 1,
+                ..GasCost::default()
+            })?;
+
+            let deadline = this.clock.now().saturating_add(voting_period_ms);
+            this.governance.proposals.push(Proposal {
+                proposer: call.caller.clone(),
+                description: description.to_string(),
+                token_id,
+                snapshot_id,
+                quorum,
+                deadline,
+                votes_for: 0,
+                votes_against: 0,
+                voters: HashMap::new(),
+            });
+            let proposal_id = this.governance.proposals.len() as u64;
+            let mut event = EventRecord::new(this.next_event_seq(), EventKind::ProposalCreated, &call.caller);
+            event.token_id = Some(token_id);
+            event.amount = Some(quorum);
+            event.expires_at = Some(deadline);
+            event.value = Some(proposal_id.to_string());
+            this.emit(event);
+            Ok(proposal_id)
+        })
+    }
+
+    /// Casts the caller's snapshot weight for or against a proposal. Each account votes once,
+    /// while voting is open.
+    pub fn vote(&mut self, caller: &str, proposal_id: u64, support: bool) -> Result<Receipt> {
+        self.enter("vote", CallKind::User, caller, &[], |this, call| {
+            let proposal = this.proposal_entry(proposal_id)?;
+            if this.clock.now() >= proposal.deadline {
+                return Err(ContractError::new(ErrorKind::InvalidInput, "Voting has closed."));
+            }
+            if proposal.voters.contains_key(&call.caller) {
+                return Err(ContractError::new(ErrorKind::InvalidInput, "Caller has already voted."));
+            }
+            let (token_id, snapshot_id) = (proposal.token_id, proposal.snapshot_id);
+            let weight = this.balances.get_at(&call.caller, token_id, snapshot_id);
+            if weight == 0 {
+                return Err(ContractError::new(
+                    ErrorKind::Unauthorized,
+                    "Caller has no voting weight in the snapshot.",
+                ));
+            }
+            this.charge_gas(GasCost {
+                reads: 2,
+                writes: 2,
+                events: 1,
+                ..GasCost::default()
+            })?;
+
+            let proposal = &mut this.governance.proposals[proposal_id as usize - 1];
+            if support {
+                proposal.votes_for = proposal.votes_for.saturating_add(weight);
+            } else {
+                proposal.votes_against = proposal.votes_against.saturating_add(weight);
+            }
+            proposal.voters.insert(call.caller.clone(), support);
+            let mut event = EventRecord::new(this.next_event_seq(), EventKind::VoteCast, &call.caller);
+            event.token_id = Some(token_id);
+            event.amount = Some(weight);
+            event.approved = Some(support);
+            event.value = Some(proposal_id.to_string());
+            this.emit(event);
+            Ok(this.receipt(Vec::new()))
+        })
+    }
+
+    /// Returns a proposal with its current tallies; `undefined` if it does not exist.
+    pub fn proposal(&self, proposal_id: u64) -> Option<ProposalInfo> {
+        let proposal = self.proposal_entry(proposal_id).ok()?;
+        Some(ProposalInfo {
+            id: proposal_id,
+            proposer: proposal.proposer.clone(),
+            description: proposal.description.clone(),
+            token_id: proposal.token_id,
+            snapshot_id: proposal.snapshot_id,
+            quorum: proposal.quorum,
+            deadline: proposal.deadline,
+            votes_for: proposal.votes_for,
+            votes_against: proposal.votes_against,
+            voters: proposal.voters.len() as u32,
+        })
+    }
+
+    /// Returns the number of proposals created so far.
+    pub fn proposal_count(&self) -> u64 {
+        self.governance.proposals.len() as u64
+    }
+
+    /// Returns how `account` voted on a proposal: `true` for, `false` against, `undefined` if
+    /// it did not vote.
+    pub fn vote_of(&self, proposal_id: u64, account: &str) -> Option<bool> {
+        let proposal = self.proposal_entry(proposal_id).ok()?;
+        proposal.voters.get(self.resolve_account(account).as_ref()).copied()
+    }
+
+    /// Returns whether the votes cast on a proposal, for and against, reach its quorum.
+    pub fn quorum_reached(&self, proposal_id: u64) -> Result<bool> {
+        let proposal = self.proposal_entry(proposal_id)?;
+        Ok(proposal.votes_for.saturating_add(proposal.votes_against) >= proposal.quorum)
+    }
+
+    /// Returns whether a proposal has passed: voting has closed, the quorum was reached,
+    /// and more weight voted for it than against it.
+    pub fn proposal_passed(&self, proposal_id: u64) -> Result<bool> {
+        let proposal = self.proposal_entry(proposal_id)?;
+        Ok(self.clock.now() >= proposal.deadline
+            && self.quorum_reached(proposal_id)?
+            && proposal.votes_for > proposal.votes_against)
+    }
+}
@@ -0,0 +1,103 @@
+use std::ops::{Deref, DerefMut};
+
+use crate::audit::PendingOperation;
+use crate::{ContractError, ErrorKind, Result, ERC1155};
+
+/// Reentrancy guard to prevent reentrancy attacks.
+///
+/// The guard is only ever locked through `ERC1155::guarded`, whose handle
+/// unlocks it on drop, so no return path can leave the contract locked.
+#[derive(Clone)]
+pub struct ReentrancyGuard {
+    is_locked: bool,
+}
+
+impl ReentrancyGuard {
+    /// Creates a new reentrancy guard.
+    pub fn new() -> Self {
+        Self { is_locked: false }
+    }
+
+    /// Returns whether a guarded call is currently in progress.
+    pub fn is_locked(&self) -> bool {
+        self.is_locked
+    }
+
+    /// Locks the guard, preventing reentrant calls.
+    fn enter(&mut self) -> Result<()> {
+        if self.is_locked {
+            return Err(ContractError::new(ErrorKind::Reentrancy, "Reentrancy detected."));
+        }
+        self.is_locked = true;
+        Ok(())
+    }
+
+    /// Unlocks the guard, allowing further function execution.
+    fn exit(&mut self) {
+        self.is_locked = false;
+    }
+}
+
+impl Default for ReentrancyGuard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Exclusive access to a contract while its reentrancy guard is held.
+pub(crate) struct Guarded<'a> {
+    contract: &'a mut ERC1155,
+    /// Audit entry of the call in progress; set by `ERC1155::enter` and ended by `finish`.
+    pub(crate) operation: Option<PendingOperation>,
+}
+
+impl Deref for Guarded<'_> {
+    type Target = ERC1155;
+
+    fn deref(&self) -> &ERC1155 {
+        self.contract
+    }
+}
+
+impl DerefMut for Guarded<'_> {
+    fn deref_mut(&mut self) -> &mut ERC1155 {
+        self.contract
+    }
+}
+
+impl Guarded<'_> {
+    /// Ends the operation opened by `ERC1155::enter` with the outcome of its method: a
+    /// success is recorded in the audit log, and a failure in the error statistics after
+    /// the events it emitted before failing are discarded.
+    pub(crate) fn finish<T>(&mut self, result: &Result<T>) {
+        let Some(operation) = self.operation.take() else { return };
+        match result {
+            Ok(_) => self.contract.finish_operation(operation),
+            Err(err) => {
+                self.contract.discard_events_from(operation.first_event_seq);
+                self.contract.error_stats.record(operation.method, err.kind());
+            }
+        }
+    }
+}
+
+impl Drop for Guarded<'_> {
+    fn drop(&mut self) {
+        self.contract.reentrancy_guard.exit();
+    }
+}
+
+impl ERC1155 {
+    /// Locks the reentrancy guard until the returned handle is dropped.
+    ///
+    /// This is the last stage of `ERC1155::enter`; public mutating methods then
+    /// work through the handle or delegate to an `*_unguarded` body shared with
+    /// other methods.
+    pub(crate) fn guarded(&mut self) -> Result<Guarded<'_>> {
+        self.reentrancy_guard.enter()?;
+        Ok(Guarded {
+            contract: self,
+            operation: None,
+        })
+    }
+}
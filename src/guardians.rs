@@ -0,0 +1,210 @@
+//! Guardian-based ownership recovery.
+//!
+//! The owner names a set of guardians and a quorum. If the owner key is lost,
+//! guardians back a new owner with `support_recovery`; once `threshold` of them
+//! agree the recovery starts, and after `delay_ms` anyone can complete it with
+//! `execute_recovery`. Until then the owner can `cancel_recovery`.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::pipeline::{normalize_account, CallKind};
+use crate::{ContractError, ErrorKind, EventKind, EventRecord, Receipt, Result, ERC1155};
+
+/// Guardian configuration and the recovery in progress.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub(crate) struct Guardians {
+    members: BTreeSet<String>,
+    threshold: u32,
+    delay_ms: u64,
+    /// Guardian -> the new owner it supports.
+    votes: BTreeMap<String, String>,
+    /// Started once a quorum agrees: `(new_owner, executable_at)`.
+    pending: Option<(String, u64)>,
+}
+
+impl Guardians {
+    fn supporters(&self, new_owner: &str) -> u32 {
+        self.votes.values().filter(|candidate| *candidate == new_owner).count() as u32
+    }
+
+    /// Forgets every vote and the pending recovery.
+    pub(crate) fn reset(&mut self) {
+        self.votes.clear();
+        self.pending = None;
+    }
+}
+
+/// State of an ownership recovery.
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecoveryStatus {
+    #[wasm_bindgen(readonly)]
+    pub new_owner: String,
+    /// Number of guardians backing `new_owner`.
+    #[wasm_bindgen(readonly)]
+    pub supporters: u32,
+    #[wasm_bindgen(readonly)]
+    pub threshold: u32,
+    /// When the recovery can be executed; `undefined` until a quorum agrees.
+    #[wasm_bindgen(readonly)]
+    pub executable_at: Option<u64>,
+}
+
+#[wasm_bindgen]
+impl ERC1155 {
+    /// Replaces the guardian set (only the owner can set guardians). Clears any recovery in progress.
+    /// # Parameters
+    /// - `guardians`: Guardian addresses or `@name`s; the owner cannot be one of them.
+    /// - `threshold`: How many guardians must agree, between 1 and the number of guardians.
+    ///   Zero guardians with a zero threshold disables recovery.
+    /// - `delay_ms`: Time between reaching the quorum and the recovery becoming executable.
+    pub fn set_guardians(
+        &mut self,
+        caller: &str,
+        guardians: Vec<String>,
+        threshold: u32,
+        delay_ms: u64,
+    ) -> Result<Receipt> {
+        self.enter("set_guardians", CallKind::Admin, caller, &[], |this, call| {
+            if !this.access_control.is_owner(&call.caller) {
+                return Err(ContractError::new(ErrorKind::Unauthorized, "Only the owner can set guardians."));
+            }
+            this.check_batch_size(guardians.len())?;
+            let members = guardians
+                .iter()
+                .map(|guardian| this.resolve_name(normalize_account(guardian)?))
+                .collect::<Result<BTreeSet<String>>>()?;
+            if members.contains(&call.caller) {
+                return Err(ContractError::new(ErrorKind::InvalidInput, "The owner cannot be a guardian."));
+            }
+            if threshold as usize > members.len() || (threshold == 0) != members.is_empty() {
+                return Err(ContractError::new(
+                    ErrorKind::InvalidInput,
+                    "Threshold must be between 1 and the number of guardians.",
+                ));
+            }
+
+            this.guardians = Guardians {
+                members,
+                threshold,
+                delay_ms,
+                ..Default::default()
+            };
+            let mut event = EventRecord::new(this.next_event_seq(), EventKind::GuardiansUpdated, &call.caller);
+            event.amount = Some(u64::from(threshold));
+            this.emit(event);
+            Ok(this.receipt(Vec::new()))
+        })
+    }
+
+    /// Backs `new_owner` as the recovered owner (guardians only). Starts the recovery
+    /// delay when the quorum is reached.
+    pub fn support_recovery(&mut self, caller: &str, new_owner: &str) -> Result<Receipt> {
+        self.enter("support_recovery", CallKind::Admin, caller, &[new_owner], |this, call| {
+            let (guardian, new_owner) = (&call.caller, &call.accounts[0]);
+            if !this.guardians.members.contains(guardian) {
+                return Err(ContractError::new(ErrorKind::Unauthorized, "Caller is not a guardian."));
+            }
+            if this.access_control.is_owner(new_owner) {
+                return Err(ContractError::new(ErrorKind::InvalidInput, "Account is already the owner."));
+            }
+            if let Some((pending_owner, _)) = &this.guardians.pending {
+                if pending_owner != new_owner {
+                    return Err(ContractError::new(
+                        ErrorKind::InvalidInput,
+                        "Another recovery is already in progress.",
+                    ));
+                }
+            }
+
+            this.guardians.votes.insert(guardian.clone(), new_owner.clone());
+            let mut event = EventRecord::new(this.next_event_seq(), EventKind::RecoverySupported, guardian);
+            event.to = Some(new_owner.clone());
+            this.emit(event);
+
+            if this.guardians.pending.is_none() && this.guardians.supporters(new_owner) >= this.guardians.threshold {
+                let executable_at = this.clock.now().saturating_add(this.guardians.delay_ms);
+                this.guardians.pending = Some((new_owner.clone(), executable_at));
+                let mut event = EventRecord::new(this.next_event_seq(), EventKind::RecoveryInitiated, guardian);
+                event.to = Some(new_owner.clone());
+                event.expires_at = Some(executable_at);
+                this.emit(event);
+            }
+            Ok(this.receipt(Vec::new()))
+        })
+    }
+
+    /// Cancels the recovery in progress and clears all guardian votes (only the owner can cancel).
+    pub fn cancel_recovery(&mut self, caller: &str) -> Result<Receipt> {
+        self.enter("cancel_recovery", CallKind::Admin, caller, &[], |this, call| {
+            if !this.access_control.is_owner(&call.caller) {
+                return Err(ContractError::new(ErrorKind::Unauthorized, "Only the owner can cancel a recovery."));
+            }
+            if this.guardians.votes.is_empty() {
+                return Err(ContractError::new(ErrorKind::InvalidInput, "No recovery is in progress."));
+            }
+
+            this.guardians.reset();
+            let event = EventRecord::new(this.next_event_seq(), EventKind::RecoveryCancelled, &call.caller);
+            this.emit(event);
+            Ok(this.receipt(Vec::new()))
+        })
+    }
+
+    /// Hands ownership to the recovered owner once the delay has passed (callable by anyone).
+    /// Admins are kept.
+    pub fn execute_recovery(&mut self, caller: &str) -> Result<Receipt> {
+        self.enter("execute_recovery", CallKind::Admin, caller, &[], |this, call| {
+            let (new_owner, executable_at) = this
+                .guardians
+                .pending
+                .clone()
+                .ok_or_else(|| ContractError::new(ErrorKind::InvalidInput, "No recovery has reached its quorum."))?;
+            if this.clock.now() < executable_at {
+                return Err(ContractError::new(ErrorKind::InvalidInput, "The recovery delay has not passed yet."));
+            }
+
+            let previous = std::mem::replace(&mut this.access_control.owner, new_owner.clone());
+            this.guardians.members.remove(&new_owner);
+            this.guardians.threshold = this.guardians.threshold.min(this.guardians.members.len() as u32);
+            this.guardians.reset();
+            let mut event = EventRecord::new(this.next_event_seq(), EventKind::OwnershipTransferred, &call.caller);
+            event.from = Some(previous);
+            event.to = Some(new_owner);
+            this.emit(event);
+            Ok(this.receipt(Vec::new()))
+        })
+    }
+
+    /// Returns the guardians, sorted.
+    pub fn guardians(&self) -> Vec<String> {
+        self.guardians.members.iter().cloned().collect()
+    }
+
+    /// Returns how many guardians must agree on a recovery.
+    pub fn guardian_threshold(&self) -> u32 {
+        self.guardians.threshold
+    }
+
+    /// Returns the recovery in progress: the pending one if a quorum agreed, otherwise
+    /// the candidate with the most guardian support; `undefined` if there are no votes.
+    pub fn recovery_status(&self) -> Option<RecoveryStatus> {
+        let guardians = &self.guardians;
+        let (new_owner, executable_at) = match &guardians.pending {
+            Some((new_owner, executable_at)) => (new_owner.clone(), Some(*executable_at)),
+            None => {
+                let leader = guardians.votes.values().max_by_key(|candidate| guardians.supporters(candidate))?;
+                (leader.clone(), None)
+            }
+        };
+        Some(RecoveryStatus {
+            supporters: guardians.supporters(&new_owner),
+            threshold: guardians.threshold,
+            new_owner,
+            executable_at,
+        })
+    }
+}
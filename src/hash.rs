@@ -0,0 +1,103 @@
+//! Deterministic state hash for checking that replicas agree.
+//!
+//! The hash covers exactly what a `StateDiff` covers: non-zero balances, stored
+//! approvals with their expiry, owner and admins, and every other section of the
+//! canonical state, so two contracts hash equal iff their diff is empty. It does
+//! not depend on the clock: approvals are hashed whether or not they are still
+//! live, so the hash of a state does not change as time passes. The event log is
+//! not hashed: replicas synced through diffs have a different one.
+//!
+//! Call results returned by `call` are hashed as well, so a replay can check
+//! that every recorded call had the same outcome.
+
+use serde_json::Value;
+use wasm_bindgen::prelude::*;
+
+use crate::ERC1155;
+
+/// Bumped whenever the canonical encoding changes.
+const ENCODING_VERSION: &[u8] = b"erc1155-wasm/state/v4";
+
+/// Canonical byte encoding fed to the hasher: length-prefixed strings and
+/// little-endian integers, with every collection sorted.
+struct Encoder {
+    hasher: blake3::Hasher,
+}
+
+impl Encoder {
+    fn u64(&mut self, value: u64) {
+        self.hasher.update(&value.to_le_bytes());
+    }
+
+    fn str(&mut self, value: &str) {
+        self.u64(value.len() as u64);
+        self.hasher.update(value.as_bytes());
+    }
+
+    /// Writes a section tag followed by the number of entries in it.
+    fn section(&mut self, tag: &str, len: usize) {
+        self.str(tag);
+        self.u64(len as u64);
+    }
+}
+
+/// Returns the hex-encoded BLAKE3 hash of a call result as serialized by `call`. Object
+/// keys serialize in sorted order, so equal results hash equal.
+pub(crate) fn receipt_hash(result: &Value) -> String {
+    blake3::hash(result.to_string().as_bytes()).to_hex().to_string()
+}
+
+#[wasm_bindgen]
+impl ERC1155 {
+    /// Returns a hex-encoded BLAKE3 hash of the contract's canonical state: balances,
+    /// approvals, roles, policies, token configuration, metadata and the state of every
+    /// extension. Independent of insertion order, so replicas that applied the same
+    /// operations agree.
+    pub fn state_hash(&self) -> String {
+        let mut encoder = Encoder {
+            hasher: blake3::Hasher::new(),
+        };
+        encoder.hasher.update(ENCODING_VERSION);
+
+        let mut balances: Vec<(u32, String, u64)> = self
+            .balances
+            .iter()
+            .filter(|(_, _, balance)| *balance > 0)
+            .map(|(owner, token_id, balance)| (token_id, owner, balance))
+            .collect();
+        balances.sort_unstable();
+        encoder.section("balances", balances.len());
+        for (token_id, owner, balance) in balances {
+            encoder.u64(u64::from(token_id));
+            encoder.str(&owner);
+            encoder.u64(balance);
+        }
+
+        let mut approvals: Vec<(&str, String, Option<u64>)> = self.approvals.iter().collect();
+        approvals.sort_unstable();
+        encoder.section("approvals", approvals.len());
+        for (owner, operator, expires_at) in approvals {
+            encoder.str(owner);
+            encoder.str(&operator);
+            // Indefinite approvals never expire, so they encode as the latest possible time.
+            encoder.u64(expires_at.unwrap_or(u64::MAX));
+        }
+
+        encoder.section("owner", 1);
+        encoder.str(&self.access_control.owner);
+        let mut admins: Vec<&str> = self.access_control.admins.iter().map(String::as_str).collect();
+        admins.sort_unstable();
+        encoder.section("admins", admins.len());
+        for admin in admins {
+            encoder.str(admin);
+        }
+
+        // Sections are sorted by name, and their JSON values by key.
+        for (name, value) in self.state_sections() {
+            encoder.section(name, 1);
+            encoder.str(&value.to_string());
+        }
+
+        encoder.hasher.finalize().to_hex().to_string()
+    }
+}
@@ -1,43 +1,151 @@
 use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 use wasm_bindgen::prelude::*;
+#[cfg(target_arch = "wasm32")]
 use web_sys::console;
 
+use allowances::Allowances;
+use analytics::Analytics;
+use approvals::Approvals;
+use authorization::Authorizations;
+use balances::Balances;
+use bridge::Bridge;
+use clock::Clock;
+use consumables::Consumables;
+use delegation::Delegations;
+use drops::Drops;
+use escrow::RENOUNCED_OWNER;
+use events::EventIndex;
+use fork::Log;
+use fractions::Fractions;
+use gas::{GasCost, GasMeter};
+use governance::Governance;
+use guardians::Guardians;
+use metadata::Metadata;
+use names::Names;
+use outbox::Outbox;
+use pipeline::{CallKind, Policy};
+use rewards::Rewards;
+use search::AttributeIndex;
+use storage::Tables;
+use telemetry::ErrorStats;
+use tokens::Tokens;
+use wrapping::Wrapping;
+
+mod airdrop;
+mod allowances;
+mod analytics;
+mod approvals;
+mod async_api;
+mod audit;
+mod authorization;
+mod balances;
+mod bridge;
+mod bulk;
+mod canonical;
+mod clock;
+mod collections;
+mod compaction;
+mod consumables;
+mod delegation;
+mod diff;
+mod drops;
+mod error;
+mod escrow;
+mod events;
+mod expiry;
+mod fork;
+mod fractions;
+mod gas;
+mod governance;
+mod guard;
+mod guardians;
+mod hash;
+mod metadata;
+mod migration;
+mod names;
+mod outbox;
+mod pipeline;
+mod replay;
+mod rewards;
+mod rpc;
+mod search;
+mod shared;
+mod snapshots;
+mod storage;
+mod split_id;
+mod telemetry;
+mod tokens;
+mod transaction;
+mod types;
+mod wrapping;
+
+pub use async_api::AsyncERC1155;
+pub use audit::{OperationFilter, OperationRecord};
+pub use bridge::BridgeTransfer;
+pub use collections::{CollectionBalance, CollectionManager};
+pub use compaction::StateStats;
+pub use diff::StateDiff;
+pub use drops::DropInfo;
+pub use error::{ContractError, ErrorKind, Result};
+pub use events::EventFilter;
+pub use fractions::Fraction;
+pub use gas::GasSchedule;
+pub use governance::ProposalInfo;
+pub use guard::ReentrancyGuard;
+pub use guardians::RecoveryStatus;
+pub use migration::{MigrationReport, TokenTotal, TotalMismatch};
+pub use rewards::DistributionInfo;
+pub use shared::{BalanceReplica, SharedERC1155};
+pub use storage::{KeyValueStore, KvStorage, MemoryStorage, Storage, StorageValue, StoreProvider};
+pub use telemetry::ErrorStat;
+pub use tokens::{RoyaltyInfo, TokenClass};
+pub use types::{BalanceChange, BalanceEntry, EventKind, EventRecord, Receipt, TransferReceipt};
+
 /// A highly optimized ERC1155 implementation in Rust for WebAssembly (WASM).
 #[wasm_bindgen]
+#[derive(Clone)]
 pub struct ERC1155 {
     access_control: AccessControl,
-    balances: HashMap<(String, u32), u64>,  // (User, TokenID) -> Balance
-    approvals: HashMap<String, HashMap<String, bool>>, // User -> (Approved User -> Approval Status)
+    balances: Balances, // TokenID -> (User -> Balance)
+    approvals: Approvals, // User -> (Approved User -> Expiry)
+    allowances: Allowances,
     reentrancy_guard: ReentrancyGuard,
-}
-
-/// Reentrancy guard to prevent reentrancy attacks.
-pub struct ReentrancyGuard {
-    is_locked: bool,
-}
-
-impl ReentrancyGuard {
-    /// Creates a new reentrancy guard.
-    pub fn new() -> Self {
-        Self { is_locked: false }
-    }
-
-    /// Locks the guard, preventing reentrant calls.
-    pub fn enter(&mut self) -> Result<(), String> {
-        if self.is_locked {
-            return Err("Reentrancy detected.".into());
-        }
-        self.is_locked = true;
-        Ok(())
-    }
-
-    /// Unlocks the guard, allowing further function execution.
-    pub fn exit(&mut self) {
-        self.is_locked = false;
-    }
+    policy: Policy,
+    guardians: Guardians,
+    tokens: Tokens,
+    metadata: Metadata,
+    attribute_index: AttributeIndex,
+    fractions: Fractions,
+    wrapping: Wrapping,
+    bridge: Bridge,
+    governance: Governance,
+    delegations: Delegations,
+    names: Names,
+    rewards: Rewards,
+    analytics: Analytics,
+    authorizations: Authorizations,
+    consumables: Consumables,
+    drops: Drops,
+    clock: Clock,
+    gas: GasMeter,
+    error_stats: ErrorStats,
+    events: Log<EventRecord>,
+    event_index: EventIndex,
+    outbox: Outbox,
+    operations: Log<OperationRecord>,
+    /// Owner the contract was created with, where its operation log starts from.
+    created_by: String,
+    /// Random ID that authorization messages name; see `instance_id`.
+    instance_id: [u8; 16],
+    /// The `call` request being run, logged with the operation it opens.
+    request: Option<String>,
+    /// Checkpoint taken by `begin_transaction`.
+    transaction: Option<Box<ERC1155>>,
 }
 
 /// Structure for managing access control (owner and admin rights).
+#[derive(Clone)]
 pub struct AccessControl {
     owner: String,
     admins: HashSet<String>,
@@ -63,9 +171,9 @@ impl AccessControl {
     }
 
     /// Adds a new admin to the contract (only the owner can add admins).
-    pub fn add_admin(&mut self, caller: &str, new_admin: &str) -> Result<(), String> {
+    pub fn add_admin(&mut self, caller: &str, new_admin: &str) -> Result<()> {
         if !self.is_owner(caller) {
-            return Err("Only the owner can add admins.".into());
+            return Err(ContractError::new(ErrorKind::Unauthorized, "Only the owner can add admins."));
         }
         self.admins.insert(new_admin.to_string());
         Ok(())
@@ -76,101 +184,574 @@ impl AccessControl {
 impl ERC1155 {
     /// Initializes a new ERC1155 contract with the owner.
     /// # Parameters
-    /// - `owner`: The initial owner of the contract.
+    /// - `owner`: The initial owner of the contract, normalized like every caller; fails
+    ///   if it is not a valid address.
     #[wasm_bindgen(constructor)]
-    pub fn new(owner: &str) -> ERC1155 {
-        console::log_1(&format!("ERC1155 initialized with owner: {}", owner).into());
-        ERC1155 {
-            access_control: AccessControl::new(owner.to_string()),
-            balances: HashMap::new(),
-            approvals: HashMap::new(),
-            reentrancy_guard: ReentrancyGuard::new(),
-        }
+    pub fn new(owner: &str) -> Result<ERC1155> {
+        Self::with_tables(owner, Tables::default())
     }
 
-    /// Mints new tokens for a given `token_id` (only admins can mint).
+    /// Mints new tokens for a given `token_id`. A new token ID can be minted by admins and
+    /// creators, and the caller becomes its creator; after that only the creator or an
+    /// admin can mint more, up to the token's maximum supply.
     /// # Parameters
-    /// - `caller`: The address calling the function (must be an admin).
+    /// - `caller`: The address calling the function.
     /// - `token_id`: The ID of the token to mint.
     /// - `amount`: The number of tokens to mint.
-    pub fn mint(&mut self, caller: &str, token_id: u32, amount: u64) -> Result<(), String> {
-        if !self.access_control.is_admin(caller) {
-            console::log_1(&format!("Mint failed: {} is not an admin", caller).into());
-            return Err("Caller is not authorized to mint tokens.".into());
-        }
-
-        self.reentrancy_guard.enter()?; // Reentrancy protection
-
-        let balance = self.balances.entry((caller.to_string(), token_id)).or_insert(0);
-        *balance += amount;
-
-        console::log_1(&format!("Minted {} tokens of ID {} to {}", amount, token_id, caller).into());
-        self.reentrancy_guard.exit(); // Reentrancy protection exit
+    pub fn mint(&mut self, caller: &str, token_id: u32, amount: u64) -> Result<TransferReceipt> {
+        self.enter("mint", CallKind::User, caller, &[], |this, call| {
+            this.charge_gas(GasCost {
+                reads: 2,
+                writes: 1,
+                events: 1,
+                ..GasCost::default()
+            })?;
+            this.mint_unguarded(&call.caller, token_id, amount)
+        })
+    }
 
-        Ok(())
+    /// Transfers the caller's tokens to another user.
+    /// # Parameters
+    /// - `caller`: The address initiating the transfer (the holder of the tokens).
+    /// - `to`: The recipient of the tokens.
+    /// - `token_id`: The ID of the token being transferred.
+    /// - `amount`: The number of tokens to transfer.
+    pub fn transfer(&mut self, caller: &str, to: &str, token_id: u32, amount: u64) -> Result<TransferReceipt> {
+        self.transfer_from(caller, caller, to, token_id, amount)
     }
 
-    /// Transfers tokens to another user.
+    /// Transfers tokens from `from` to another user.
     /// # Parameters
-    /// - `caller`: The address initiating the transfer (must be owner or approved).
+    /// - `caller`: The address initiating the transfer (must be `from` or approved by `from`).
+    /// - `from`: The holder of the tokens.
     /// - `to`: The recipient of the tokens.
     /// - `token_id`: The ID of the token being transferred.
     /// - `amount`: The number of tokens to transfer.
-    pub fn transfer(&mut self, caller: &str, to: &str, token_id: u32, amount: u64) -> Result<(), String> {
-        // Check if the caller is the owner or approved to transfer
-        if !self.is_approved(caller, token_id) && !self.access_control.is_owner(caller) {
-            console::log_1(&format!("Transfer failed: {} is not approved or the owner.", caller).into());
-            return Err("Caller is not authorized to transfer.".into());
-        }
+    pub fn transfer_from(
+        &mut self,
+        caller: &str,
+        from: &str,
+        to: &str,
+        token_id: u32,
+        amount: u64,
+    ) -> Result<TransferReceipt> {
+        self.enter("transfer_from", CallKind::User, caller, &[from, to], |this, call| {
+            this.charge_gas(GasCost {
+                reads: 3,
+                writes: 2,
+                events: 1,
+                ..GasCost::default()
+            })?;
+            this.transfer_from_unguarded(&call.caller, &call.accounts[0], &call.accounts[1], token_id, amount)
+        })
+    }
 
-        // Transfer logic
-        let balance = self.balances.entry((caller.to_string(), token_id)).or_insert(0);
-        if *balance < amount {
-            return Err("Insufficient balance.".into());
-        }
-        *balance -= amount;
-        let recipient_balance = self.balances.entry((to.to_string(), token_id)).or_insert(0);
-        *recipient_balance += amount;
+    /// Transfers several token IDs to another user in one all-or-nothing call.
+    /// # Parameters
+    /// - `caller`: The address initiating the transfer (the holder of the tokens).
+    /// - `to`: The recipient of the tokens.
+    /// - `token_ids`: The IDs of the tokens being transferred.
+    /// - `amounts`: The number of tokens to transfer, paired index-wise with `token_ids`.
+    pub fn transfer_batch(
+        &mut self,
+        caller: &str,
+        to: &str,
+        token_ids: Vec<u32>,
+        amounts: Vec<u64>,
+    ) -> Result<Vec<TransferReceipt>> {
+        self.enter("transfer_batch", CallKind::User, caller, &[to], |this, call| {
+            let items = token_ids.len() as u64;
+            this.charge_gas(GasCost {
+                reads: 1 + 2 * items,
+                writes: 2 * items,
+                events: items,
+                iterations: items,
+            })?;
+            let (caller, to) = (&call.caller, &call.accounts[0]);
+            this.check_transfer_batch(caller, caller, to, &token_ids, &amounts)?;
 
-        console::log_1(&format!("Transferred {} tokens of ID {} from {} to {}", amount, token_id, caller, to).into());
-        Ok(())
+            token_ids
+                .into_iter()
+                .zip(amounts)
+                .map(|(token_id, amount)| this.transfer_from_unguarded(caller, caller, to, token_id, amount))
+                .collect()
+        })
+    }
+
+    /// Burns the caller's tokens, reducing the total supply of `token_id`.
+    pub fn burn(&mut self, caller: &str, token_id: u32, amount: u64) -> Result<Receipt> {
+        self.burn_from(caller, caller, token_id, amount)
+    }
+
+    /// Burns tokens held by `from`, reducing the total supply of `token_id`.
+    /// # Parameters
+    /// - `caller`: The address initiating the burn (must be `from` or approved by `from`).
+    /// - `from`: The holder of the tokens.
+    /// - `token_id`: The ID of the token being burned.
+    /// - `amount`: The number of tokens to burn.
+    pub fn burn_from(&mut self, caller: &str, from: &str, token_id: u32, amount: u64) -> Result<Receipt> {
+        self.enter("burn_from", CallKind::User, caller, &[from], |this, call| {
+            let (caller, from) = (&call.caller, &call.accounts[0]);
+            if caller != from && !this.is_approved(from, caller) {
+                return Err(ContractError::new(ErrorKind::Unauthorized, "Caller is not authorized to burn."));
+            }
+            this.check_burn(token_id, amount)?;
+            this.charge_gas(GasCost {
+                reads: 3,
+                writes: 1,
+                events: 2,
+                ..GasCost::default()
+            })?;
+
+            let changes = this.move_tokens(caller, Some(from), None, token_id, amount)?;
+            log(&format!("Burned {} tokens of ID {} from {}", amount, token_id, from));
+            Ok(this.receipt(changes))
+        })
     }
 
     /// Approves another user to transfer tokens on behalf of the caller.
-    pub fn approve(&mut self, caller: &str, approved: &str, token_id: u32) -> Result<(), String> {
-        let approval_entry = self.approvals.entry(caller.to_string()).or_insert_with(HashMap::new);
-        approval_entry.insert(approved.to_string(), true);
+    pub fn approve(&mut self, caller: &str, approved: &str, token_id: u32) -> Result<Receipt> {
+        self.enter("approve", CallKind::User, caller, &[approved], |this, call| {
+            this.approve_unguarded(&call.caller, &call.accounts[0], None)?;
+            log(&format!(
+                "Approval set for {} to transfer token ID {} by {}",
+                call.accounts[0], token_id, call.caller
+            ));
+            Ok(this.receipt(Vec::new()))
+        })
+    }
 
-        console::log_1(&format!("Approval set for {} to transfer token ID {} by {}", approved, token_id, caller).into());
-        Ok(())
+    /// Approves `operator` to transfer the caller's tokens until `expires_at`
+    /// (contract time in milliseconds); afterwards the approval no longer counts.
+    pub fn approve_until(&mut self, caller: &str, operator: &str, expires_at: u64) -> Result<Receipt> {
+        self.enter("approve_until", CallKind::User, caller, &[operator], |this, call| {
+            if expires_at <= this.clock.now() {
+                return Err(ContractError::new(ErrorKind::InvalidInput, "Expiry must be in the future."));
+            }
+            this.approve_unguarded(&call.caller, &call.accounts[0], Some(expires_at))?;
+            Ok(this.receipt(Vec::new()))
+        })
+    }
+
+    /// Revokes the approval the caller gave to `operator`.
+    pub fn revoke_approval(&mut self, caller: &str, operator: &str) -> Result<Receipt> {
+        self.enter("revoke_approval", CallKind::User, caller, &[operator], |this, call| {
+            this.charge_gas(GasCost {
+                reads: 1,
+                writes: 1,
+                events: 1,
+                ..GasCost::default()
+            })?;
+            let (caller, operator) = (&call.caller, &call.accounts[0]);
+            if !this.is_approved(caller, operator) {
+                return Err(ContractError::new(ErrorKind::InvalidInput, "Operator is not approved."));
+            }
+            this.revoke_unguarded(caller, operator);
+            Ok(this.receipt(Vec::new()))
+        })
     }
 
-    /// Returns the balance of tokens for a specific user and token ID.
+    /// Revokes every approval the caller has given.
+    pub fn revoke_all_approvals(&mut self, caller: &str) -> Result<Receipt> {
+        self.enter("revoke_all_approvals", CallKind::User, caller, &[], |this, call| {
+            let operators = this.get_approvals(&call.caller);
+            let count = operators.len() as u64;
+            this.charge_gas(GasCost {
+                reads: 1,
+                writes: count,
+                events: count,
+                iterations: count,
+            })?;
+            for operator in &operators {
+                this.revoke_unguarded(&call.caller, operator);
+            }
+            Ok(this.receipt(Vec::new()))
+        })
+    }
+
+    /// Returns the operators `owner` has approved that have not expired, sorted.
+    pub fn get_approvals(&self, owner: &str) -> Vec<String> {
+        let mut operators: Vec<String> = self
+            .approvals
+            .of_owner(&self.resolve_account(owner), self.clock.now())
+            .map(|(operator, _)| operator.to_string())
+            .collect();
+        operators.sort_unstable();
+        operators
+    }
+
+    /// Returns when `owner`'s approval of `operator` expires; `undefined` if it never
+    /// does or there is no live approval.
+    pub fn approval_expiry(&self, owner: &str, operator: &str) -> Option<u64> {
+        let (owner, operator) = (self.resolve_account(owner), self.resolve_account(operator));
+        if !self.approvals.is_approved(&owner, &operator, self.clock.now()) {
+            return None;
+        }
+        self.approvals.get(&owner, &operator).flatten()
+    }
+
+    /// Returns the balance of tokens for a specific user and token ID; zero once the
+    /// token has expired.
     pub fn balance_of(&self, owner: &str, token_id: u32) -> u64 {
-        *self.balances.get(&(owner.to_string(), token_id)).unwrap_or(&0)
+        if self.is_token_expired(token_id) {
+            return 0;
+        }
+        self.balances.get(&self.resolve_account(owner), token_id)
+    }
+
+    /// Returns the balances of several `(owner, token_id)` pairs at once.
+    /// # Parameters
+    /// - `owners`: The accounts to query.
+    /// - `token_ids`: The token IDs to query, paired index-wise with `owners`.
+    pub fn balance_of_batch(&self, owners: Vec<String>, token_ids: Vec<u32>) -> Result<Vec<BalanceEntry>> {
+        if owners.len() != token_ids.len() {
+            return Err(ContractError::new(ErrorKind::InvalidInput, "Owners and token IDs length mismatch."));
+        }
+        self.check_batch_size(owners.len())?;
+        Ok(owners
+            .into_iter()
+            .zip(token_ids)
+            .map(|(owner, token_id)| {
+                let balance = self.balance_of(&owner, token_id);
+                BalanceEntry { owner, token_id, balance }
+            })
+            .collect())
+    }
+
+    /// Returns every non-zero balance held by `owner` of tokens that have not expired,
+    /// ordered by token ID.
+    pub fn balances_of(&self, owner: &str) -> Vec<BalanceEntry> {
+        let owner = self.resolve_account(owner);
+        let mut entries: Vec<BalanceEntry> = self
+            .balances
+            .of_owner(&owner)
+            .filter(|(token_id, balance)| *balance > 0 && !self.is_token_expired(*token_id))
+            .map(|(token_id, balance)| BalanceEntry {
+                owner: owner.to_string(),
+                token_id,
+                balance,
+            })
+            .collect();
+        entries.sort_by_key(|entry| entry.token_id);
+        entries
+    }
+
+    /// Returns up to `limit` events starting at sequence number `from_seq`.
+    pub fn get_events(&self, from_seq: u64, limit: usize) -> Vec<EventRecord> {
+        self.events.iter_from(from_seq as usize).take(limit).cloned().collect()
+    }
+
+    /// Returns the total number of events emitted so far.
+    pub fn event_count(&self) -> u64 {
+        self.events.len() as u64
     }
 
     /// Adds a new admin to the contract (only the owner can add admins).
-    pub fn add_admin(&mut self, caller: &str, new_admin: &str) -> Result<(), String> {
-        self.access_control.add_admin(caller, new_admin)
+    pub fn add_admin(&mut self, caller: &str, new_admin: &str) -> Result<Receipt> {
+        self.enter("add_admin", CallKind::Admin, caller, &[new_admin], |this, call| {
+            let (caller, new_admin) = (&call.caller, &call.accounts[0]);
+            this.access_control.add_admin(caller, new_admin)?;
+
+            let mut event = EventRecord::new(this.next_event_seq(), EventKind::AdminAdded, caller);
+            event.to = Some(new_admin.clone());
+            this.emit(event);
+            Ok(this.receipt(Vec::new()))
+        })
     }
 
     /// Transfers ownership of the contract (only the current owner can transfer).
-    pub fn transfer_ownership(&mut self, caller: &str, new_owner: &str) -> Result<(), String> {
-        if self.access_control.is_owner(caller) {
-            self.access_control = AccessControl::new(new_owner.to_string());
-            console::log_1(&format!("Ownership transferred to {}", new_owner).into());
-            Ok(())
-        } else {
-            Err("Caller is not authorized to transfer ownership.".into())
+    pub fn transfer_ownership(&mut self, caller: &str, new_owner: &str) -> Result<Receipt> {
+        self.enter("transfer_ownership", CallKind::Admin, caller, &[new_owner], |this, call| {
+            let (caller, new_owner) = (&call.caller, &call.accounts[0]);
+            if this.access_control.is_owner(caller) {
+                this.access_control = AccessControl::new(new_owner.clone());
+                this.guardians.reset();
+
+                let mut event = EventRecord::new(this.next_event_seq(), EventKind::OwnershipTransferred, caller);
+                event.from = Some(caller.clone());
+                event.to = Some(new_owner.clone());
+                this.emit(event);
+
+                log(&format!("Ownership transferred to {}", new_owner));
+                Ok(this.receipt(Vec::new()))
+            } else {
+                Err(ContractError::new(
+                    ErrorKind::Unauthorized,
+                    "Caller is not authorized to transfer ownership.",
+                ))
+            }
+        })
+    }
+
+    /// Permanently gives up ownership (only the owner can renounce). Owner-only calls are
+    /// disabled for good and guardians can no longer recover ownership.
+    /// # Parameters
+    /// - `freeze_configuration`: Also make the contract immutable: every admin call, minting,
+    ///   token configuration and deposit is rejected from then on, leaving transfers, burns
+    ///   and the other holder actions.
+    pub fn renounce_ownership(&mut self, caller: &str, freeze_configuration: bool) -> Result<Receipt> {
+        self.enter("renounce_ownership", CallKind::Admin, caller, &[], |this, call| {
+            if !this.access_control.is_owner(&call.caller) {
+                return Err(ContractError::new(ErrorKind::Unauthorized, "Only the owner can renounce ownership."));
+            }
+
+            this.access_control.owner = RENOUNCED_OWNER.to_string();
+            this.guardians = Guardians::default();
+            if freeze_configuration {
+                this.make_immutable();
+            }
+            let mut event = EventRecord::new(this.next_event_seq(), EventKind::OwnershipRenounced, &call.caller);
+            event.from = Some(call.caller.clone());
+            event.approved = Some(freeze_configuration);
+            this.emit(event);
+            log(&format!("Ownership renounced by {}", call.caller));
+            Ok(this.receipt(Vec::new()))
+        })
+    }
+
+    /// Returns the contract owner; `undefined` once ownership was renounced.
+    pub fn owner(&self) -> Option<String> {
+        Some(self.access_control.owner.clone()).filter(|owner| owner != RENOUNCED_OWNER)
+    }
+
+    /// Checks if `operator` is approved to transfer tokens on behalf of `owner`.
+    /// Expired approvals do not count.
+    pub fn is_approved(&self, owner: &str, operator: &str) -> bool {
+        self.approvals
+            .is_approved(&self.resolve_account(owner), &self.resolve_account(operator), self.clock.now())
+    }
+}
+
+/// Writes a message to the browser console. A no-op outside WASM so the contract can run natively.
+fn log(message: &str) {
+    #[cfg(target_arch = "wasm32")]
+    console::log_1(&message.into());
+    #[cfg(not(target_arch = "wasm32"))]
+    let _ = message;
+}
+
+impl ERC1155 {
+    /// Initializes a new contract, like [`ERC1155::new`], that keeps its balances,
+    /// approvals and attributes in tables `provider` opens.
+    pub fn with_store(owner: &str, provider: Arc<dyn StoreProvider>) -> Result<ERC1155> {
+        Self::with_tables(owner, Tables::new(provider))
+    }
+
+    /// Initializes a new contract whose tables are opened in `tables`.
+    fn with_tables(owner: &str, tables: Tables) -> Result<ERC1155> {
+        telemetry::install_panic_hook();
+        let owner = pipeline::normalize_account(owner)?;
+        log(&format!("ERC1155 initialized with owner: {}", owner));
+        Ok(ERC1155 {
+            access_control: AccessControl::new(owner.clone()),
+            balances: Balances::new(tables.clone()),
+            approvals: Approvals::new(tables.clone()),
+            allowances: Allowances::default(),
+            reentrancy_guard: ReentrancyGuard::new(),
+            policy: Policy::default(),
+            guardians: Guardians::default(),
+            tokens: Tokens::default(),
+            metadata: Metadata::new(tables),
+            attribute_index: AttributeIndex::default(),
+            fractions: Fractions::default(),
+            wrapping: Wrapping::default(),
+            bridge: Bridge::default(),
+            governance: Governance::default(),
+            delegations: Delegations::default(),
+            names: Names::default(),
+            rewards: Rewards::default(),
+            analytics: Analytics::default(),
+            authorizations: Authorizations::default(),
+            consumables: Consumables::default(),
+            drops: Drops::default(),
+            clock: Clock::default(),
+            gas: GasMeter::default(),
+            error_stats: ErrorStats::default(),
+            events: Log::default(),
+            event_index: EventIndex::default(),
+            outbox: Outbox::default(),
+            operations: Log::default(),
+            created_by: owner,
+            instance_id: authorization::new_instance_id()?,
+            request: None,
+            transaction: None,
+        })
+    }
+
+    /// Body of [`ERC1155::mint`]; the caller must hold the reentrancy guard.
+    fn mint_unguarded(&mut self, caller: &str, token_id: u32, amount: u64) -> Result<TransferReceipt> {
+        self.check_mint(caller, token_id, amount)?;
+
+        let to_balance = self
+            .balance_of(caller, token_id)
+            .checked_add(amount)
+            .ok_or_else(|| ContractError::new(ErrorKind::Overflow, "Balance overflow."))?;
+
+        self.balances.set(caller, token_id, to_balance);
+        self.tokens.create(token_id, caller);
+
+        let mut event = EventRecord::new(self.next_event_seq(), EventKind::TransferSingle, caller);
+        event.to = Some(caller.to_string());
+        event.token_id = Some(token_id);
+        event.amount = Some(amount);
+        let event_seq = self.emit(event);
+
+        log(&format!("Minted {} tokens of ID {} to {}", amount, token_id, caller));
+
+        Ok(TransferReceipt {
+            operation_id: self.next_operation_id(),
+            event_seq,
+            operator: caller.to_string(),
+            from: None,
+            to: caller.to_string(),
+            token_id,
+            amount,
+            from_balance: None,
+            to_balance,
+            gas_used: self.gas.used(),
+        })
+    }
+
+    /// Body of [`ERC1155::transfer_from`]; the caller must hold the reentrancy guard.
+    fn transfer_from_unguarded(
+        &mut self,
+        caller: &str,
+        from: &str,
+        to: &str,
+        token_id: u32,
+        amount: u64,
+    ) -> Result<TransferReceipt> {
+        self.check_spend(caller, from, token_id, amount)?;
+        if amount == 0 {
+            return Err(ContractError::new(ErrorKind::InvalidInput, "Transfer amount must be positive."));
+        }
+        self.check_not_expired(token_id)?;
+
+        // Transfer logic
+        let from_balance = self
+            .balance_of(from, token_id)
+            .checked_sub(amount)
+            .ok_or_else(|| ContractError::new(ErrorKind::InsufficientBalance, "Insufficient balance."))?;
+        self.balances.set(from, token_id, from_balance);
+        let to_balance = match self.balance_of(to, token_id).checked_add(amount) {
+            Some(to_balance) => to_balance,
+            None => {
+                self.balances.set(from, token_id, from_balance + amount);
+                return Err(ContractError::new(ErrorKind::Overflow, "Recipient balance overflow."));
+            }
+        };
+        self.balances.set(to, token_id, to_balance);
+        self.spend(caller, from, token_id, amount);
+
+        let mut event = EventRecord::new(self.next_event_seq(), EventKind::TransferSingle, caller);
+        event.from = Some(from.to_string());
+        event.to = Some(to.to_string());
+        event.token_id = Some(token_id);
+        event.amount = Some(amount);
+        let event_seq = self.emit(event);
+
+        log(&format!("Transferred {} tokens of ID {} from {} to {}", amount, token_id, from, to));
+        Ok(TransferReceipt {
+            operation_id: self.next_operation_id(),
+            event_seq,
+            operator: caller.to_string(),
+            from: Some(from.to_string()),
+            to: to.to_string(),
+            token_id,
+            amount,
+            from_balance: Some(self.balance_of(from, token_id)),
+            to_balance,
+            gas_used: self.gas.used(),
+        })
+    }
+
+    /// Body of [`ERC1155::approve`] and [`ERC1155::approve_until`].
+    fn approve_unguarded(&mut self, owner: &str, operator: &str, expires_at: Option<u64>) -> Result<()> {
+        self.charge_gas(GasCost {
+            writes: 1,
+            events: 1,
+            ..GasCost::default()
+        })?;
+        let now = self.clock.now();
+        self.approvals.set(owner, operator, expires_at, now);
+
+        let mut event = EventRecord::new(self.next_event_seq(), EventKind::ApprovalForAll, operator);
+        event.from = Some(owner.to_string());
+        event.approved = Some(true);
+        event.expires_at = expires_at;
+        self.emit(event);
+        Ok(())
+    }
+
+    /// Removes the approval `owner` gave to `operator` and emits the revocation.
+    fn revoke_unguarded(&mut self, owner: &str, operator: &str) {
+        let now = self.clock.now();
+        self.approvals.remove(owner, operator, now);
+
+        let mut event = EventRecord::new(self.next_event_seq(), EventKind::ApprovalForAll, operator);
+        event.from = Some(owner.to_string());
+        event.approved = Some(false);
+        self.emit(event);
+        log(&format!("Approval for {} revoked by {}", operator, owner));
+    }
+
+    /// Verifies that a whole batch transfer would succeed without applying any of it.
+    pub(crate) fn check_transfer_batch(
+        &self,
+        caller: &str,
+        from: &str,
+        to: &str,
+        token_ids: &[u32],
+        amounts: &[u64],
+    ) -> Result<()> {
+        if token_ids.len() != amounts.len() {
+            return Err(ContractError::new(ErrorKind::InvalidInput, "Token IDs and amounts length mismatch."));
+        }
+        self.check_batch_size(token_ids.len())?;
+        if amounts.contains(&0) {
+            return Err(ContractError::new(ErrorKind::InvalidInput, "Transfer amount must be positive."));
+        }
+
+        let mut required: HashMap<u32, u64> = HashMap::new();
+        for (&token_id, &amount) in token_ids.iter().zip(amounts) {
+            let total = required.entry(token_id).or_insert(0);
+            *total = total
+                .checked_add(amount)
+                .ok_or_else(|| ContractError::new(ErrorKind::Overflow, "Batch amount overflow."))?;
         }
+        for (&token_id, &total) in &required {
+            self.check_spend(caller, from, token_id, total)?;
+        }
+        for (token_id, total) in required {
+            if self.balance_of(from, token_id) < total {
+                return Err(ContractError::new(ErrorKind::InsufficientBalance, "Insufficient balance."));
+            }
+            if from != to && self.balance_of(to, token_id).checked_add(total).is_none() {
+                return Err(ContractError::new(ErrorKind::Overflow, "Recipient balance overflow."));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the sequence number the next emitted event will receive.
+    fn next_event_seq(&self) -> u64 {
+        self.events.len() as u64
+    }
+
+    /// Appends an event to the log and returns its sequence number.
+    fn emit(&mut self, event: EventRecord) -> u64 {
+        let seq = event.seq;
+        self.event_index.insert(&event);
+        self.events.push(event);
+        seq
     }
 
-    /// Internal function to check if the caller is approved to transfer a token.
-    fn is_approved(&self, caller: &str, token_id: u32) -> bool {
-        if let Some(approval_map) = self.approvals.get(caller) {
-            return *approval_map.get(&token_id.to_string()).unwrap_or(&false);
+    /// Removes the events from `seq` on, which a failed call emitted before failing.
+    fn discard_events_from(&mut self, seq: u64) {
+        let discarded: Vec<EventRecord> = self.events.iter_from(seq as usize).cloned().collect();
+        for event in discarded.iter().rev() {
+            self.event_index.remove(event);
         }
-        false
+        self.events.truncate(seq as usize);
     }
 }
@@ -0,0 +1,445 @@
+//! Token attributes, generated metadata and metadata freezing.
+//!
+//! A token's URI is its own or else the collection's base URI, with `{id}`
+//! expanded to the token ID in hex as the ERC1155 metadata spec describes.
+//! Each token can carry string attributes. A token switched to generative mode
+//! has `uri` return a self-contained `data:application/json;base64,...`
+//! document built from its name and attributes, with an SVG image rendered from
+//! the collection's template when one is set, so nothing has to be hosted.
+//!
+//! Freezing a token's metadata, or the whole collection's, is permanent: from
+//! then on its URI, attributes and mode can no longer be changed by anyone,
+//! which gives collectors a guarantee that what they bought stays what it is.
+//! That includes the collection settings a frozen token's URI is built from:
+//! the base URI is fixed once a frozen token without its own URI relies on it,
+//! and the SVG template once a frozen token is generative.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use wasm_bindgen::prelude::*;
+
+use crate::gas::GasCost;
+use crate::pipeline::CallKind;
+use crate::storage::{Backend, Storage, Tables};
+use crate::{ContractError, ErrorKind, EventKind, EventRecord, Receipt, Result, ERC1155};
+
+/// Per-token attributes, generation settings and which metadata is frozen.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub(crate) struct Metadata {
+    /// Token ID -> (key -> value).
+    #[serde(serialize_with = "crate::canonical::tables", deserialize_with = "crate::canonical::load_tables")]
+    attributes: HashMap<u32, Arc<Backend<String>>>,
+    /// Tokens whose `uri` is generated.
+    #[serde(serialize_with = "crate::canonical::sorted")]
+    generative: HashSet<u32>,
+    /// SVG image template shared by every generated token.
+    svg_template: Option<String>,
+    /// URI of the tokens without their own, where `{id}` stands for the token ID.
+    base_uri: Option<String>,
+    /// Whether every token, including future ones, is frozen.
+    all_frozen: bool,
+    #[serde(serialize_with = "crate::canonical::sorted")]
+    frozen: HashSet<u32>,
+    /// Where new per-token tables are opened.
+    #[serde(skip)]
+    tables: Tables,
+}
+
+impl Metadata {
+    pub fn new(tables: Tables) -> Self {
+        Self {
+            tables,
+            ..Self::default()
+        }
+    }
+
+    pub fn tables(&self) -> &Tables {
+        &self.tables
+    }
+
+    /// Moves every table to `tables`, where new ones are opened from then on.
+    pub fn move_to(&mut self, tables: Tables) {
+        for attributes in self.attributes.values_mut() {
+            let table = std::mem::take(Arc::make_mut(attributes));
+            *attributes = Arc::new(tables.adopt(table));
+        }
+        self.tables = tables;
+    }
+
+    pub fn is_frozen(&self, token_id: u32) -> bool {
+        self.all_frozen || self.frozen.contains(&token_id)
+    }
+
+    /// Returns the number of attributes set across every token.
+    pub fn attribute_count(&self) -> u64 {
+        self.attributes.values().map(|attributes| attributes.len() as u64).sum()
+    }
+
+    /// Iterates over every `(token_id, key, value)` attribute, in no particular order.
+    pub fn all_attributes(&self) -> impl Iterator<Item = (u32, String, String)> + '_ {
+        self.attributes
+            .iter()
+            .flat_map(|(token_id, attributes)| {
+                attributes
+                    .iter()
+                    .map(move |(key, value)| (*token_id, key.into_owned(), value))
+            })
+    }
+
+    pub fn is_generative(&self, token_id: u32) -> bool {
+        self.generative.contains(&token_id)
+    }
+
+    pub fn base_uri(&self) -> Option<&str> {
+        self.base_uri.as_deref()
+    }
+
+    /// Returns the attributes of `token_id`, ordered by key.
+    pub fn attributes(&self, token_id: u32) -> Vec<(String, String)> {
+        let mut attributes: Vec<(String, String)> = self
+            .attributes
+            .get(&token_id)
+            .into_iter()
+            .flat_map(|attributes| attributes.iter())
+            .map(|(key, value)| (key.into_owned(), value))
+            .collect();
+        attributes.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+        attributes
+    }
+}
+
+/// Substitutes `{id}` in `uri` with `token_id` as 64 lowercase hex digits, as the
+/// ERC1155 metadata spec has clients do.
+pub(crate) fn expand_id(uri: &str, token_id: u32) -> String {
+    uri.replace("{id}", &format!("{:064x}", token_id))
+}
+
+/// Escapes the characters that are not allowed verbatim in XML text and attributes.
+fn escape_xml(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Replaces each `{name}` in `template` for which `lookup` has a value. Other braces,
+/// such as CSS blocks, are kept as they are.
+fn render(template: &str, lookup: impl Fn(&str) -> Option<String>) -> String {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        rendered.push_str(&rest[..open]);
+        let tail = &rest[open + 1..];
+        let value = tail
+            .find('}')
+            .and_then(|close| lookup(&tail[..close]).map(|value| (value, close)));
+        match value {
+            Some((value, close)) => {
+                rendered.push_str(&value);
+                rest = &tail[close + 1..];
+            }
+            None => {
+                rendered.push('{');
+                rest = tail;
+            }
+        }
+    }
+    rendered.push_str(rest);
+    rendered
+}
+
+impl ERC1155 {
+    /// Fails if the metadata of `token_id` is frozen.
+    pub(crate) fn check_metadata_mutable(&self, token_id: u32) -> Result<()> {
+        if self.metadata.is_frozen(token_id) {
+            return Err(ContractError::new(ErrorKind::InvalidInput, "Token metadata is frozen."));
+        }
+        Ok(())
+    }
+
+    /// Checks that `caller` may change collection-wide metadata.
+    fn check_collection_metadata_admin(&self, caller: &str) -> Result<()> {
+        if !self.access_control.is_owner(caller) && !self.access_control.is_admin(caller) {
+            return Err(ContractError::new(
+                ErrorKind::Unauthorized,
+                "Caller is not authorized to change collection metadata.",
+            ));
+        }
+        if self.metadata.all_frozen {
+            return Err(ContractError::new(ErrorKind::InvalidInput, "Collection metadata is frozen."));
+        }
+        Ok(())
+    }
+
+    /// Returns whether the frozen metadata of some token matches `relies`.
+    fn frozen_token_relies_on(&self, relies: impl Fn(u32) -> bool) -> bool {
+        self.metadata.frozen.iter().any(|&token_id| relies(token_id))
+    }
+
+    /// Body of [`ERC1155::set_attribute`] and [`ERC1155::remove_attribute`].
+    fn write_attribute(&mut self, caller: &str, token_id: u32, key: &str, value: Option<&str>) -> Result<()> {
+        self.check_token_manager(caller, token_id)?;
+        self.check_metadata_mutable(token_id)?;
+        let key = key.trim();
+        if key.is_empty() {
+            return Err(ContractError::new(ErrorKind::InvalidInput, "Attribute key must not be empty."));
+        }
+        if value.is_none() && self.attribute(token_id, key).is_none() {
+            return Err(ContractError::new(ErrorKind::InvalidInput, "Attribute is not set."));
+        }
+        self.charge_gas(GasCost {
+            reads: 1,
+            writes: 1,
+            events: 1,
+            ..GasCost::default()
+        })?;
+
+        if let Some(previous) = self.attribute(token_id, key) {
+            self.attribute_index.remove(key, &previous, token_id);
+        }
+        match value {
+            Some(value) => {
+                self.attribute_index.insert(key, value, token_id);
+                let tables = &self.metadata.tables;
+                let attributes = self.metadata.attributes.entry(token_id);
+                let attributes = Arc::make_mut(attributes.or_insert_with(|| Arc::new(tables.open())));
+                attributes.set(key, value.to_string());
+            }
+            None => {
+                if let Some(attributes) = self.metadata.attributes.get_mut(&token_id) {
+                    let attributes = Arc::make_mut(attributes);
+                    attributes.remove(key);
+                    if attributes.is_empty() {
+                        self.metadata.attributes.remove(&token_id);
+                    }
+                }
+            }
+        }
+        let mut event = EventRecord::new(self.next_event_seq(), EventKind::AttributeUpdated, caller);
+        event.token_id = Some(token_id);
+        event.approved = Some(value.is_some());
+        event.value = Some(key.to_string());
+        self.emit(event);
+        Ok(())
+    }
+
+    /// Renders the collection's SVG template for `token_id`, if a template is set.
+    fn render_svg(&self, token_id: u32, name: &str) -> Option<String> {
+        let template = self.metadata.svg_template.as_ref()?;
+        let attributes: HashMap<String, String> = self.metadata.attributes(token_id).into_iter().collect();
+        Some(render(template, |key| match key {
+            "token_id" => Some(token_id.to_string()),
+            "name" => Some(escape_xml(name)),
+            key => attributes.get(key).map(|value| escape_xml(value)),
+        }))
+    }
+}
+
+#[wasm_bindgen]
+impl ERC1155 {
+    /// Sets the attribute `key` of `token_id` to `value` (creator or admins only), unless
+    /// its metadata is frozen.
+    pub fn set_attribute(&mut self, caller: &str, token_id: u32, key: &str, value: &str) -> Result<Receipt> {
+        self.enter("set_attribute", CallKind::Admin, caller, &[], |this, call| {
+            this.write_attribute(&call.caller, token_id, key, Some(value))?;
+            Ok(this.receipt(Vec::new()))
+        })
+    }
+
+    /// Removes the attribute `key` of `token_id` (creator or admins only), unless its
+    /// metadata is frozen.
+    pub fn remove_attribute(&mut self, caller: &str, token_id: u32, key: &str) -> Result<Receipt> {
+        self.enter("remove_attribute", CallKind::Admin, caller, &[], |this, call| {
+            this.write_attribute(&call.caller, token_id, key, None)?;
+            Ok(this.receipt(Vec::new()))
+        })
+    }
+
+    /// Returns the attribute `key` of `token_id`, if set.
+    pub fn attribute(&self, token_id: u32, key: &str) -> Option<String> {
+        self.metadata
+            .attributes
+            .get(&token_id)
+            .and_then(|attributes| attributes.get(key.trim()))
+    }
+
+    /// Returns the attribute keys of `token_id`, sorted.
+    pub fn attribute_keys(&self, token_id: u32) -> Vec<String> {
+        self.metadata
+            .attributes(token_id)
+            .into_iter()
+            .map(|(key, _)| key.to_string())
+            .collect()
+    }
+
+    /// Switches `token_id` between a generated `uri` and the one set with `set_token_uri`
+    /// (creator or admins only), unless its metadata is frozen.
+    pub fn set_generative_metadata(&mut self, caller: &str, token_id: u32, enabled: bool) -> Result<Receipt> {
+        self.enter("set_generative_metadata", CallKind::Admin, caller, &[], |this, call| {
+            this.check_token_manager(&call.caller, token_id)?;
+            this.check_metadata_mutable(token_id)?;
+            this.charge_gas(GasCost {
+                reads: 1,
+                writes: 1,
+                events: 1,
+                ..GasCost::default()
+            })?;
+
+            if enabled {
+                this.metadata.generative.insert(token_id);
+            } else {
+                this.metadata.generative.remove(&token_id);
+            }
+            let uri = this.uri(token_id);
+            let mut event = EventRecord::new(this.next_event_seq(), EventKind::Uri, &call.caller);
+            event.token_id = Some(token_id);
+            event.value = Some(uri);
+            this.emit(event);
+            Ok(this.receipt(Vec::new()))
+        })
+    }
+
+    /// Returns whether the `uri` of `token_id` is generated.
+    pub fn is_generative_metadata(&self, token_id: u32) -> bool {
+        self.metadata.is_generative(token_id)
+    }
+
+    /// Sets the SVG image template of generated metadata, or removes it when `undefined`
+    /// (owner or admins only, until the collection or a generative token is frozen).
+    /// `{token_id}`, `{name}` and
+    /// `{<attribute key>}` are replaced with the token's values, XML-escaped; any other
+    /// text, including other braces, is kept as is.
+    pub fn set_svg_template(&mut self, caller: &str, template: Option<String>) -> Result<Receipt> {
+        self.enter("set_svg_template", CallKind::Admin, caller, &[], |this, call| {
+            this.check_collection_metadata_admin(&call.caller)?;
+            if this.frozen_token_relies_on(|token_id| this.metadata.is_generative(token_id)) {
+                return Err(ContractError::new(
+                    ErrorKind::InvalidInput,
+                    "The SVG template is used by a token whose metadata is frozen.",
+                ));
+            }
+
+            let mut event = EventRecord::new(this.next_event_seq(), EventKind::SvgTemplateUpdated, &call.caller);
+            event.approved = Some(template.is_some());
+            this.metadata.svg_template = template;
+            this.emit(event);
+            Ok(this.receipt(Vec::new()))
+        })
+    }
+
+    /// Sets the URI of every token without its own, or removes it when `undefined` (owner or
+    /// admins only, until the collection or a token relying on it is frozen). `{id}` in it
+    /// stands for the token ID.
+    pub fn set_base_uri(&mut self, caller: &str, base_uri: Option<String>) -> Result<Receipt> {
+        self.enter("set_base_uri", CallKind::Admin, caller, &[], |this, call| {
+            this.check_collection_metadata_admin(&call.caller)?;
+            let uses_base_uri = |token_id| {
+                !this.metadata.is_generative(token_id)
+                    && this.tokens.get(token_id).and_then(|config| config.uri.as_ref()).is_none()
+            };
+            if this.frozen_token_relies_on(uses_base_uri) {
+                return Err(ContractError::new(
+                    ErrorKind::InvalidInput,
+                    "The base URI is used by a token whose metadata is frozen.",
+                ));
+            }
+
+            let mut event = EventRecord::new(this.next_event_seq(), EventKind::BaseUriUpdated, &call.caller);
+            event.value = base_uri.clone();
+            this.metadata.base_uri = base_uri;
+            this.emit(event);
+            Ok(this.receipt(Vec::new()))
+        })
+    }
+
+    /// Returns the base URI as set, with `{id}` unexpanded.
+    pub fn base_uri(&self) -> Option<String> {
+        self.metadata.base_uri.clone()
+    }
+
+    /// Returns the JSON metadata document generated for `token_id`: its `name`, `decimals`
+    /// when registered, the rendered `image` when a template is set, and its `attributes`.
+    pub fn metadata_json(&self, token_id: u32) -> String {
+        let class = self.token_class(token_id);
+        let name = class
+            .as_ref()
+            .map_or_else(|| format!("#{}", token_id), |class| class.name.clone());
+        let mut document = json!({
+            "name": name,
+            "attributes": self
+                .metadata
+                .attributes(token_id)
+                .into_iter()
+                .map(|(key, value)| json!({ "trait_type": key, "value": value }))
+                .collect::<Vec<Value>>(),
+        });
+        if let Some(class) = class {
+            document["decimals"] = json!(class.decimals);
+        }
+        if let Some(svg) = self.render_svg(token_id, &name) {
+            document["image"] = json!(format!("data:image/svg+xml;base64,{}", STANDARD.encode(svg)));
+        }
+        document.to_string()
+    }
+
+    /// Permanently freezes the metadata of `token_id` (creator or admins only).
+    pub fn freeze_metadata(&mut self, caller: &str, token_id: u32) -> Result<Receipt> {
+        self.enter("freeze_metadata", CallKind::Admin, caller, &[], |this, call| {
+            this.check_token_manager(&call.caller, token_id)?;
+            this.check_metadata_mutable(token_id)?;
+            this.charge_gas(GasCost {
+                reads: 1,
+                writes: 1,
+                events: 1,
+                ..GasCost::default()
+            })?;
+
+            this.metadata.frozen.insert(token_id);
+            let mut event = EventRecord::new(this.next_event_seq(), EventKind::MetadataFrozen, &call.caller);
+            event.token_id = Some(token_id);
+            this.emit(event);
+            Ok(this.receipt(Vec::new()))
+        })
+    }
+
+    /// Permanently freezes the metadata of every token, including tokens created later
+    /// (only the owner can freeze the collection).
+    pub fn freeze_all_metadata(&mut self, caller: &str) -> Result<Receipt> {
+        self.enter("freeze_all_metadata", CallKind::Admin, caller, &[], |this, call| {
+            if !this.access_control.is_owner(&call.caller) {
+                return Err(ContractError::new(ErrorKind::Unauthorized, "Only the owner can freeze the collection."));
+            }
+            if this.metadata.all_frozen {
+                return Err(ContractError::new(ErrorKind::InvalidInput, "Collection metadata is already frozen."));
+            }
+
+            this.metadata.all_frozen = true;
+            let event = EventRecord::new(this.next_event_seq(), EventKind::MetadataFrozen, &call.caller);
+            this.emit(event);
+            Ok(this.receipt(Vec::new()))
+        })
+    }
+
+    /// Returns whether the metadata of `token_id` is frozen, on its own or with the collection.
+    pub fn is_metadata_frozen(&self, token_id: u32) -> bool {
+        self.metadata.is_frozen(token_id)
+    }
+
+    /// Returns whether the metadata of the whole collection is frozen.
+    pub fn is_collection_metadata_frozen(&self) -> bool {
+        self.metadata.all_frozen
+    }
+}
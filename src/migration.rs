@@ -0,0 +1,318 @@
+//! Initializes a contract from a balance/approval dump exported from an EVM ERC1155.
+//!
+//! Two formats are accepted:
+//! - `json`: `{ "balances": [{ "holder", "id", "amount" }], "approvals": [{ "owner", "operator" }], "totals": { "<id>": "<amount>" } }`,
+//!   where `approvals` and `totals` are optional and numbers may be JSON numbers,
+//!   decimal strings or `0x` hex strings.
+//! - `csv`: one `holder,id,amount` row per line; an optional header line and `#` comments are skipped.
+//!
+//! When `totals` are supplied (or later via `verify_totals`) they are compared
+//! against the imported balances so a mirror can be checked against the source.
+
+use std::collections::{BTreeMap, HashMap};
+use std::convert::TryFrom;
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::pipeline::{normalize_account, CallKind};
+use crate::{ContractError, ErrorKind, EventKind, EventRecord, Result, ERC1155};
+
+/// Total supply of one token ID.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct TokenTotal {
+    #[wasm_bindgen(readonly)]
+    pub token_id: u32,
+    #[wasm_bindgen(readonly)]
+    pub total: u64,
+}
+
+/// A token whose computed total differs from the expected one.
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TotalMismatch {
+    #[wasm_bindgen(readonly)]
+    pub token_id: u32,
+    /// Total from the dump, as a decimal string.
+    #[wasm_bindgen(readonly)]
+    pub expected: String,
+    /// Total held in this contract, as a decimal string.
+    #[wasm_bindgen(readonly)]
+    pub actual: String,
+}
+
+/// Summary of an import.
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MigrationReport {
+    /// Non-zero `(holder, id)` balances written.
+    #[wasm_bindgen(readonly)]
+    pub balances_imported: u32,
+    #[wasm_bindgen(readonly)]
+    pub approvals_imported: u32,
+    #[wasm_bindgen(readonly)]
+    pub holders: u32,
+    /// Per-token totals computed from the imported balances, ordered by token ID.
+    #[wasm_bindgen(readonly)]
+    pub totals: Vec<TokenTotal>,
+    /// Differences against the dump's `totals`; empty when they match or none were given.
+    #[wasm_bindgen(readonly)]
+    pub mismatches: Vec<TotalMismatch>,
+}
+
+#[wasm_bindgen]
+impl MigrationReport {
+    /// Returns whether every expected total matched.
+    pub fn verified(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+}
+
+/// A number given as a JSON number or a decimal/hex string.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Number {
+    Int(u64),
+    Text(String),
+}
+
+#[derive(Deserialize)]
+struct JsonBalance {
+    holder: String,
+    id: Number,
+    amount: Number,
+}
+
+#[derive(Deserialize)]
+struct JsonApproval {
+    owner: String,
+    operator: String,
+}
+
+#[derive(Deserialize)]
+struct JsonDump {
+    #[serde(default)]
+    balances: Vec<JsonBalance>,
+    #[serde(default)]
+    approvals: Vec<JsonApproval>,
+    #[serde(default)]
+    totals: Option<HashMap<String, Number>>,
+}
+
+/// A dump after parsing, with addresses normalized and duplicate rows merged.
+#[derive(Default)]
+struct Dump {
+    balances: BTreeMap<(u32, String), u64>,
+    approvals: Vec<(String, String)>,
+    totals: Option<BTreeMap<u32, u128>>,
+}
+
+fn invalid(message: String) -> ContractError {
+    ContractError::new(ErrorKind::InvalidInput, message)
+}
+
+/// Parses a decimal or `0x` hex unsigned integer.
+fn parse_u128(text: &str) -> Option<u128> {
+    let text = text.trim();
+    match text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        Some(hex) => u128::from_str_radix(hex, 16).ok(),
+        None => text.parse().ok(),
+    }
+}
+
+fn number(value: &Number) -> Option<u128> {
+    match value {
+        Number::Int(value) => Some(u128::from(*value)),
+        Number::Text(text) => parse_u128(text),
+    }
+}
+
+fn token_id(value: Option<u128>, row: usize) -> Result<u32> {
+    value
+        .and_then(|id| u32::try_from(id).ok())
+        .ok_or_else(|| invalid(format!("Row {}: token ID is not a valid u32.", row)))
+}
+
+fn amount(value: Option<u128>, row: usize) -> Result<u64> {
+    value
+        .and_then(|amount| u64::try_from(amount).ok())
+        .ok_or_else(|| invalid(format!("Row {}: amount is not a valid u64.", row)))
+}
+
+impl Dump {
+    fn add_balance(&mut self, holder: &str, token_id: u32, amount: u64, row: usize) -> Result<()> {
+        if amount == 0 {
+            return Ok(());
+        }
+        let holder = normalize_account(holder).map_err(|err| invalid(format!("Row {}: {}", row, err)))?;
+        let balance = self.balances.entry((token_id, holder)).or_insert(0);
+        *balance = balance
+            .checked_add(amount)
+            .ok_or_else(|| invalid(format!("Row {}: balance overflows u64.", row)))?;
+        Ok(())
+    }
+
+    fn parse_json(data: &str) -> Result<Dump> {
+        let json: JsonDump =
+            serde_json::from_str(data).map_err(|err| invalid(format!("Invalid migration JSON: {}", err)))?;
+        let mut dump = Dump::default();
+        for (row, entry) in json.balances.iter().enumerate() {
+            let token_id = token_id(number(&entry.id), row)?;
+            let amount = amount(number(&entry.amount), row)?;
+            dump.add_balance(&entry.holder, token_id, amount, row)?;
+        }
+        for approval in &json.approvals {
+            dump.approvals
+                .push((normalize_account(&approval.owner)?, normalize_account(&approval.operator)?));
+        }
+        if let Some(totals) = &json.totals {
+            dump.totals = Some(parse_totals(totals)?);
+        }
+        Ok(dump)
+    }
+
+    fn parse_csv(data: &str) -> Result<Dump> {
+        let mut dump = Dump::default();
+        let mut first = true;
+        for (row, line) in data.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let fields: Vec<&str> = line.split(',').map(|field| field.trim().trim_matches('"')).collect();
+            if fields.len() != 3 {
+                return Err(invalid(format!("Row {}: expected holder,id,amount.", row)));
+            }
+            let id = parse_u128(fields[1]);
+            if std::mem::replace(&mut first, false) && id.is_none() {
+                continue; // header
+            }
+            let token_id = token_id(id, row)?;
+            let amount = amount(parse_u128(fields[2]), row)?;
+            dump.add_balance(fields[0], token_id, amount, row)?;
+        }
+        Ok(dump)
+    }
+}
+
+fn parse_totals(totals: &HashMap<String, Number>) -> Result<BTreeMap<u32, u128>> {
+    totals
+        .iter()
+        .map(|(id, total)| {
+            let id = token_id(parse_u128(id), 0)?;
+            let total = number(total).ok_or_else(|| invalid(format!("Total for token {} is not a number.", id)))?;
+            Ok((id, total))
+        })
+        .collect()
+}
+
+fn compare_totals(expected: &BTreeMap<u32, u128>, actual: &BTreeMap<u32, u128>) -> Vec<TotalMismatch> {
+    let mut ids: Vec<u32> = expected.keys().chain(actual.keys()).copied().collect();
+    ids.sort_unstable();
+    ids.dedup();
+    ids.into_iter()
+        .filter_map(|token_id| {
+            let expected = expected.get(&token_id).copied().unwrap_or(0);
+            let actual = actual.get(&token_id).copied().unwrap_or(0);
+            (expected != actual).then(|| TotalMismatch {
+                token_id,
+                expected: expected.to_string(),
+                actual: actual.to_string(),
+            })
+        })
+        .collect()
+}
+
+#[wasm_bindgen]
+impl ERC1155 {
+    /// Initializes balances and approvals from an exported dump (only the owner can import,
+    /// and only before any tokens exist). Each token's imported supply must be mintable,
+    /// so NFT items need their type to exist and a single holder. Emits a mint event per
+    /// imported balance.
+    /// # Parameters
+    /// - `caller`: The contract owner.
+    /// - `data`: The dump contents.
+    /// - `format`: `"json"` or `"csv"`.
+    pub fn import_state(&mut self, caller: &str, data: &str, format: &str) -> Result<MigrationReport> {
+        self.enter("import_state", CallKind::Admin, caller, &[], |this, call| {
+            if !this.access_control.is_owner(&call.caller) {
+                return Err(ContractError::new(ErrorKind::Unauthorized, "Only the owner can import state."));
+            }
+            if this.balances.iter().any(|(_, _, balance)| balance > 0) {
+                return Err(ContractError::new(ErrorKind::InvalidInput, "State can only be imported into an empty contract."));
+            }
+
+            let dump = match format {
+                "json" => Dump::parse_json(data)?,
+                "csv" => Dump::parse_csv(data)?,
+                other => return Err(invalid(format!("Unsupported migration format: {}.", other))),
+            };
+            let mut totals: BTreeMap<u32, u128> = BTreeMap::new();
+            for ((token_id, _), amount) in &dump.balances {
+                *totals.entry(*token_id).or_insert(0) += u128::from(*amount);
+            }
+            if totals.values().any(|total| *total > u128::from(u64::MAX)) {
+                return Err(invalid("A token's total supply overflows u64.".into()));
+            }
+            // Each token's imported supply follows the rules of minting it.
+            for (token_id, total) in &totals {
+                this.check_mintable(*token_id, *total as u64)?;
+                this.check_max_supply(*token_id, *total as u64)?;
+            }
+
+            for ((token_id, holder), amount) in &dump.balances {
+                this.balances.set(holder, *token_id, *amount);
+                this.tokens.create(*token_id, &call.caller);
+                let mut event = EventRecord::new(this.next_event_seq(), EventKind::TransferSingle, &call.caller);
+                event.to = Some(holder.clone());
+                event.token_id = Some(*token_id);
+                event.amount = Some(*amount);
+                this.emit(event);
+            }
+            for (owner, operator) in &dump.approvals {
+                let now = this.clock.now();
+                this.approvals.set(owner, operator, None, now);
+                let mut event = EventRecord::new(this.next_event_seq(), EventKind::ApprovalForAll, operator);
+                event.from = Some(owner.clone());
+                event.approved = Some(true);
+                this.emit(event);
+            }
+
+            let mut holders: Vec<&String> = dump.balances.keys().map(|(_, holder)| holder).collect();
+            holders.sort_unstable();
+            holders.dedup();
+            Ok(MigrationReport {
+                balances_imported: dump.balances.len() as u32,
+                approvals_imported: dump.approvals.len() as u32,
+                holders: holders.len() as u32,
+                mismatches: dump
+                    .totals
+                    .as_ref()
+                    .map(|expected| compare_totals(expected, &totals))
+                    .unwrap_or_default(),
+                totals: totals
+                    .into_iter()
+                    .map(|(token_id, total)| TokenTotal {
+                        token_id,
+                        total: total as u64,
+                    })
+                    .collect(),
+            })
+        })
+    }
+
+    /// Compares this contract's per-token totals with the expected ones and returns the differences.
+    /// # Parameters
+    /// - `expected`: JSON object mapping token IDs to totals, e.g. `{ "1": "1000", "0x2": 5 }`.
+    pub fn verify_totals(&self, expected: &str) -> Result<Vec<TotalMismatch>> {
+        let expected: HashMap<String, Number> =
+            serde_json::from_str(expected).map_err(|err| invalid(format!("Invalid totals JSON: {}", err)))?;
+        let mut actual: BTreeMap<u32, u128> = BTreeMap::new();
+        for (_, token_id, balance) in self.balances.iter() {
+            *actual.entry(token_id).or_insert(0) += u128::from(balance);
+        }
+        actual.retain(|_, total| *total > 0);
+        Ok(compare_totals(&parse_totals(&expected)?, &actual))
+    }
+}
@@ -0,0 +1,140 @@
+//! Human-readable account names.
+//!
+//! Any account can register one name, such as `alice`, and then be referred to as
+//! `@alice` wherever a method takes an address. Mutating calls resolve names in
+//! the pipeline and reject unregistered ones; views resolve them too, so an
+//! unknown name reads like an account that holds nothing.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::gas::GasCost;
+use crate::pipeline::{canonical_account, CallKind};
+use crate::{ContractError, ErrorKind, EventKind, EventRecord, Receipt, Result, ERC1155};
+
+/// Marks an address argument as a registered name.
+pub(crate) const NAME_PREFIX: &str = "@";
+const MAX_NAME_LEN: usize = 32;
+
+/// Registered names, stored lowercase and without the prefix.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub(crate) struct Names {
+    /// Name -> account.
+    accounts: HashMap<String, String>,
+    /// Account -> name.
+    names: HashMap<String, String>,
+}
+
+/// Returns `name` lowercased and without the prefix, or an error if it is not a valid name.
+fn normalize_name(name: &str) -> Result<String> {
+    let name = name.trim();
+    let name = name.strip_prefix(NAME_PREFIX).unwrap_or(name).to_ascii_lowercase();
+    let valid_char = |byte: u8| byte.is_ascii_alphanumeric() || byte == b'-' || byte == b'_' || byte == b'.';
+    if name.is_empty() || name.len() > MAX_NAME_LEN || !name.bytes().all(valid_char) {
+        return Err(ContractError::new(
+            ErrorKind::InvalidInput,
+            "Names are 1 to 32 letters, digits, '-', '_' or '.'.",
+        ));
+    }
+    Ok(name)
+}
+
+impl ERC1155 {
+    /// Returns the canonical form of `account`, with a registered `@name` replaced by its
+    /// account. Unregistered names are returned unchanged.
+    pub(crate) fn resolve_account<'a>(&'a self, account: &'a str) -> Cow<'a, str> {
+        let account = canonical_account(account);
+        let resolved = account
+            .strip_prefix(NAME_PREFIX)
+            .and_then(|name| self.names.accounts.get(&name.to_ascii_lowercase()));
+        match resolved {
+            Some(resolved) => Cow::Borrowed(resolved.as_str()),
+            None => account,
+        }
+    }
+
+    /// Replaces a normalized `@name` argument of a mutating call by its account.
+    pub(crate) fn resolve_name(&self, account: String) -> Result<String> {
+        let name = match account.strip_prefix(NAME_PREFIX) {
+            Some(name) => name,
+            None => return Ok(account),
+        };
+        self.names
+            .accounts
+            .get(&name.to_ascii_lowercase())
+            .cloned()
+            .ok_or_else(|| ContractError::new(ErrorKind::InvalidInput, format!("Name {} is not registered.", account)))
+    }
+}
+
+#[wasm_bindgen]
+impl ERC1155 {
+    /// Registers `name` for the caller, replacing the caller's previous name. The name may
+    /// be given with or without the leading `@` and is matched case-insensitively.
+    pub fn register_name(&mut self, caller: &str, name: &str) -> Result<Receipt> {
+        self.enter("register_name", CallKind::User, caller, &[], |this, call| {
+            let name = normalize_name(name)?;
+            if this.names.accounts.contains_key(&name) {
+                return Err(ContractError::new(ErrorKind::InvalidInput, "Name is already registered."));
+            }
+            this.charge_gas(GasCost {
+                reads: 1,
+                writes: 2,
+                events: 1,
+                ..GasCost::default()
+            })?;
+
+            if let Some(previous) = this.names.names.insert(call.caller.clone(), name.clone()) {
+                this.names.accounts.remove(&previous);
+            }
+            this.names.accounts.insert(name.clone(), call.caller.clone());
+            let mut event = EventRecord::new(this.next_event_seq(), EventKind::NameUpdated, &call.caller);
+            event.to = Some(call.caller.clone());
+            event.approved = Some(true);
+            event.value = Some(name);
+            this.emit(event);
+            Ok(this.receipt(Vec::new()))
+        })
+    }
+
+    /// Releases the caller's name so another account can register it.
+    pub fn release_name(&mut self, caller: &str) -> Result<Receipt> {
+        self.enter("release_name", CallKind::User, caller, &[], |this, call| {
+            let name = this
+                .names
+                .names
+                .get(&call.caller)
+                .cloned()
+                .ok_or_else(|| ContractError::new(ErrorKind::InvalidInput, "Caller has no registered name."))?;
+            this.charge_gas(GasCost {
+                reads: 1,
+                writes: 2,
+                events: 1,
+                ..GasCost::default()
+            })?;
+
+            this.names.names.remove(&call.caller);
+            this.names.accounts.remove(&name);
+            let mut event = EventRecord::new(this.next_event_seq(), EventKind::NameUpdated, &call.caller);
+            event.to = Some(call.caller.clone());
+            event.approved = Some(false);
+            event.value = Some(name);
+            this.emit(event);
+            Ok(this.receipt(Vec::new()))
+        })
+    }
+
+    /// Returns the account registered under `name`, given with or without the leading `@`.
+    pub fn resolve(&self, name: &str) -> Option<String> {
+        let name = normalize_name(name).ok()?;
+        self.names.accounts.get(&name).cloned()
+    }
+
+    /// Returns the name registered by `account`, without the leading `@`.
+    pub fn name_of(&self, account: &str) -> Option<String> {
+        self.names.names.get(self.resolve_account(account).as_ref()).cloned()
+    }
+}
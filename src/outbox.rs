@@ -0,0 +1,74 @@
+//! Outbox: ordered, acknowledged hand-off of the event log to an external indexer.
+//!
+//! `drain_pending_events` returns the events emitted since the last drain as
+//! JSON lines and moves the delivery cursor past them, so each event is handed
+//! out once. The host confirms that a batch reached its destination with
+//! `ack_events`; if forwarding failed, `redeliver_unacknowledged` rewinds the
+//! cursor to the last acknowledged event. Events of an open transaction are
+//! held back until it is committed. Like the transaction controls these are
+//! host-side and take no caller argument.
+
+use wasm_bindgen::prelude::*;
+
+use crate::{ContractError, ErrorKind, Result, ERC1155};
+
+/// Delivery cursors into the event log; both are sequence numbers of the next event.
+#[derive(Clone, Default)]
+pub(crate) struct Outbox {
+    delivered: u64,
+    acknowledged: u64,
+}
+
+impl ERC1155 {
+    /// Sequence number of the first event that may not be delivered yet, because it
+    /// belongs to an open transaction or has not been emitted.
+    fn outbox_end(&self) -> u64 {
+        self.transaction
+            .as_ref()
+            .map_or_else(|| self.next_event_seq(), |checkpoint| checkpoint.next_event_seq())
+    }
+}
+
+#[wasm_bindgen]
+impl ERC1155 {
+    /// Returns up to `max` events not delivered yet, oldest first, as JSON lines (one
+    /// `EventRecord` object per line, each ending with a newline), and marks them delivered.
+    pub fn drain_pending_events(&mut self, max: usize) -> String {
+        let start = self.outbox.delivered;
+        let end = self.outbox_end().min(start.saturating_add(max as u64));
+        let mut lines = String::new();
+        for event in self.events.range(start as usize..end as usize) {
+            lines.push_str(&serde_json::to_string(event).expect("events are always serializable"));
+            lines.push('\n');
+        }
+        self.outbox.delivered = end;
+        lines
+    }
+
+    /// Confirms that every delivered event up to and including `through_seq` was
+    /// forwarded, so it is never redelivered.
+    pub fn ack_events(&mut self, through_seq: u64) -> Result<()> {
+        if through_seq >= self.outbox.delivered {
+            return Err(ContractError::new(ErrorKind::InvalidInput, "Event has not been delivered."));
+        }
+        self.outbox.acknowledged = self.outbox.acknowledged.max(through_seq + 1);
+        Ok(())
+    }
+
+    /// Makes the delivered but unacknowledged events pending again and returns how many.
+    pub fn redeliver_unacknowledged(&mut self) -> u64 {
+        let count = self.outbox.delivered - self.outbox.acknowledged;
+        self.outbox.delivered = self.outbox.acknowledged;
+        count
+    }
+
+    /// Returns the number of events waiting to be drained.
+    pub fn pending_event_count(&self) -> u64 {
+        self.outbox_end() - self.outbox.delivered
+    }
+
+    /// Returns the number of delivered events waiting for `ack_events`.
+    pub fn unacknowledged_event_count(&self) -> u64 {
+        self.outbox.delivered - self.outbox.acknowledged
+    }
+}
@@ -0,0 +1,280 @@
+//! Pre-call pipeline shared by every public mutating method.
+//!
+//! Each call runs, in order: the batch size check, caller/account normalization,
+//! validation and name resolution, the immutability check, the pause check, the
+//! blacklist check, the base gas charge, and finally the reentrancy lock, which
+//! also opens the call's audit log entry. Methods only contain their own
+//! authorization and business logic; new policies are added here so every entry
+//! point picks them up at once. Amounts and token ID arrays are validated by the
+//! shared checks of the methods that take them.
+
+use std::borrow::Cow;
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::audit::PendingOperation;
+use crate::escrow::RESERVED_PREFIX;
+use crate::guard::Guarded;
+use crate::{log, ContractError, ErrorKind, EventKind, EventRecord, Receipt, Result, ERC1155};
+
+/// Longest address accepted by mutating calls, in bytes.
+const MAX_ADDRESS_LEN: usize = 128;
+/// Batch size limit until the owner or an admin sets another.
+const DEFAULT_MAX_BATCH_SIZE: u32 = 1_000;
+
+/// Contract-wide policy state consulted by the pipeline.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub(crate) struct Policy {
+    paused: bool,
+    #[serde(serialize_with = "crate::canonical::sorted")]
+    blacklist: HashSet<String>,
+    /// Set for good when ownership is renounced with the configuration frozen.
+    immutable: bool,
+    /// Most items a batch call may carry; `None` means `DEFAULT_MAX_BATCH_SIZE`.
+    max_batch_size: Option<u32>,
+}
+
+/// Which policies apply to a call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CallKind {
+    /// Token movements and approvals: blocked while paused or for blacklisted accounts.
+    User,
+    /// Configuration, of the contract by the owner and admins or of a token by its creator:
+    /// always allowed so a paused contract can be managed.
+    Admin,
+}
+
+/// Inputs of a call after they passed the pipeline.
+pub(crate) struct Call {
+    /// Normalized caller address.
+    pub caller: String,
+    /// Normalized addresses of the other accounts the call touches, in the order given.
+    pub accounts: Vec<String>,
+}
+
+/// Returns the canonical form of an address: surrounding whitespace removed and
+/// `0x`-prefixed hex addresses lowercased. Borrows when nothing changes.
+pub(crate) fn canonical_account(account: &str) -> Cow<'_, str> {
+    let trimmed = account.trim();
+    let is_hex = trimmed.len() > 2
+        && (trimmed.starts_with("0x") || trimmed.starts_with("0X"))
+        && trimmed[2..].bytes().all(|byte| byte.is_ascii_hexdigit());
+    if is_hex && trimmed.bytes().any(|byte| byte.is_ascii_uppercase()) {
+        Cow::Owned(trimmed.to_ascii_lowercase())
+    } else {
+        Cow::Borrowed(trimmed)
+    }
+}
+
+/// Canonicalizes an address supplied to a mutating call, rejecting empty, oversized and
+/// reserved ones.
+pub(crate) fn normalize_account(account: &str) -> Result<String> {
+    let account = canonical_account(account);
+    if account.is_empty() {
+        return Err(ContractError::new(ErrorKind::InvalidInput, "Address must not be empty."));
+    }
+    if account.len() > MAX_ADDRESS_LEN {
+        return Err(ContractError::new(ErrorKind::InvalidInput, "Address must be at most 128 bytes."));
+    }
+    if account.starts_with(RESERVED_PREFIX) {
+        return Err(ContractError::new(ErrorKind::InvalidInput, "Address is reserved for the contract."));
+    }
+    Ok(account.into_owned())
+}
+
+impl ERC1155 {
+    /// Runs the pre-call pipeline and, if every check passes, runs `body` with the
+    /// reentrancy guard locked and records the call in the audit log if it succeeds.
+    /// # Parameters
+    /// - `method`: Name of the public method, used for diagnostics.
+    /// - `kind`: Which policies apply.
+    /// - `caller`: The address calling the method.
+    /// - `accounts`: Other addresses the call touches (sender, recipient, operator, ...).
+    /// - `body`: The method itself, given the guarded contract and the normalized inputs.
+    pub(crate) fn enter<T>(
+        &mut self,
+        method: &'static str,
+        kind: CallKind,
+        caller: &str,
+        accounts: &[&str],
+        body: impl FnOnce(&mut Guarded<'_>, Call) -> Result<T>,
+    ) -> Result<T> {
+        let checked = self
+            .check_call(kind, caller, accounts)
+            .and_then(|call| self.gas.start(kind).map(|()| call));
+        let call = checked.map_err(|err| {
+            log(&format!("{} rejected: {}", method, err));
+            self.error_stats.record(method, err.kind());
+            err
+        })?;
+        let operation = PendingOperation {
+            method,
+            caller: call.caller.clone(),
+            accounts: call.accounts.clone(),
+            timestamp: self.clock.now(),
+            first_event_seq: self.next_event_seq(),
+            request: self.request.take(),
+        };
+        let mut guarded = self.guarded()?;
+        guarded.operation = Some(operation);
+        let result = body(&mut guarded, call);
+        guarded.finish(&result);
+        result
+    }
+
+    /// Fails once the contract is immutable. Besides every `CallKind::Admin` call, this
+    /// guards the privileged user calls: minting and deposits.
+    pub(crate) fn check_configurable(&self) -> Result<()> {
+        if self.policy.immutable {
+            return Err(ContractError::new(ErrorKind::Unauthorized, "Contract configuration is frozen."));
+        }
+        Ok(())
+    }
+
+    /// Fails if a batch of `len` items exceeds the maximum batch size.
+    pub(crate) fn check_batch_size(&self, len: usize) -> Result<()> {
+        let max = self.max_batch_size();
+        if len > max as usize {
+            return Err(ContractError::new(
+                ErrorKind::InvalidInput,
+                format!("Batch of {} items exceeds the maximum of {}.", len, max),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Makes the contract immutable; see `ERC1155::renounce_ownership`.
+    pub(crate) fn make_immutable(&mut self) {
+        self.policy.immutable = true;
+    }
+
+    /// Normalizes and resolves the accounts of a call and checks the policies that apply
+    /// to it, as `enter` does before running the call.
+    pub(crate) fn check_call(&self, kind: CallKind, caller: &str, accounts: &[&str]) -> Result<Call> {
+        self.check_batch_size(accounts.len())?;
+        let call = Call {
+            caller: self.resolve_name(normalize_account(caller)?)?,
+            accounts: accounts
+                .iter()
+                .map(|account| self.resolve_name(normalize_account(account)?))
+                .collect::<Result<_>>()?,
+        };
+
+        if kind == CallKind::Admin {
+            self.check_configurable()?;
+        }
+        if kind == CallKind::User {
+            if self.policy.paused {
+                return Err(ContractError::new(ErrorKind::Paused, "Contract is paused."));
+            }
+            let mut involved = std::iter::once(&call.caller).chain(&call.accounts);
+            if let Some(account) = involved.find(|account| self.policy.blacklist.contains(*account)) {
+                return Err(ContractError::new(
+                    ErrorKind::Blacklisted,
+                    format!("Account {} is blacklisted.", account),
+                ));
+            }
+        }
+        Ok(call)
+    }
+}
+
+#[wasm_bindgen]
+impl ERC1155 {
+    /// Pauses all token movements and approvals (only the owner can pause).
+    pub fn pause(&mut self, caller: &str) -> Result<Receipt> {
+        self.set_paused("pause", caller, true)
+    }
+
+    /// Resumes token movements and approvals (only the owner can unpause).
+    pub fn unpause(&mut self, caller: &str) -> Result<Receipt> {
+        self.set_paused("unpause", caller, false)
+    }
+
+    /// Returns whether the contract is paused.
+    pub fn is_paused(&self) -> bool {
+        self.policy.paused
+    }
+
+    /// Adds `account` to or removes it from the blacklist (owner or admins only).
+    /// Blacklisted accounts can neither send, receive, operate nor approve.
+    pub fn set_blacklisted(&mut self, caller: &str, account: &str, blacklisted: bool) -> Result<Receipt> {
+        self.enter("set_blacklisted", CallKind::Admin, caller, &[account], |this, call| {
+            if !this.access_control.is_owner(&call.caller) && !this.access_control.is_admin(&call.caller) {
+                return Err(ContractError::new(
+                    ErrorKind::Unauthorized,
+                    "Caller is not authorized to manage the blacklist.",
+                ));
+            }
+
+            let account = &call.accounts[0];
+            if blacklisted {
+                this.policy.blacklist.insert(account.clone());
+            } else {
+                this.policy.blacklist.remove(account);
+            }
+
+            let mut event = EventRecord::new(this.next_event_seq(), EventKind::BlacklistUpdated, &call.caller);
+            event.to = Some(account.clone());
+            event.approved = Some(blacklisted);
+            this.emit(event);
+            Ok(this.receipt(Vec::new()))
+        })
+    }
+
+    /// Returns whether the configuration was frozen when ownership was renounced, leaving
+    /// only transfers, burns and other holder actions.
+    pub fn is_immutable(&self) -> bool {
+        self.policy.immutable
+    }
+
+    /// Sets the most items a batch call may carry, such as the recipients of an airdrop or
+    /// the token IDs of a batch transfer (owner or admins only). Defaults to 1000.
+    pub fn set_max_batch_size(&mut self, caller: &str, max_batch_size: u32) -> Result<Receipt> {
+        self.enter("set_max_batch_size", CallKind::Admin, caller, &[], |this, call| {
+            if !this.access_control.is_owner(&call.caller) && !this.access_control.is_admin(&call.caller) {
+                return Err(ContractError::new(
+                    ErrorKind::Unauthorized,
+                    "Caller is not authorized to set the batch size.",
+                ));
+            }
+            if max_batch_size == 0 {
+                return Err(ContractError::new(ErrorKind::InvalidInput, "Maximum batch size must be positive."));
+            }
+
+            this.policy.max_batch_size = Some(max_batch_size);
+            let mut event = EventRecord::new(this.next_event_seq(), EventKind::MaxBatchSizeUpdated, &call.caller);
+            event.amount = Some(u64::from(max_batch_size));
+            this.emit(event);
+            Ok(this.receipt(Vec::new()))
+        })
+    }
+
+    /// Returns the most items a batch call may carry.
+    pub fn max_batch_size(&self) -> u32 {
+        self.policy.max_batch_size.unwrap_or(DEFAULT_MAX_BATCH_SIZE)
+    }
+
+    /// Returns whether `account` is blacklisted.
+    pub fn is_blacklisted(&self, account: &str) -> bool {
+        self.policy.blacklist.contains(self.resolve_account(account).as_ref())
+    }
+}
+
+impl ERC1155 {
+    fn set_paused(&mut self, method: &'static str, caller: &str, paused: bool) -> Result<Receipt> {
+        self.enter(method, CallKind::Admin, caller, &[], |this, call| {
+            if !this.access_control.is_owner(&call.caller) {
+                return Err(ContractError::new(ErrorKind::Unauthorized, "Only the owner can pause or unpause."));
+            }
+
+            this.policy.paused = paused;
+            let kind = if paused { EventKind::Paused } else { EventKind::Unpaused };
+            let event = EventRecord::new(this.next_event_seq(), kind, &call.caller);
+            this.emit(event);
+            Ok(this.receipt(Vec::new()))
+        })
+    }
+}
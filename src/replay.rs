@@ -0,0 +1,122 @@
+//! Deterministic replay of recorded calls.
+//!
+//! An operation log lists the requests made through `call`, each with the
+//! contract time it ran at and the `receipt_hash` of its response. `replay`
+//! re-executes them on a new contract and checks every result against its
+//! hash, so a log that replays reproduces the original outcomes; an optional
+//! final `state_hash` confirms the resulting state. The log must start from the
+//! contract's creation; `call` routes every method that records an operation.
+//!
+//! `export_replay_log` produces such a log from the contract's own operation
+//! log, as long as every operation was made through `call`: calls made through
+//! the typed methods record no request, so hosts that need a replayable log
+//! make every mutating call through `call`.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use wasm_bindgen::prelude::*;
+
+use crate::authorization::decode_hex;
+use crate::hash::receipt_hash;
+use crate::{ContractError, ErrorKind, Result, ERC1155};
+
+#[derive(Serialize, Deserialize)]
+struct OperationLog {
+    owner: String,
+    /// `instance_id` of the recorded contract; a new one is drawn when missing.
+    #[serde(default)]
+    instance_id: Option<String>,
+    operations: Vec<RecordedCall>,
+    /// Expected `state_hash` after the last call.
+    #[serde(default)]
+    state_hash: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RecordedCall {
+    method: String,
+    #[serde(default)]
+    params: Map<String, Value>,
+    #[serde(default)]
+    caller: String,
+    /// Contract time of the call in milliseconds; the host clock is used when missing.
+    #[serde(default)]
+    timestamp: Option<u64>,
+    receipt_hash: String,
+}
+
+/// A request as kept in `OperationRecord::request`.
+#[derive(Deserialize)]
+struct RecordedRequest {
+    method: String,
+    params: Map<String, Value>,
+}
+
+#[wasm_bindgen]
+impl ERC1155 {
+    /// Builds a contract by re-executing an operation log, given as JSON
+    /// `{owner, instance_id, operations: [{method, params, caller, timestamp, receipt_hash}], state_hash}`.
+    /// Fails at the first call that fails or whose result does not match its hash, or if
+    /// the final state does not match `state_hash` when given. The new contract follows
+    /// the host clock.
+    pub fn replay(operations_json: &str) -> Result<ERC1155> {
+        let log: OperationLog = serde_json::from_str(operations_json)
+            .map_err(|err| ContractError::new(ErrorKind::InvalidInput, format!("Invalid operation log: {}", err)))?;
+        let mut contract = ERC1155::new(&log.owner)?;
+        if let Some(instance_id) = &log.instance_id {
+            let instance_id = decode_hex(instance_id, 16, "Instance ID")?;
+            contract.instance_id.copy_from_slice(&instance_id);
+        }
+        for (index, operation) in log.operations.iter().enumerate() {
+            contract.clock.pin(operation.timestamp);
+            let result = contract
+                .dispatch(&operation.method, &operation.caller, &operation.params)
+                .map_err(|err| ContractError::new(err.kind(), format!("Operation {} failed: {}", index, err)))?;
+            if receipt_hash(&result) != operation.receipt_hash {
+                return Err(ContractError::new(
+                    ErrorKind::InvalidInput,
+                    format!("Operation {} does not match its receipt hash.", index),
+                ));
+            }
+        }
+        if log.state_hash.is_some_and(|hash| hash != contract.state_hash()) {
+            return Err(ContractError::new(ErrorKind::InvalidInput, "Replayed state does not match the state hash."));
+        }
+        contract.clock.pin(None);
+        Ok(contract)
+    }
+
+    /// Returns the operation log of this contract in the input format of `replay`, with
+    /// the `state_hash` of the current state. Fails if an operation was not made through
+    /// `call`, since only its request can be replayed.
+    pub fn export_replay_log(&self) -> Result<String> {
+        let mut operations = Vec::with_capacity(self.operations.len());
+        for operation in self.operations.iter_from(0) {
+            let not_replayable = || {
+                ContractError::new(
+                    ErrorKind::InvalidInput,
+                    format!("Operation {} was not made through `call`.", operation.id),
+                )
+            };
+            let request: RecordedRequest = operation
+                .request
+                .as_deref()
+                .and_then(|request| serde_json::from_str(request).ok())
+                .ok_or_else(not_replayable)?;
+            operations.push(RecordedCall {
+                method: request.method,
+                params: request.params,
+                caller: operation.caller.clone(),
+                timestamp: Some(operation.timestamp),
+                receipt_hash: operation.receipt_hash.clone().ok_or_else(not_replayable)?,
+            });
+        }
+        let log = OperationLog {
+            owner: self.created_by.clone(),
+            instance_id: Some(self.instance_id()),
+            operations,
+            state_hash: Some(self.state_hash()),
+        };
+        Ok(serde_json::to_string(&log).expect("operation logs are always serializable"))
+    }
+}
@@ -0,0 +1,243 @@
+//! Pro-rata reward distribution.
+//!
+//! `distribute_to_holders` mints an amount of a reward token and splits it
+//! between the holders of a source token in proportion to their balances at a
+//! snapshot, in a single call. Shares are rounded down and the dust is not
+//! minted. In direct mode every holder is credited right away; in claim mode
+//! the rewards sit in escrow until each holder collects its share with
+//! `claim_reward`. Balances held by the contract itself or by blacklisted
+//! accounts earn nothing and are left out of the split.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::escrow::{ESCROW_ACCOUNT, RESERVED_PREFIX};
+use crate::gas::GasCost;
+use crate::pipeline::CallKind;
+use crate::{ContractError, ErrorKind, EventKind, EventRecord, Receipt, Result, ERC1155};
+
+/// A distribution and, in claim mode, the shares not collected yet.
+#[derive(Clone, Serialize, Deserialize)]
+pub(crate) struct Distribution {
+    source_token_id: u32,
+    snapshot_id: u64,
+    reward_token_id: u32,
+    distributed: u64,
+    recipients: u32,
+    claim: bool,
+    /// Holder -> unclaimed share; always empty in direct mode.
+    unclaimed: HashMap<String, u64>,
+}
+
+/// Distributions, indexed by ID minus one.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub(crate) struct Rewards {
+    distributions: Vec<Distribution>,
+}
+
+/// Read-only view of a distribution.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DistributionInfo {
+    #[wasm_bindgen(readonly)]
+    pub id: u64,
+    /// Token whose snapshot balances set each holder's share.
+    #[wasm_bindgen(readonly)]
+    pub source_token_id: u32,
+    #[wasm_bindgen(readonly)]
+    pub snapshot_id: u64,
+    #[wasm_bindgen(readonly)]
+    pub reward_token_id: u32,
+    /// Reward tokens minted, which is the requested amount minus rounding dust.
+    #[wasm_bindgen(readonly)]
+    pub distributed: u64,
+    /// Number of holders that received a share.
+    #[wasm_bindgen(readonly)]
+    pub recipients: u32,
+    /// Whether holders collect their shares with `claim_reward`.
+    #[wasm_bindgen(readonly)]
+    pub claimable: bool,
+    /// Reward tokens still waiting in escrow to be claimed.
+    #[wasm_bindgen(readonly)]
+    pub unclaimed: u64,
+}
+
+impl ERC1155 {
+    fn distribution_entry(&self, distribution_id: u64) -> Result<&Distribution> {
+        distribution_id
+            .checked_sub(1)
+            .and_then(|index| self.rewards.distributions.get(index as usize))
+            .ok_or_else(|| ContractError::new(ErrorKind::InvalidInput, "Distribution does not exist."))
+    }
+
+    /// Splits `amount` between the holders of `token_id` at `snapshot_id`, rounding down.
+    /// Returns the non-zero shares ordered by holder. Blacklisted holders are skipped.
+    fn reward_shares(&self, token_id: u32, snapshot_id: u64, amount: u64) -> Vec<(String, u64)> {
+        let mut holders: Vec<(String, u64)> = self
+            .balances
+            .holders_at(token_id, snapshot_id)
+            .filter(|(holder, _)| !holder.starts_with(RESERVED_PREFIX) && !self.is_blacklisted(holder))
+            .collect();
+        holders.sort_unstable();
+        let total: u128 = holders.iter().map(|(_, balance)| u128::from(*balance)).sum();
+        holders
+            .into_iter()
+            .map(|(holder, balance)| {
+                // Never above `amount`, since `balance <= total`.
+                let share = u128::from(amount) * u128::from(balance) / total;
+                (holder, share as u64)
+            })
+            .filter(|(_, share)| *share > 0)
+            .collect()
+    }
+}
+
+#[wasm_bindgen]
+impl ERC1155 {
+    /// Mints `total_amount` of `reward_token_id` split between the holders of
+    /// `source_token_id` in proportion to their balances at `snapshot_id`, and returns the
+    /// distribution's ID. The caller needs the right to mint the reward token.
+    /// # Parameters
+    /// - `claimable`: Whether the rewards wait in escrow for holders to `claim_reward` them,
+    ///   rather than being credited to every holder at once.
+    pub fn distribute_to_holders(
+        &mut self,
+        caller: &str,
+        source_token_id: u32,
+        snapshot_id: u64,
+        reward_token_id: u32,
+        total_amount: u64,
+        claimable: bool,
+    ) -> Result<u64> {
+        self.enter("distribute_to_holders", CallKind::User, caller, &[], |this, call| {
+            this.check_snapshot(snapshot_id)?;
+            if total_amount == 0 {
+                return Err(ContractError::new(ErrorKind::InvalidInput, "Reward amount must be positive."));
+            }
+            let shares = this.reward_shares(source_token_id, snapshot_id, total_amount);
+            if shares.is_empty() {
+                return Err(ContractError::new(
+                    ErrorKind::InvalidInput,
+                    "No holder of the snapshot is owed a share of the reward.",
+                ));
+            }
+            let distributed: u64 = shares.iter().map(|(_, share)| share).sum();
+            this.check_mint(&call.caller, reward_token_id, distributed)?;
+            let overflows = if claimable {
+                this.balances.get(ESCROW_ACCOUNT, reward_token_id).checked_add(distributed).is_none()
+            } else {
+                shares
+                    .iter()
+                    .any(|(holder, share)| this.balances.get(holder, reward_token_id).checked_add(*share).is_none())
+            };
+            if overflows {
+                return Err(ContractError::new(ErrorKind::Overflow, "Recipient balance overflow."));
+            }
+            let recipients = shares.len() as u64;
+            let credits = if claimable { 1 } else { recipients };
+            this.charge_gas(GasCost {
+                reads: 2 + recipients,
+                writes: 1 + credits,
+                events: 1 + credits,
+                iterations: recipients,
+            })?;
+
+            this.tokens.create(reward_token_id, &call.caller);
+            let mut unclaimed = HashMap::new();
+            if claimable {
+                this.move_tokens(&call.caller, None, Some(ESCROW_ACCOUNT), reward_token_id, distributed)?;
+                unclaimed.extend(shares);
+            } else {
+                for (holder, share) in &shares {
+                    this.move_tokens(&call.caller, None, Some(holder), reward_token_id, *share)?;
+                }
+            }
+            this.rewards.distributions.push(Distribution {
+                source_token_id,
+                snapshot_id,
+                reward_token_id,
+                distributed,
+                recipients: recipients as u32,
+                claim: claimable,
+                unclaimed,
+            });
+
+            let distribution_id = this.rewards.distributions.len() as u64;
+            let mut event = EventRecord::new(this.next_event_seq(), EventKind::RewardsDistributed, &call.caller);
+            event.token_id = Some(reward_token_id);
+            event.amount = Some(distributed);
+            event.approved = Some(claimable);
+            event.value = Some(distribution_id.to_string());
+            this.emit(event);
+            Ok(distribution_id)
+        })
+    }
+
+    /// Releases the caller's share of a claim-mode distribution from escrow.
+    pub fn claim_reward(&mut self, caller: &str, distribution_id: u64) -> Result<Receipt> {
+        self.enter("claim_reward", CallKind::User, caller, &[], |this, call| {
+            let distribution = this.distribution_entry(distribution_id)?;
+            let share = distribution.unclaimed.get(&call.caller).copied().unwrap_or(0);
+            if share == 0 {
+                return Err(ContractError::new(ErrorKind::InvalidInput, "Caller has no reward to claim."));
+            }
+            let reward_token_id = distribution.reward_token_id;
+            this.charge_gas(GasCost {
+                reads: 2,
+                writes: 3,
+                events: 2,
+                ..GasCost::default()
+            })?;
+
+            let changes = this.move_tokens(
+                &call.caller,
+                Some(ESCROW_ACCOUNT),
+                Some(&call.caller),
+                reward_token_id,
+                share,
+            )?;
+            this.rewards.distributions[distribution_id as usize - 1]
+                .unclaimed
+                .remove(&call.caller);
+            let mut event = EventRecord::new(this.next_event_seq(), EventKind::RewardClaimed, &call.caller);
+            event.to = Some(call.caller.clone());
+            event.token_id = Some(reward_token_id);
+            event.amount = Some(share);
+            event.value = Some(distribution_id.to_string());
+            this.emit(event);
+            Ok(this.receipt(changes))
+        })
+    }
+
+    /// Returns a distribution; `undefined` if it does not exist.
+    pub fn distribution(&self, distribution_id: u64) -> Option<DistributionInfo> {
+        let distribution = self.distribution_entry(distribution_id).ok()?;
+        Some(DistributionInfo {
+            id: distribution_id,
+            source_token_id: distribution.source_token_id,
+            snapshot_id: distribution.snapshot_id,
+            reward_token_id: distribution.reward_token_id,
+            distributed: distribution.distributed,
+            recipients: distribution.recipients,
+            claimable: distribution.claim,
+            // Shares add up to `distributed`, so this cannot overflow.
+            unclaimed: distribution.unclaimed.values().sum(),
+        })
+    }
+
+    /// Returns the number of distributions made so far.
+    pub fn distribution_count(&self) -> u64 {
+        self.rewards.distributions.len() as u64
+    }
+
+    /// Returns the share of a claim-mode distribution `account` has not claimed yet.
+    pub fn claimable_reward(&self, distribution_id: u64, account: &str) -> u64 {
+        self.distribution_entry(distribution_id)
+            .ok()
+            .and_then(|distribution| distribution.unclaimed.get(self.resolve_account(account).as_ref()))
+            .copied()
+            .unwrap_or(0)
+    }
+}
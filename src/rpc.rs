@@ -0,0 +1,371 @@
+//! JSON-RPC-style entry point.
+//!
+//! `call` takes a `{method, params, caller, id}` request as JSON, runs the named
+//! method and answers with an envelope: `{id, ok: true, result}` on success or
+//! `{id, ok: false, error: {code, message}}` on failure, where `code` is the
+//! same string as the `name` of errors thrown by direct calls. Parameters are
+//! passed by name, using the argument names of the methods themselves. Every
+//! method that records an operation is routed, so a host can drive the contract
+//! through `call` alone, along with the core query methods; see `route`.
+//!
+//! Successful responses also carry the `receipt_hash` of their result, which
+//! `replay` checks when re-executing recorded requests.
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use wasm_bindgen::prelude::*;
+
+use crate::hash::receipt_hash;
+use crate::{ContractError, ErrorKind, Result, ERC1155};
+
+#[derive(Deserialize)]
+struct Request {
+    method: String,
+    #[serde(default)]
+    params: Map<String, Value>,
+    /// Required by mutating methods only.
+    #[serde(default)]
+    caller: Option<String>,
+    /// Echoed back so callers can match responses to requests.
+    #[serde(default)]
+    id: Value,
+}
+
+/// Named parameters of a request.
+struct Params<'a>(&'a Map<String, Value>);
+
+impl Params<'_> {
+    fn get<T: DeserializeOwned>(&self, name: &str) -> Result<T> {
+        let value = self.0.get(name).cloned().unwrap_or(Value::Null);
+        serde_json::from_value(value).map_err(|err| {
+            ContractError::new(ErrorKind::InvalidInput, format!("Invalid parameter `{}`: {}", name, err))
+        })
+    }
+}
+
+fn to_value<T: Serialize>(result: Result<T>) -> Result<Value> {
+    result.map(|value| serde_json::to_value(value).expect("call results are always serializable"))
+}
+
+impl ERC1155 {
+    /// Runs `method` with named `params` and returns its result as JSON. The request and
+    /// the `receipt_hash` of the result are logged with the operation the method records.
+    pub(crate) fn dispatch(&mut self, method: &str, caller: &str, params: &Map<String, Value>) -> Result<Value> {
+        let operations = self.operations.len();
+        self.request = Some(json!({ "method": method, "params": params }).to_string());
+        let result = self.route(method, caller, params);
+        self.request = None;
+        if let Ok(value) = &result {
+            if self.operations.len() == operations + 1 {
+                let operation = self.operations.last_mut().expect("the operation was just recorded");
+                operation.receipt_hash = Some(receipt_hash(value));
+            }
+        }
+        result
+    }
+
+    fn route(&mut self, method: &str, caller: &str, params: &Map<String, Value>) -> Result<Value> {
+        let p = &Params(params);
+        match method {
+            "mint" => to_value(self.mint(caller, p.get("token_id")?, p.get("amount")?)),
+            "mint_to_many" => to_value(self.mint_to_many(
+                caller,
+                p.get("token_id")?,
+                p.get("recipients")?,
+                p.get("amounts")?,
+            )),
+            "transfer" => to_value(self.transfer(
+                caller,
+                &p.get::<String>("to")?,
+                p.get("token_id")?,
+                p.get("amount")?,
+            )),
+            "transfer_from" => to_value(self.transfer_from(
+                caller,
+                &p.get::<String>("from")?,
+                &p.get::<String>("to")?,
+                p.get("token_id")?,
+                p.get("amount")?,
+            )),
+            "transfer_batch" => to_value(self.transfer_batch(
+                caller,
+                &p.get::<String>("to")?,
+                p.get("token_ids")?,
+                p.get("amounts")?,
+            )),
+            "burn" => to_value(self.burn(caller, p.get("token_id")?, p.get("amount")?)),
+            "burn_from" => to_value(self.burn_from(
+                caller,
+                &p.get::<String>("from")?,
+                p.get("token_id")?,
+                p.get("amount")?,
+            )),
+            "approve" => to_value(self.approve(caller, &p.get::<String>("approved")?, p.get("token_id")?)),
+            "approve_until" => to_value(self.approve_until(
+                caller,
+                &p.get::<String>("operator")?,
+                p.get("expires_at")?,
+            )),
+            "revoke_approval" => to_value(self.revoke_approval(caller, &p.get::<String>("operator")?)),
+            "add_admin" => to_value(self.add_admin(caller, &p.get::<String>("new_admin")?)),
+            "transfer_ownership" => to_value(self.transfer_ownership(caller, &p.get::<String>("new_owner")?)),
+            "pause" => to_value(self.pause(caller)),
+            "unpause" => to_value(self.unpause(caller)),
+            "set_token_uri" => to_value(self.set_token_uri(caller, p.get("token_id")?, &p.get::<String>("uri")?)),
+            "register_name" => to_value(self.register_name(caller, &p.get::<String>("name")?)),
+            "take_snapshot" => to_value(self.take_snapshot(caller)),
+            "revoke_all_approvals" => to_value(self.revoke_all_approvals(caller)),
+            "renounce_ownership" => to_value(self.renounce_ownership(caller, p.get("freeze_configuration")?)),
+            "mint_to_many_same" => to_value(self.mint_to_many_same(
+                caller,
+                p.get("token_id")?,
+                p.get("recipients")?,
+                p.get("amount")?,
+            )),
+            "approve_scoped" => to_value(self.approve_scoped(
+                caller,
+                &p.get::<String>("operator")?,
+                p.get("token_ids")?,
+                p.get("amounts")?,
+            )),
+            "set_treasury_account" => to_value(self.set_treasury_account(
+                caller,
+                &p.get::<String>("account")?,
+                p.get("enabled")?,
+            )),
+            "set_mint_authorizer" => to_value(self.set_mint_authorizer(caller, p.get("public_key")?)),
+            "mint_with_authorization" => to_value(self.mint_with_authorization(
+                caller,
+                &p.get::<String>("to")?,
+                p.get("token_id")?,
+                p.get("amount")?,
+                p.get("expiry")?,
+                &p.get::<String>("signature")?,
+            )),
+            "set_bridge_operator" => to_value(self.set_bridge_operator(
+                caller,
+                &p.get::<String>("account")?,
+                p.get("enabled")?,
+            )),
+            "bridge_out" => to_value(self.bridge_out(
+                caller,
+                p.get("token_id")?,
+                p.get("amount")?,
+                &p.get::<String>("destination")?,
+            )),
+            "bridge_in" => to_value(self.bridge_in(
+                caller,
+                &p.get::<String>("proof_id")?,
+                &p.get::<String>("to")?,
+                p.get("token_id")?,
+                p.get("amount")?,
+            )),
+            "apply_transfers_bulk" => to_value(self.apply_transfers_bulk(
+                caller,
+                p.get("accounts")?,
+                &p.get::<Vec<u64>>("packed")?,
+            )),
+            "set_token_uses" => to_value(self.set_token_uses(caller, p.get("token_id")?, p.get("uses")?)),
+            "consume" => to_value(self.consume(
+                caller,
+                p.get("token_id")?,
+                p.get("amount")?,
+                &p.get::<String>("context")?,
+            )),
+            "delegate" => to_value(self.delegate(caller, p.get("token_id")?, &p.get::<String>("delegatee")?)),
+            "undelegate" => to_value(self.undelegate(caller, p.get("token_id")?)),
+            "create_drop" => to_value(self.create_drop(
+                caller,
+                &p.get::<String>("commitment")?,
+                p.get("token_ids")?,
+                p.get("weights")?,
+                p.get("registration_period_ms")?,
+            )),
+            "register_for_drop" => to_value(self.register_for_drop(caller, p.get("drop_id")?)),
+            "reveal_drop" => to_value(self.reveal_drop(caller, p.get("drop_id")?, &p.get::<String>("seed")?)),
+            "cancel_drop" => to_value(self.cancel_drop(caller, p.get("drop_id")?)),
+            "set_token_expiry" => to_value(self.set_token_expiry(caller, p.get("token_id")?, p.get("expires_at")?)),
+            "sweep_expired" => to_value(self.sweep_expired(caller, p.get("token_id")?, p.get("limit")?)),
+            "fractionalize" => to_value(self.fractionalize(
+                caller,
+                p.get("nft_id")?,
+                p.get("share_token_id")?,
+                p.get("total_shares")?,
+            )),
+            "redeem" => to_value(self.redeem(caller, p.get("nft_id")?)),
+            "set_gas_schedule" => to_value(self.set_gas_schedule(caller, p.get("schedule")?)),
+            "set_gas_limit" => to_value(self.set_gas_limit(caller, p.get("limit")?)),
+            "create_proposal" => to_value(self.create_proposal(
+                caller,
+                &p.get::<String>("description")?,
+                p.get("token_id")?,
+                p.get("snapshot_id")?,
+                p.get("quorum")?,
+                p.get("voting_period_ms")?,
+            )),
+            "vote" => to_value(self.vote(caller, p.get("proposal_id")?, p.get("support")?)),
+            "set_guardians" => to_value(self.set_guardians(
+                caller,
+                p.get("guardians")?,
+                p.get("threshold")?,
+                p.get("delay_ms")?,
+            )),
+            "support_recovery" => to_value(self.support_recovery(caller, &p.get::<String>("new_owner")?)),
+            "cancel_recovery" => to_value(self.cancel_recovery(caller)),
+            "execute_recovery" => to_value(self.execute_recovery(caller)),
+            "set_attribute" => to_value(self.set_attribute(
+                caller,
+                p.get("token_id")?,
+                &p.get::<String>("key")?,
+                &p.get::<String>("value")?,
+            )),
+            "remove_attribute" => to_value(self.remove_attribute(caller, p.get("token_id")?, &p.get::<String>("key")?)),
+            "set_generative_metadata" => to_value(self.set_generative_metadata(
+                caller,
+                p.get("token_id")?,
+                p.get("enabled")?,
+            )),
+            "set_svg_template" => to_value(self.set_svg_template(caller, p.get("template")?)),
+            "set_base_uri" => to_value(self.set_base_uri(caller, p.get("base_uri")?)),
+            "freeze_metadata" => to_value(self.freeze_metadata(caller, p.get("token_id")?)),
+            "freeze_all_metadata" => to_value(self.freeze_all_metadata(caller)),
+            "import_state" => to_value(self.import_state(
+                caller,
+                &p.get::<String>("data")?,
+                &p.get::<String>("format")?,
+            )),
+            "release_name" => to_value(self.release_name(caller)),
+            "set_blacklisted" => to_value(self.set_blacklisted(
+                caller,
+                &p.get::<String>("account")?,
+                p.get("blacklisted")?,
+            )),
+            "set_max_batch_size" => to_value(self.set_max_batch_size(caller, p.get("max_batch_size")?)),
+            "distribute_to_holders" => to_value(self.distribute_to_holders(
+                caller,
+                p.get("source_token_id")?,
+                p.get("snapshot_id")?,
+                p.get("reward_token_id")?,
+                p.get("total_amount")?,
+                p.get("claimable")?,
+            )),
+            "claim_reward" => to_value(self.claim_reward(caller, p.get("distribution_id")?)),
+            "create_token_type" => to_value(self.create_token_type(caller, p.get("fungible")?)),
+            "set_creator_role" => to_value(self.set_creator_role(
+                caller,
+                &p.get::<String>("account")?,
+                p.get("enabled")?,
+            )),
+            "set_royalty" => to_value(self.set_royalty(
+                caller,
+                p.get("token_id")?,
+                &p.get::<String>("receiver")?,
+                p.get("basis_points")?,
+            )),
+            "delete_token_royalty" => to_value(self.delete_token_royalty(caller, p.get("token_id")?)),
+            "set_default_royalty" => to_value(self.set_default_royalty(
+                caller,
+                p.get("receiver")?,
+                p.get("basis_points")?,
+            )),
+            "set_royalty_ceiling" => to_value(self.set_royalty_ceiling(caller, p.get("basis_points")?)),
+            "set_max_supply" => to_value(self.set_max_supply(caller, p.get("token_id")?, p.get("max_supply")?)),
+            "register_token" => to_value(self.register_token(
+                caller,
+                p.get("token_id")?,
+                &p.get::<String>("name")?,
+                &p.get::<String>("symbol")?,
+                p.get("decimals")?,
+            )),
+            "set_wrap_operator" => to_value(self.set_wrap_operator(
+                caller,
+                &p.get::<String>("account")?,
+                p.get("enabled")?,
+            )),
+            "map_wrapped_asset" => to_value(self.map_wrapped_asset(
+                caller,
+                p.get("token_id")?,
+                &p.get::<String>("external_asset")?,
+            )),
+            "deposit" => to_value(self.deposit(
+                caller,
+                p.get("token_id")?,
+                &p.get::<String>("to")?,
+                p.get("amount")?,
+                &p.get::<String>("external_ref")?,
+            )),
+            "withdraw" => to_value(self.withdraw(
+                caller,
+                p.get("token_id")?,
+                p.get("amount")?,
+                &p.get::<String>("external_recipient")?,
+            )),
+            "balance_of" => to_value(Ok(self.balance_of(&p.get::<String>("owner")?, p.get("token_id")?))),
+            "balance_of_batch" => to_value(self.balance_of_batch(p.get("owners")?, p.get("token_ids")?)),
+            "balances_of" => to_value(Ok(self.balances_of(&p.get::<String>("owner")?))),
+            "balance_of_at" => to_value(self.balance_of_at(
+                &p.get::<String>("owner")?,
+                p.get("token_id")?,
+                p.get("snapshot_id")?,
+            )),
+            "total_supply" => to_value(Ok(self.total_supply(p.get("token_id")?))),
+            "exists" => to_value(Ok(self.exists(p.get("token_id")?))),
+            "uri" => to_value(Ok(self.uri(p.get("token_id")?))),
+            "owner" => to_value(Ok(self.owner())),
+            "is_approved" => to_value(Ok(self.is_approved(&p.get::<String>("owner")?, &p.get::<String>("operator")?))),
+            "is_paused" => to_value(Ok(self.is_paused())),
+            "resolve" => to_value(Ok(self.resolve(&p.get::<String>("name")?))),
+            "get_events" => to_value(Ok(self.get_events(p.get("from_seq")?, p.get("limit")?))),
+            "event_count" => to_value(Ok(self.event_count())),
+            _ => Err(ContractError::new(ErrorKind::InvalidInput, format!("Unknown method `{}`.", method))),
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl ERC1155 {
+    /// Runs the method named by a JSON request and returns a JSON response envelope, with
+    /// the `receipt_hash` of the result on success. Never throws: malformed requests and
+    /// failed calls are reported in the envelope.
+    pub fn call(&mut self, request_json: &str) -> String {
+        let (id, outcome) = match serde_json::from_str::<Request>(request_json) {
+            Ok(request) => {
+                let caller = request.caller.as_deref().unwrap_or("");
+                let outcome = self.dispatch(&request.method, caller, &request.params);
+                (request.id, outcome)
+            }
+            Err(err) => (
+                Value::Null,
+                Err(ContractError::new(ErrorKind::InvalidInput, format!("Invalid request: {}", err))),
+            ),
+        };
+        response(id, outcome)
+    }
+}
+
+/// Encodes the response envelope of the request with `id`.
+fn response(id: Value, outcome: Result<Value>) -> String {
+    let response = match outcome {
+        Ok(result) => {
+            let hash = receipt_hash(&result);
+            json!({ "id": id, "ok": true, "result": result, "receipt_hash": hash })
+        }
+        Err(err) => json!({
+            "id": id,
+            "ok": false,
+            "error": { "code": err.kind().code(), "message": err.message() },
+        }),
+    };
+    response.to_string()
+}
+
+/// Returns the envelope reporting `err` for a request that was not run, echoing its `id`
+/// if it has one.
+pub(crate) fn error_response(request_json: &str, err: ContractError) -> String {
+    let id = serde_json::from_str::<Value>(request_json)
+        .ok()
+        .and_then(|request| request.get("id").cloned())
+        .unwrap_or(Value::Null);
+    response(id, Err(err))
+}
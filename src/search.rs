@@ -0,0 +1,145 @@
+//! Attribute-based token search.
+//!
+//! Attribute writes keep an inverted index from every `(key, value)` to the
+//! tokens carrying it, plus a numeric index of the values that are integers.
+//! `find_tokens` answers a conjunction of equality and range conditions from
+//! these indexes, starting from the most selective one, instead of reading the
+//! attributes of every token.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::ops::Bound;
+use std::sync::Arc;
+
+use serde::Deserialize;
+use serde_json::Value;
+use wasm_bindgen::prelude::*;
+
+use crate::metadata::Metadata;
+use crate::{ContractError, ErrorKind, Result, ERC1155};
+
+/// The tokens carrying each value of one attribute key.
+#[derive(Clone, Default)]
+struct KeyIndex {
+    by_value: BTreeMap<String, BTreeSet<u32>>,
+    /// Values that parse as integers, for range conditions.
+    by_number: BTreeMap<i64, BTreeSet<u32>>,
+}
+
+/// Inverted index over token attributes; each key's index is shared between forks
+/// until written.
+#[derive(Clone, Default)]
+pub(crate) struct AttributeIndex {
+    keys: HashMap<String, Arc<KeyIndex>>,
+}
+
+impl AttributeIndex {
+    /// Indexes every attribute of `metadata`.
+    pub fn build(metadata: &Metadata) -> AttributeIndex {
+        let mut index = AttributeIndex::default();
+        for (token_id, key, value) in metadata.all_attributes() {
+            index.insert(&key, &value, token_id);
+        }
+        index
+    }
+
+    /// Records that `token_id` has `value` for `key`.
+    pub fn insert(&mut self, key: &str, value: &str, token_id: u32) {
+        let index = Arc::make_mut(self.keys.entry(key.to_string()).or_default());
+        index.by_value.entry(value.to_string()).or_default().insert(token_id);
+        if let Ok(number) = value.trim().parse() {
+            index.by_number.entry(number).or_default().insert(token_id);
+        }
+    }
+
+    /// Forgets that `token_id` has `value` for `key`.
+    pub fn remove(&mut self, key: &str, value: &str, token_id: u32) {
+        let Some(index) = self.keys.get_mut(key) else { return };
+        let index = Arc::make_mut(index);
+        if let Some(tokens) = index.by_value.get_mut(value) {
+            tokens.remove(&token_id);
+            if tokens.is_empty() {
+                index.by_value.remove(value);
+            }
+        }
+        if let Ok(number) = value.trim().parse::<i64>() {
+            if let Some(tokens) = index.by_number.get_mut(&number) {
+                tokens.remove(&token_id);
+                if tokens.is_empty() {
+                    index.by_number.remove(&number);
+                }
+            }
+        }
+        if index.by_value.is_empty() {
+            self.keys.remove(key);
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct Condition {
+    key: String,
+    op: String,
+    value: Value,
+}
+
+impl Condition {
+    /// Returns the tokens matching the condition.
+    fn matches(&self, index: &AttributeIndex) -> Result<BTreeSet<u32>> {
+        let Some(index) = index.keys.get(self.key.trim()) else {
+            return Ok(BTreeSet::new());
+        };
+        if self.op == "==" {
+            let value = match &self.value {
+                Value::String(value) => value.clone(),
+                value => value.to_string(),
+            };
+            return Ok(index.by_value.get(&value).cloned().unwrap_or_default());
+        }
+        let bound = self
+            .value
+            .as_i64()
+            .or_else(|| self.value.as_str().and_then(|value| value.trim().parse().ok()))
+            .ok_or_else(|| ContractError::new(ErrorKind::InvalidInput, "Range conditions need an integer value."))?;
+        let range = match self.op.as_str() {
+            "<" => (Bound::Unbounded, Bound::Excluded(bound)),
+            "<=" => (Bound::Unbounded, Bound::Included(bound)),
+            ">" => (Bound::Excluded(bound), Bound::Unbounded),
+            ">=" => (Bound::Included(bound), Bound::Unbounded),
+            op => {
+                return Err(ContractError::new(
+                    ErrorKind::InvalidInput,
+                    format!("Unknown operator `{}`; use ==, <, <=, > or >=.", op),
+                ))
+            }
+        };
+        Ok(index.by_number.range(range).flat_map(|(_, tokens)| tokens).copied().collect())
+    }
+}
+
+#[wasm_bindgen]
+impl ERC1155 {
+    /// Returns the IDs of the tokens whose attributes match every condition of `query_json`,
+    /// ascending, skipping `offset` and returning at most `limit`. The query is an array of
+    /// `{key, op, value}` conditions: `==` compares values as strings, while `<`, `<=`, `>`
+    /// and `>=` compare integer values, as in `[{"key": "level", "op": ">=", "value": 10}]`.
+    pub fn find_tokens(&self, query_json: &str, offset: usize, limit: usize) -> Result<Vec<u32>> {
+        let conditions: Vec<Condition> = serde_json::from_str(query_json)
+            .map_err(|err| ContractError::new(ErrorKind::InvalidInput, format!("Invalid query: {}", err)))?;
+        if conditions.is_empty() {
+            return Err(ContractError::new(ErrorKind::InvalidInput, "Query must have at least one condition."));
+        }
+        let mut matches = conditions
+            .iter()
+            .map(|condition| condition.matches(&self.attribute_index))
+            .collect::<Result<Vec<_>>>()?;
+        matches.sort_unstable_by_key(BTreeSet::len);
+        let (first, rest) = matches.split_first().expect("queries have a condition");
+        Ok(first
+            .iter()
+            .filter(|token_id| rest.iter().all(|tokens| tokens.contains(token_id)))
+            .skip(offset)
+            .take(limit)
+            .copied()
+            .collect())
+    }
+}
@@ -0,0 +1,197 @@
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::balances::Balances;
+use crate::{ContractError, ErrorKind, EventKind, EventRecord, Receipt, Result, TransferReceipt, ERC1155};
+
+/// Cloneable, thread-safe handle to a single contract instance.
+///
+/// All clones share the same state behind a read-write lock, so balance queries
+/// can run concurrently while mutations are serialized. Use it directly from
+/// native hosts or shared-memory WASM builds; for plain Web Workers, post
+/// `snapshot_json`/`events_json` to a `BalanceReplica` instead.
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct SharedERC1155 {
+    inner: Arc<RwLock<ERC1155>>,
+}
+
+#[wasm_bindgen]
+impl SharedERC1155 {
+    /// Initializes a new shared contract with the owner.
+    #[wasm_bindgen(constructor)]
+    pub fn new(owner: &str) -> Result<SharedERC1155> {
+        ERC1155::new(owner).map(Self::from_contract)
+    }
+
+    /// Wraps an existing contract. The contract object is consumed.
+    pub fn from_contract(contract: ERC1155) -> SharedERC1155 {
+        SharedERC1155 {
+            inner: Arc::new(RwLock::new(contract)),
+        }
+    }
+
+    /// Returns another handle to the same underlying state.
+    pub fn clone_handle(&self) -> SharedERC1155 {
+        self.clone()
+    }
+
+    /// Returns the balance of tokens for a specific user and token ID.
+    pub fn balance_of(&self, owner: &str, token_id: u32) -> u64 {
+        self.read().balance_of(owner, token_id)
+    }
+
+    /// Mints new tokens for a given `token_id` (only admins can mint).
+    pub fn mint(&self, caller: &str, token_id: u32, amount: u64) -> Result<TransferReceipt> {
+        self.write().mint(caller, token_id, amount)
+    }
+
+    /// Transfers tokens to another user.
+    pub fn transfer(&self, caller: &str, to: &str, token_id: u32, amount: u64) -> Result<TransferReceipt> {
+        self.write().transfer(caller, to, token_id, amount)
+    }
+
+    /// Approves another user to transfer tokens on behalf of the caller.
+    pub fn approve(&self, caller: &str, approved: &str, token_id: u32) -> Result<Receipt> {
+        self.write().approve(caller, approved, token_id)
+    }
+
+    /// Adds a new admin to the contract (only the owner can add admins).
+    pub fn add_admin(&self, caller: &str, new_admin: &str) -> Result<Receipt> {
+        self.write().add_admin(caller, new_admin)
+    }
+
+    /// See [`ERC1155::snapshot_json`].
+    pub fn snapshot_json(&self) -> String {
+        self.read().snapshot_json()
+    }
+
+    /// See [`ERC1155::events_json`].
+    pub fn events_json(&self, from_seq: u64, limit: usize) -> String {
+        self.read().events_json(from_seq, limit)
+    }
+}
+
+impl SharedERC1155 {
+    /// Locks the state for reading. A poisoned lock still yields the last written state.
+    pub fn read(&self) -> RwLockReadGuard<'_, ERC1155> {
+        self.inner.read().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Locks the state for writing.
+    pub fn write(&self) -> RwLockWriteGuard<'_, ERC1155> {
+        self.inner.write().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Balances at a given point of the event log, as exchanged with workers.
+#[derive(Serialize, Deserialize)]
+struct BalanceSnapshot {
+    /// Sequence number of the first event not reflected in `balances`.
+    seq: u64,
+    balances: Vec<(String, u32, u64)>,
+}
+
+#[wasm_bindgen]
+impl ERC1155 {
+    /// Returns a JSON snapshot of every balance, tagged with the current event sequence.
+    /// Post it to a worker and load it with `BalanceReplica.from_snapshot_json`.
+    pub fn snapshot_json(&self) -> String {
+        let snapshot = BalanceSnapshot {
+            seq: self.next_event_seq(),
+            balances: self
+                .balances
+                .iter()
+                .map(|(owner, token_id, balance)| (owner.to_string(), token_id, balance))
+                .collect(),
+        };
+        serde_json::to_string(&snapshot).expect("balance snapshot is always serializable")
+    }
+
+    /// Returns up to `limit` events starting at `from_seq`, encoded as a JSON array.
+    pub fn events_json(&self, from_seq: u64, limit: usize) -> String {
+        let events: Vec<&EventRecord> = self.events.iter_from(from_seq as usize).take(limit).collect();
+        serde_json::to_string(&events).expect("events are always serializable")
+    }
+
+    /// Returns an independent copy of the contract, like `fork`.
+    #[wasm_bindgen(js_name = clone)]
+    pub fn clone_contract(&self) -> ERC1155 {
+        self.clone()
+    }
+}
+
+/// Read-only copy of contract balances for serving queries off the main thread.
+///
+/// Seed it with `snapshot_json` and keep it current by feeding it `events_json`
+/// batches in order.
+#[wasm_bindgen]
+pub struct BalanceReplica {
+    balances: Balances,
+    seq: u64,
+}
+
+#[wasm_bindgen]
+impl BalanceReplica {
+    /// Builds a replica from the output of `snapshot_json`.
+    pub fn from_snapshot_json(snapshot: &str) -> Result<BalanceReplica> {
+        let snapshot: BalanceSnapshot = serde_json::from_str(snapshot)
+            .map_err(|err| ContractError::new(ErrorKind::InvalidInput, format!("Invalid snapshot: {}", err)))?;
+        let mut balances = Balances::default();
+        for (owner, token_id, balance) in &snapshot.balances {
+            balances.set(owner, *token_id, *balance);
+        }
+        Ok(BalanceReplica {
+            balances,
+            seq: snapshot.seq,
+        })
+    }
+
+    /// Applies the output of `events_json` and returns the next expected sequence number.
+    ///
+    /// Events already reflected in the replica are skipped; a gap in the sequence is an error.
+    pub fn apply_events_json(&mut self, events: &str) -> Result<u64> {
+        let events: Vec<EventRecord> = serde_json::from_str(events)
+            .map_err(|err| ContractError::new(ErrorKind::InvalidInput, format!("Invalid events: {}", err)))?;
+        for event in events {
+            if event.seq < self.seq {
+                continue;
+            }
+            if event.seq > self.seq {
+                return Err(ContractError::new(
+                    ErrorKind::InvalidInput,
+                    format!("Missing events {}..{}.", self.seq, event.seq),
+                ));
+            }
+            if let (EventKind::TransferSingle, Some(token_id), Some(amount)) = (event.kind, event.token_id, event.amount) {
+                if let Some(from) = &event.from {
+                    let balance = self.balances.get(from, token_id).saturating_sub(amount);
+                    self.balances.set(from, token_id, balance);
+                }
+                if let Some(to) = &event.to {
+                    let balance = self.balances.get(to, token_id).saturating_add(amount);
+                    self.balances.set(to, token_id, balance);
+                }
+            }
+            self.seq += 1;
+        }
+        Ok(self.seq)
+    }
+
+    /// Returns the sequence number of the next event the replica expects.
+    pub fn seq(&self) -> u64 {
+        self.seq
+    }
+
+    /// Returns the balance of tokens for a specific user and token ID.
+    pub fn balance_of(&self, owner: &str, token_id: u32) -> u64 {
+        self.balances.get(owner, token_id)
+    }
+}
+
+const _: fn() = || {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<SharedERC1155>();
+};
@@ -0,0 +1,90 @@
+//! Balance snapshots.
+//!
+//! A snapshot freezes every balance and total supply at the moment it is taken,
+//! so later queries can ask what an account held back then. Taking one is O(1);
+//! `Balances` records history lazily on the writes that follow.
+
+use std::convert::TryFrom;
+
+use wasm_bindgen::prelude::*;
+
+use crate::pipeline::CallKind;
+use crate::{BalanceChange, ContractError, ErrorKind, EventKind, EventRecord, Result, ERC1155};
+
+impl ERC1155 {
+    /// Fails unless `snapshot_id` names a snapshot that was taken.
+    pub(crate) fn check_snapshot(&self, snapshot_id: u64) -> Result<()> {
+        if snapshot_id == 0 || snapshot_id > self.balances.last_snapshot() {
+            return Err(ContractError::new(ErrorKind::InvalidInput, "Snapshot does not exist."));
+        }
+        Ok(())
+    }
+}
+
+#[wasm_bindgen]
+impl ERC1155 {
+    /// Takes a snapshot of every balance and returns its ID (owner or admins only).
+    pub fn take_snapshot(&mut self, caller: &str) -> Result<u64> {
+        self.enter("take_snapshot", CallKind::Admin, caller, &[], |this, call| {
+            if !this.access_control.is_owner(&call.caller) && !this.access_control.is_admin(&call.caller) {
+                return Err(ContractError::new(ErrorKind::Unauthorized, "Caller is not authorized to take snapshots."));
+            }
+
+            let snapshot_id = this.balances.take_snapshot();
+            let mut event = EventRecord::new(this.next_event_seq(), EventKind::SnapshotTaken, &call.caller);
+            event.amount = Some(snapshot_id);
+            this.emit(event);
+            Ok(snapshot_id)
+        })
+    }
+
+    /// Returns the ID of the latest snapshot; zero if none was taken.
+    pub fn last_snapshot_id(&self) -> u64 {
+        self.balances.last_snapshot()
+    }
+
+    /// Returns the balance `owner` held of `token_id` when `snapshot_id` was taken.
+    pub fn balance_of_at(&self, owner: &str, token_id: u32, snapshot_id: u64) -> Result<u64> {
+        self.check_snapshot(snapshot_id)?;
+        Ok(self.balances.get_at(&self.resolve_account(owner), token_id, snapshot_id))
+    }
+
+    /// Returns the total supply of `token_id` when `snapshot_id` was taken, saturating at `u64::MAX`.
+    pub fn total_supply_at(&self, token_id: u32, snapshot_id: u64) -> Result<u64> {
+        self.check_snapshot(snapshot_id)?;
+        Ok(u64::try_from(self.balances.total_at(token_id, snapshot_id)).unwrap_or(u64::MAX))
+    }
+
+    /// Returns the accounts whose `token_id` balance differs between two snapshots, ordered
+    /// by account, with `before` read at `snapshot_a` and `after` at `snapshot_b`.
+    /// # Parameters
+    /// - `offset`: Number of changed accounts to skip, for paging.
+    /// - `limit`: Maximum number of entries to return.
+    pub fn balance_changes(
+        &self,
+        snapshot_a: u64,
+        snapshot_b: u64,
+        token_id: u32,
+        offset: usize,
+        limit: usize,
+    ) -> Result<Vec<BalanceChange>> {
+        self.check_snapshot(snapshot_a)?;
+        self.check_snapshot(snapshot_b)?;
+        let mut changes: Vec<BalanceChange> = self
+            .balances
+            .owners_with_history(token_id)
+            .filter_map(|owner| {
+                let before = self.balances.get_at(owner, token_id, snapshot_a);
+                let after = self.balances.get_at(owner, token_id, snapshot_b);
+                (before != after).then(|| BalanceChange {
+                    owner: owner.to_string(),
+                    token_id,
+                    before,
+                    after,
+                })
+            })
+            .collect();
+        changes.sort_unstable_by(|a, b| a.owner.cmp(&b.owner));
+        Ok(changes.into_iter().skip(offset).take(limit).collect())
+    }
+}
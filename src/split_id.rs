@@ -0,0 +1,115 @@
+//! Split token IDs for mixed fungible / non-fungible collections.
+//!
+//! The upper 16 bits of a token ID name its type and the lower 16 bits an index
+//! within it. The top bit marks non-fungible types: every index of such a type
+//! is a distinct NFT with a supply of exactly one, while the type ID itself
+//! (index zero) only identifies the type and is never minted. IDs without the
+//! top bit are fungible, including the plain IDs of type zero.
+
+use wasm_bindgen::prelude::*;
+
+use crate::gas::GasCost;
+use crate::pipeline::CallKind;
+use crate::{ContractError, ErrorKind, EventKind, EventRecord, Result, ERC1155};
+
+/// Set on the IDs of non-fungible types and their items.
+pub(crate) const NFT_FLAG: u32 = 1 << 31;
+const INDEX_BITS: u32 = 16;
+const INDEX_MASK: u32 = (1 << INDEX_BITS) - 1;
+/// Type numbers are 15 bits; zero is reserved for plain IDs.
+const MAX_TYPE: u32 = (NFT_FLAG >> INDEX_BITS) - 1;
+
+/// Returns the type ID `token_id` belongs to.
+pub(crate) fn type_of(token_id: u32) -> u32 {
+    token_id & !INDEX_MASK
+}
+
+/// Returns whether `token_id` is an item of a non-fungible type.
+pub(crate) fn is_nft_item(token_id: u32) -> bool {
+    token_id & NFT_FLAG != 0 && token_id & INDEX_MASK != 0
+}
+
+impl ERC1155 {
+    /// Checks the split-ID rules for minting `amount` of `token_id`.
+    pub(crate) fn check_split_id_mint(&self, token_id: u32, amount: u64) -> Result<()> {
+        if token_id & NFT_FLAG == 0 {
+            return Ok(());
+        }
+        if !is_nft_item(token_id) {
+            return Err(ContractError::new(
+                ErrorKind::InvalidInput,
+                "A non-fungible type ID cannot be minted; mint one of its items instead.",
+            ));
+        }
+        if self.tokens.get(type_of(token_id)).is_none() {
+            return Err(ContractError::new(ErrorKind::InvalidInput, "Token type does not exist."));
+        }
+        if amount != 1 || self.balances.total(token_id) != 0 {
+            return Err(ContractError::new(
+                ErrorKind::InvalidInput,
+                "A non-fungible token has a supply of exactly one.",
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[wasm_bindgen]
+impl ERC1155 {
+    /// Creates a new token type and returns its ID (admins and creators only). The caller
+    /// becomes the creator of the type; for non-fungible types, of every item minted from it.
+    /// # Parameters
+    /// - `fungible`: Whether the type is a fungible token, minted under the returned ID, or a
+    ///   collection of NFTs, minted one at a time under `type_id + index` for indexes 1 to 65535.
+    pub fn create_token_type(&mut self, caller: &str, fungible: bool) -> Result<u32> {
+        self.enter("create_token_type", CallKind::User, caller, &[], |this, call| {
+            let flag = if fungible { 0 } else { NFT_FLAG };
+            // Plain IDs share the fungible range, so skip types whose ID is already in use.
+            let type_id = (this.tokens.last_type + 1..=MAX_TYPE)
+                .map(|number| flag | number << INDEX_BITS)
+                .find(|type_id| this.tokens.get(*type_id).is_none())
+                .ok_or_else(|| ContractError::new(ErrorKind::InvalidInput, "No token types are left."))?;
+            if !this.can_issue(&call.caller, type_id) {
+                return Err(ContractError::new(
+                    ErrorKind::Unauthorized,
+                    "Caller is not authorized to create token types.",
+                ));
+            }
+            this.charge_gas(GasCost {
+                reads: 1,
+                writes: 2,
+                events: 1,
+                ..GasCost::default()
+            })?;
+
+            this.tokens.last_type = (type_id & !NFT_FLAG) >> INDEX_BITS;
+            this.tokens.create(type_id, &call.caller);
+            let mut event = EventRecord::new(this.next_event_seq(), EventKind::TokenTypeCreated, &call.caller);
+            event.token_id = Some(type_id);
+            event.approved = Some(fungible);
+            this.emit(event);
+            Ok(type_id)
+        })
+    }
+
+    /// Returns whether `token_id` is fungible: a fungible type or a plain ID.
+    pub fn is_fungible(&self, token_id: u32) -> bool {
+        token_id & NFT_FLAG == 0
+    }
+
+    /// Returns whether `token_id` is an NFT, an item of a non-fungible type. Neither this nor
+    /// `is_fungible` holds for the ID of a non-fungible type itself.
+    pub fn is_nft(&self, token_id: u32) -> bool {
+        is_nft_item(token_id)
+    }
+
+    /// Returns the ID of the type `token_id` belongs to; plain IDs below 65536 have type zero.
+    pub fn token_type(&self, token_id: u32) -> u32 {
+        type_of(token_id)
+    }
+
+    /// Returns the index of `token_id` within its type.
+    pub fn token_index(&self, token_id: u32) -> u32 {
+        token_id & INDEX_MASK
+    }
+}
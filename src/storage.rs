@@ -0,0 +1,404 @@
+//! Storage backends for the contract's state tables.
+//!
+//! Balances, approvals and token attributes keep their inner tables — the
+//! holders of a token, the operators of an owner, the attributes of a token —
+//! in a [`Storage`], a string-keyed map. Two backends ship with the crate:
+//! [`MemoryStorage`], a plain `HashMap`, and [`KvStorage`], which encodes every
+//! entry into an ordered byte [`KeyValueStore`]. The contract keeps its tables
+//! in the former unless the `kv-storage` feature is enabled, or in a store the
+//! host supplies at runtime through a [`StoreProvider`]; see
+//! `ERC1155::with_store`. Persistence and host-chain adapters implement
+//! `KeyValueStore` for their own store.
+
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::convert::TryInto;
+use std::fmt;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+/// String-keyed map holding one state table.
+pub trait Storage<V>: Clone {
+    /// Returns the value stored under `key`.
+    fn get(&self, key: &str) -> Option<V>;
+
+    /// Stores `value` under `key`, replacing any previous value.
+    fn set(&mut self, key: &str, value: V);
+
+    /// Removes `key` and returns its value.
+    fn remove(&mut self, key: &str) -> Option<V>;
+
+    /// Iterates over every `(key, value)` entry, in an order defined by the backend. Keys
+    /// are borrowed when the backend holds them as strings.
+    fn iter(&self) -> Box<dyn Iterator<Item = (Cow<'_, str>, V)> + '_>;
+
+    /// Returns the number of entries.
+    fn len(&self) -> usize;
+
+    /// Returns whether the map has no entries.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Releases spare capacity left by removed entries, if the backend keeps any.
+    fn shrink_to_fit(&mut self) {}
+}
+
+/// In-memory backend: a `HashMap` that only allocates a key when it is first inserted.
+#[derive(Clone, Debug)]
+pub struct MemoryStorage<V> {
+    entries: HashMap<String, V>,
+}
+
+impl<V> Default for MemoryStorage<V> {
+    fn default() -> Self {
+        Self {
+            entries: HashMap::new(),
+        }
+    }
+}
+
+impl<V: Clone> Storage<V> for MemoryStorage<V> {
+    fn get(&self, key: &str) -> Option<V> {
+        self.entries.get(key).cloned()
+    }
+
+    fn set(&mut self, key: &str, value: V) {
+        match self.entries.get_mut(key) {
+            Some(current) => *current = value,
+            None => {
+                self.entries.insert(key.to_string(), value);
+            }
+        }
+    }
+
+    fn remove(&mut self, key: &str) -> Option<V> {
+        self.entries.remove(key)
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = (Cow<'_, str>, V)> + '_> {
+        Box::new(self.entries.iter().map(|(key, value)| (Cow::Borrowed(key.as_str()), value.clone())))
+    }
+
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    fn shrink_to_fit(&mut self) {
+        self.entries.shrink_to_fit();
+    }
+}
+
+/// Ordered byte-level key-value store, such as a database table, behind a [`KvStorage`].
+///
+/// Reads return owned bytes, so the entries may live outside the process's memory,
+/// for instance in the host of a WASM module.
+pub trait KeyValueStore {
+    fn get(&self, key: &[u8]) -> Option<Vec<u8>>;
+
+    fn put(&mut self, key: &[u8], value: Vec<u8>);
+
+    /// Removes `key` and returns its value.
+    fn delete(&mut self, key: &[u8]) -> Option<Vec<u8>>;
+
+    /// Iterates over every entry in ascending key order.
+    fn scan(&self) -> Box<dyn Iterator<Item = (Vec<u8>, Vec<u8>)> + '_>;
+
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl KeyValueStore for BTreeMap<Vec<u8>, Vec<u8>> {
+    fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        BTreeMap::get(self, key).cloned()
+    }
+
+    fn put(&mut self, key: &[u8], value: Vec<u8>) {
+        match self.get_mut(key) {
+            Some(current) => *current = value,
+            None => {
+                self.insert(key.to_vec(), value);
+            }
+        }
+    }
+
+    fn delete(&mut self, key: &[u8]) -> Option<Vec<u8>> {
+        self.remove(key)
+    }
+
+    fn scan(&self) -> Box<dyn Iterator<Item = (Vec<u8>, Vec<u8>)> + '_> {
+        Box::new(self.iter().map(|(key, value)| (key.clone(), value.clone())))
+    }
+
+    fn len(&self) -> usize {
+        BTreeMap::len(self)
+    }
+}
+
+/// A value [`KvStorage`] can encode.
+pub trait StorageValue: Sized {
+    fn encode(&self) -> Vec<u8>;
+
+    /// Decodes bytes produced by `encode`; `None` if they are not a valid encoding.
+    fn decode(bytes: &[u8]) -> Option<Self>;
+}
+
+impl StorageValue for u64 {
+    fn encode(&self) -> Vec<u8> {
+        self.to_le_bytes().to_vec()
+    }
+
+    fn decode(bytes: &[u8]) -> Option<Self> {
+        Some(u64::from_le_bytes(bytes.try_into().ok()?))
+    }
+}
+
+/// Empty for `None`, the little-endian value for `Some`.
+impl StorageValue for Option<u64> {
+    fn encode(&self) -> Vec<u8> {
+        self.map(|value| value.encode()).unwrap_or_default()
+    }
+
+    fn decode(bytes: &[u8]) -> Option<Self> {
+        if bytes.is_empty() {
+            return Some(None);
+        }
+        u64::decode(bytes).map(Some)
+    }
+}
+
+impl StorageValue for String {
+    fn encode(&self) -> Vec<u8> {
+        self.as_bytes().to_vec()
+    }
+
+    fn decode(bytes: &[u8]) -> Option<Self> {
+        String::from_utf8(bytes.to_vec()).ok()
+    }
+}
+
+/// Backend that stores UTF-8 keys and encoded values in a [`KeyValueStore`], iterating
+/// in key order.
+///
+/// Entries are trusted to have been written through this type: one that does not decode
+/// is a corrupted store and panics.
+#[derive(Clone, Debug)]
+pub struct KvStorage<V, S = BTreeMap<Vec<u8>, Vec<u8>>> {
+    store: S,
+    values: PhantomData<V>,
+}
+
+impl<V, S: KeyValueStore> KvStorage<V, S> {
+    /// Wraps a store, which may already hold entries written by another `KvStorage`.
+    pub fn new(store: S) -> Self {
+        Self {
+            store,
+            values: PhantomData,
+        }
+    }
+
+    /// Returns the underlying store.
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+}
+
+impl<V, S: KeyValueStore + Default> Default for KvStorage<V, S> {
+    fn default() -> Self {
+        Self::new(S::default())
+    }
+}
+
+fn decode_value<V: StorageValue>(bytes: &[u8]) -> V {
+    V::decode(bytes).expect("key-value store holds a corrupted value")
+}
+
+impl<V: StorageValue + Clone, S: KeyValueStore + Clone> Storage<V> for KvStorage<V, S> {
+    fn get(&self, key: &str) -> Option<V> {
+        self.store.get(key.as_bytes()).map(|bytes| decode_value(&bytes))
+    }
+
+    fn set(&mut self, key: &str, value: V) {
+        self.store.put(key.as_bytes(), value.encode());
+    }
+
+    fn remove(&mut self, key: &str) -> Option<V> {
+        self.store.delete(key.as_bytes()).map(|bytes| decode_value(&bytes))
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = (Cow<'_, str>, V)> + '_> {
+        Box::new(self.store.scan().map(|(key, value)| {
+            let key = String::from_utf8(key).expect("key-value store holds a corrupted key");
+            (Cow::Owned(key), decode_value(&value))
+        }))
+    }
+
+    fn len(&self) -> usize {
+        self.store.len()
+    }
+}
+
+/// Opens the tables of a contract in a store the host supplies at runtime.
+///
+/// Every per-token or per-owner table the contract creates is opened here, empty. When
+/// a table shared with a fork or an open transaction is first written, a new table is
+/// opened and the entries are copied into it, so forks and rollbacks behave as with
+/// the in-memory backend. A table is closed by dropping it.
+pub trait StoreProvider: Send + Sync {
+    fn open(&self) -> Box<dyn KeyValueStore + Send + Sync>;
+}
+
+/// A table opened by a [`StoreProvider`].
+pub(crate) struct HostTable {
+    provider: Arc<dyn StoreProvider>,
+    store: Box<dyn KeyValueStore + Send + Sync>,
+}
+
+impl Clone for HostTable {
+    fn clone(&self) -> Self {
+        let mut store = self.provider.open();
+        for (key, value) in self.store.scan() {
+            store.put(&key, value);
+        }
+        Self {
+            provider: Arc::clone(&self.provider),
+            store,
+        }
+    }
+}
+
+impl fmt::Debug for HostTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HostTable").field("len", &self.store.len()).finish()
+    }
+}
+
+impl KeyValueStore for HostTable {
+    fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        self.store.get(key)
+    }
+
+    fn put(&mut self, key: &[u8], value: Vec<u8>) {
+        self.store.put(key, value);
+    }
+
+    fn delete(&mut self, key: &[u8]) -> Option<Vec<u8>> {
+        self.store.delete(key)
+    }
+
+    fn scan(&self) -> Box<dyn Iterator<Item = (Vec<u8>, Vec<u8>)> + '_> {
+        self.store.scan()
+    }
+
+    fn len(&self) -> usize {
+        self.store.len()
+    }
+}
+
+/// Backend of the contract's tables kept in its own memory.
+#[cfg(not(feature = "kv-storage"))]
+type LocalBackend<V> = MemoryStorage<V>;
+/// Backend of the contract's tables kept in its own memory.
+#[cfg(feature = "kv-storage")]
+type LocalBackend<V> = KvStorage<V>;
+
+/// Backend of the contract's state tables, chosen when the table is opened by [`Tables`].
+#[derive(Clone, Debug)]
+pub(crate) enum Backend<V> {
+    Local(LocalBackend<V>),
+    Host(KvStorage<V, HostTable>),
+}
+
+impl<V> Default for Backend<V> {
+    fn default() -> Self {
+        Backend::Local(LocalBackend::default())
+    }
+}
+
+impl<V: StorageValue + Clone> Storage<V> for Backend<V> {
+    fn get(&self, key: &str) -> Option<V> {
+        match self {
+            Backend::Local(table) => table.get(key),
+            Backend::Host(table) => table.get(key),
+        }
+    }
+
+    fn set(&mut self, key: &str, value: V) {
+        match self {
+            Backend::Local(table) => table.set(key, value),
+            Backend::Host(table) => table.set(key, value),
+        }
+    }
+
+    fn remove(&mut self, key: &str) -> Option<V> {
+        match self {
+            Backend::Local(table) => table.remove(key),
+            Backend::Host(table) => table.remove(key),
+        }
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = (Cow<'_, str>, V)> + '_> {
+        match self {
+            Backend::Local(table) => table.iter(),
+            Backend::Host(table) => table.iter(),
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Backend::Local(table) => table.len(),
+            Backend::Host(table) => table.len(),
+        }
+    }
+
+    fn shrink_to_fit(&mut self) {
+        match self {
+            Backend::Local(table) => table.shrink_to_fit(),
+            Backend::Host(table) => table.shrink_to_fit(),
+        }
+    }
+}
+
+/// Where a contract opens its state tables: in its own memory by default, or through
+/// the [`StoreProvider`] it was built with.
+#[derive(Clone, Default)]
+pub(crate) struct Tables {
+    provider: Option<Arc<dyn StoreProvider>>,
+}
+
+impl Tables {
+    pub fn new(provider: Arc<dyn StoreProvider>) -> Self {
+        Self {
+            provider: Some(provider),
+        }
+    }
+
+    /// Opens an empty table.
+    pub fn open<V>(&self) -> Backend<V> {
+        match &self.provider {
+            Some(provider) => Backend::Host(KvStorage::new(HostTable {
+                provider: Arc::clone(provider),
+                store: provider.open(),
+            })),
+            None => Backend::default(),
+        }
+    }
+
+    /// Moves a table opened elsewhere, such as a decoded one, to where this contract
+    /// keeps its tables.
+    pub fn adopt<V: StorageValue + Clone>(&self, table: Backend<V>) -> Backend<V> {
+        match (&self.provider, table) {
+            (Some(_), Backend::Local(local)) => {
+                let mut table = self.open();
+                for (key, value) in local.iter() {
+                    table.set(&key, value);
+                }
+                table
+            }
+            (_, table) => table,
+        }
+    }
+}
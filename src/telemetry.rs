@@ -0,0 +1,77 @@
+//! Failure telemetry.
+//!
+//! Every failed mutating call is counted per method and error category, so a
+//! host can see what keeps failing in production without logging each call.
+//! Pipeline rejections are recorded by `ERC1155::enter`, and failures inside a
+//! method body from the error the method returns. The counters survive
+//! `rollback`, like the outbox.
+//!
+//! With the `console_error_panic_hook` feature (on by default), panics are also
+//! reported to the browser console with their message and location instead of
+//! an opaque `unreachable` trap.
+
+use std::collections::HashMap;
+
+use wasm_bindgen::prelude::*;
+
+use crate::{ErrorKind, ERC1155};
+
+/// Routes panics to the browser console, once per process.
+pub(crate) fn install_panic_hook() {
+    #[cfg(feature = "console_error_panic_hook")]
+    console_error_panic_hook::set_once();
+}
+
+/// Failure counts per `(method, error kind)`.
+#[derive(Clone, Default)]
+pub(crate) struct ErrorStats {
+    counts: HashMap<(&'static str, ErrorKind), u64>,
+}
+
+impl ErrorStats {
+    pub fn record(&mut self, method: &'static str, kind: ErrorKind) {
+        *self.counts.entry((method, kind)).or_insert(0) += 1;
+    }
+}
+
+/// Number of times a method failed with a given error category.
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErrorStat {
+    #[wasm_bindgen(readonly)]
+    pub method: String,
+    /// Error code, as in the `name` of the thrown error.
+    #[wasm_bindgen(readonly)]
+    pub code: String,
+    #[wasm_bindgen(readonly)]
+    pub count: u64,
+}
+
+#[wasm_bindgen]
+impl ERC1155 {
+    /// Returns how often each method failed, per error category, ordered by method and code.
+    pub fn get_error_stats(&self) -> Vec<ErrorStat> {
+        let mut stats: Vec<ErrorStat> = self
+            .error_stats
+            .counts
+            .iter()
+            .map(|((method, kind), count)| ErrorStat {
+                method: method.to_string(),
+                code: kind.code().to_string(),
+                count: *count,
+            })
+            .collect();
+        stats.sort_unstable_by(|a, b| (&a.method, &a.code).cmp(&(&b.method, &b.code)));
+        stats
+    }
+
+    /// Returns the total number of failed calls recorded.
+    pub fn error_count(&self) -> u64 {
+        self.error_stats.counts.values().sum()
+    }
+
+    /// Clears the failure counters.
+    pub fn reset_error_stats(&mut self) {
+        self.error_stats = ErrorStats::default();
+    }
+}
@@ -0,0 +1,544 @@
+//! Per-token configuration and creator-scoped permissions.
+//!
+//! The account that first mints a token ID becomes its creator. Only the
+//! creator or a contract admin may mint more of it or change its URI, royalty
+//! and maximum supply. New token IDs can be created by admins and by accounts
+//! holding the creator role, either by minting them or by registering their
+//! display metadata.
+
+use std::collections::{HashMap, HashSet};
+use std::convert::TryFrom;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::gas::GasCost;
+use crate::metadata::expand_id;
+use crate::pipeline::CallKind;
+use crate::split_id::{is_nft_item, type_of};
+use crate::{log, ContractError, ErrorKind, EventKind, EventRecord, Receipt, Result, ERC1155};
+
+/// Royalties are expressed in basis points of the sale price.
+const BASIS_POINTS: u64 = 10_000;
+
+/// Configuration of one token ID.
+#[derive(Clone, Serialize, Deserialize)]
+pub(crate) struct TokenConfig {
+    pub creator: String,
+    pub uri: Option<String>,
+    /// `(receiver, basis_points)`.
+    pub royalty: Option<(String, u64)>,
+    pub max_supply: Option<u64>,
+    pub class: Option<TokenClass>,
+    /// Contract time in milliseconds from which every balance counts as zero.
+    pub expires_at: Option<u64>,
+}
+
+/// Token configurations and the accounts allowed to create tokens.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub(crate) struct Tokens {
+    configs: HashMap<u32, TokenConfig>,
+    #[serde(serialize_with = "crate::canonical::sorted")]
+    creator_role: HashSet<String>,
+    /// `(receiver, basis_points)` of tokens without a royalty of their own.
+    default_royalty: Option<(String, u64)>,
+    /// Highest royalty allowed, in basis points; `None` means `BASIS_POINTS`.
+    royalty_ceiling: Option<u64>,
+    /// Number of the last type handed out by `create_token_type`.
+    pub last_type: u32,
+}
+
+impl Tokens {
+    pub fn get(&self, token_id: u32) -> Option<&TokenConfig> {
+        self.configs.get(&token_id)
+    }
+
+    pub fn royalty_ceiling(&self) -> u64 {
+        self.royalty_ceiling.unwrap_or(BASIS_POINTS)
+    }
+
+    /// Returns the royalty of `token_id`: its own, or else the default.
+    fn royalty(&self, token_id: u32) -> Option<&(String, u64)> {
+        self.get(token_id)
+            .and_then(|config| config.royalty.as_ref())
+            .or(self.default_royalty.as_ref())
+    }
+
+    /// Records `creator` for `token_id` unless it already has one.
+    pub fn create(&mut self, token_id: u32, creator: &str) {
+        self.configs.entry(token_id).or_insert_with(|| TokenConfig {
+            creator: creator.to_string(),
+            uri: None,
+            royalty: None,
+            max_supply: None,
+            class: None,
+            expires_at: None,
+        });
+    }
+}
+
+/// Display metadata of a fungible token ID, as ERC-20 tokens declare it.
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenClass {
+    #[wasm_bindgen(readonly)]
+    pub name: String,
+    #[wasm_bindgen(readonly)]
+    pub symbol: String,
+    /// Number of decimals balances are displayed with: a balance of 150 with 2 decimals is 1.50.
+    #[wasm_bindgen(readonly)]
+    pub decimals: u8,
+}
+
+/// Royalty owed on a sale, following EIP-2981.
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RoyaltyInfo {
+    #[wasm_bindgen(readonly)]
+    pub receiver: String,
+    #[wasm_bindgen(readonly)]
+    pub amount: u64,
+}
+
+impl ERC1155 {
+    /// Checks that `caller` may mint `amount` of `token_id`, creating the token if needed.
+    pub(crate) fn check_mint(&self, caller: &str, token_id: u32, amount: u64) -> Result<()> {
+        self.check_mintable(token_id, amount)?;
+        if !self.can_issue(caller, token_id) {
+            log(&format!("Mint failed: {} may not mint token ID {}", caller, token_id));
+            return Err(ContractError::new(ErrorKind::Unauthorized, "Caller is not authorized to mint tokens."));
+        }
+        self.check_max_supply(token_id, amount)
+    }
+
+    /// Checks that `amount` of `token_id` may be minted by someone, before authorization
+    /// and the supply cap are checked.
+    pub(crate) fn check_mintable(&self, token_id: u32, amount: u64) -> Result<()> {
+        if amount == 0 {
+            return Err(ContractError::new(ErrorKind::InvalidInput, "Mint amount must be positive."));
+        }
+        self.check_split_id_mint(token_id, amount)?;
+        self.check_not_expired(token_id)?;
+        if self.fractions.is_share(token_id) {
+            return Err(ContractError::new(
+                ErrorKind::InvalidInput,
+                "Shares of a fractionalized NFT cannot be minted.",
+            ));
+        }
+        if self.wrapping.is_wrapped(token_id) {
+            return Err(ContractError::new(
+                ErrorKind::InvalidInput,
+                "Wrapped tokens are only minted by deposits.",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Checks that minting `amount` more of `token_id` stays within its maximum supply.
+    pub(crate) fn check_max_supply(&self, token_id: u32, amount: u64) -> Result<()> {
+        if let Some(max_supply) = self.tokens.get(token_id).and_then(|config| config.max_supply) {
+            if self.balances.total(token_id) + u128::from(amount) > u128::from(max_supply) {
+                return Err(ContractError::new(ErrorKind::InvalidInput, "Mint would exceed the maximum supply."));
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that `amount` of `token_id` may be burned outright.
+    pub(crate) fn check_burn(&self, token_id: u32, amount: u64) -> Result<()> {
+        if amount == 0 {
+            return Err(ContractError::new(ErrorKind::InvalidInput, "Burn amount must be positive."));
+        }
+        self.check_not_expired(token_id)?;
+        if self.fractions.is_share(token_id) {
+            return Err(ContractError::new(
+                ErrorKind::InvalidInput,
+                "Shares of a fractionalized NFT are only burned by redeeming it.",
+            ));
+        }
+        if self.wrapping.is_wrapped(token_id) {
+            return Err(ContractError::new(
+                ErrorKind::InvalidInput,
+                "Wrapped tokens are only burned by withdrawals.",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Returns whether `caller` may issue `token_id`: as its creator or an admin if it
+    /// exists, as an admin or the creator of its type for a new NFT, and as an admin or
+    /// creator-role holder otherwise. No one may once the contract is immutable.
+    pub(crate) fn can_issue(&self, caller: &str, token_id: u32) -> bool {
+        if self.check_configurable().is_err() {
+            return false;
+        }
+        if self.access_control.is_admin(caller) {
+            return true;
+        }
+        match self.tokens.get(token_id) {
+            Some(config) => config.creator == caller,
+            None if is_nft_item(token_id) => self
+                .tokens
+                .get(type_of(token_id))
+                .is_some_and(|config| config.creator == caller),
+            None => self.tokens.creator_role.contains(caller),
+        }
+    }
+
+    /// Checks that `caller` may change the configuration of `token_id`.
+    pub(crate) fn check_token_manager(&self, caller: &str, token_id: u32) -> Result<()> {
+        let config = self
+            .tokens
+            .get(token_id)
+            .ok_or_else(|| ContractError::new(ErrorKind::InvalidInput, "Token does not exist."))?;
+        if config.creator != caller && !self.access_control.is_admin(caller) {
+            return Err(ContractError::new(
+                ErrorKind::Unauthorized,
+                "Only the token creator or an admin can configure this token.",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Checks that `basis_points` does not exceed the royalty ceiling.
+    fn check_royalty(&self, basis_points: u64) -> Result<()> {
+        let ceiling = self.tokens.royalty_ceiling();
+        if basis_points > ceiling {
+            return Err(ContractError::new(
+                ErrorKind::InvalidInput,
+                format!("Royalty cannot exceed {} basis points.", ceiling),
+            ));
+        }
+        Ok(())
+    }
+
+    fn check_royalty_admin(&self, caller: &str) -> Result<()> {
+        if !self.access_control.is_owner(caller) && !self.access_control.is_admin(caller) {
+            return Err(ContractError::new(ErrorKind::Unauthorized, "Caller is not authorized to manage royalties."));
+        }
+        Ok(())
+    }
+
+    pub(crate) fn token_config_mut(&mut self, token_id: u32) -> &mut TokenConfig {
+        self.tokens
+            .configs
+            .get_mut(&token_id)
+            .expect("callers check that the token exists")
+    }
+}
+
+#[wasm_bindgen]
+impl ERC1155 {
+    /// Grants or revokes the right to create new token IDs (owner or admins only).
+    pub fn set_creator_role(&mut self, caller: &str, account: &str, enabled: bool) -> Result<Receipt> {
+        self.enter("set_creator_role", CallKind::Admin, caller, &[account], |this, call| {
+            if !this.access_control.is_owner(&call.caller) && !this.access_control.is_admin(&call.caller) {
+                return Err(ContractError::new(ErrorKind::Unauthorized, "Caller is not authorized to manage creators."));
+            }
+
+            let account = &call.accounts[0];
+            if enabled {
+                this.tokens.creator_role.insert(account.clone());
+            } else {
+                this.tokens.creator_role.remove(account);
+            }
+            let mut event = EventRecord::new(this.next_event_seq(), EventKind::CreatorRoleUpdated, &call.caller);
+            event.to = Some(account.clone());
+            event.approved = Some(enabled);
+            this.emit(event);
+            Ok(this.receipt(Vec::new()))
+        })
+    }
+
+    /// Returns whether `account` may create new token IDs without being an admin.
+    pub fn has_creator_role(&self, account: &str) -> bool {
+        self.tokens.creator_role.contains(self.resolve_account(account).as_ref())
+    }
+
+    /// Returns the creator of `token_id`; `undefined` if it was never minted or registered.
+    pub fn creator_of(&self, token_id: u32) -> Option<String> {
+        self.tokens.get(token_id).map(|config| config.creator.clone())
+    }
+
+    /// Sets the metadata URI of `token_id` (creator or admins only), unless its metadata is frozen.
+    pub fn set_token_uri(&mut self, caller: &str, token_id: u32, uri: &str) -> Result<Receipt> {
+        self.enter("set_token_uri", CallKind::Admin, caller, &[], |this, call| {
+            this.check_token_manager(&call.caller, token_id)?;
+            this.check_metadata_mutable(token_id)?;
+            this.charge_gas(GasCost {
+                reads: 1,
+                writes: 1,
+                events: 1,
+                ..GasCost::default()
+            })?;
+
+            this.token_config_mut(token_id).uri = Some(uri.to_string());
+            let mut event = EventRecord::new(this.next_event_seq(), EventKind::Uri, &call.caller);
+            event.token_id = Some(token_id);
+            event.value = Some(uri.to_string());
+            this.emit(event);
+            Ok(this.receipt(Vec::new()))
+        })
+    }
+
+    /// Returns the metadata URI of `token_id`: for generative tokens a `data:` URI embedding
+    /// `metadata_json`, otherwise the token's own URI or the base URI, with `{id}` replaced by
+    /// the token ID as 64 lowercase hex digits. Empty if neither is set.
+    pub fn uri(&self, token_id: u32) -> String {
+        if self.metadata.is_generative(token_id) {
+            return format!("data:application/json;base64,{}", STANDARD.encode(self.metadata_json(token_id)));
+        }
+        self.tokens
+            .get(token_id)
+            .and_then(|config| config.uri.as_deref())
+            .or_else(|| self.metadata.base_uri())
+            .map(|uri| expand_id(uri, token_id))
+            .unwrap_or_default()
+    }
+
+    /// Sets the royalty paid to `receiver` on sales of `token_id` (creator or admins only),
+    /// overriding the default royalty.
+    /// # Parameters
+    /// - `basis_points`: Share of the sale price, in hundredths of a percent (at most the
+    ///   royalty ceiling, 10000 unless lowered).
+    pub fn set_royalty(&mut self, caller: &str, token_id: u32, receiver: &str, basis_points: u64) -> Result<Receipt> {
+        self.enter("set_royalty", CallKind::Admin, caller, &[receiver], |this, call| {
+            this.check_token_manager(&call.caller, token_id)?;
+            this.check_royalty(basis_points)?;
+            this.charge_gas(GasCost {
+                reads: 1,
+                writes: 1,
+                events: 1,
+                ..GasCost::default()
+            })?;
+
+            let receiver = call.accounts[0].clone();
+            this.token_config_mut(token_id).royalty = Some((receiver.clone(), basis_points));
+            let mut event = EventRecord::new(this.next_event_seq(), EventKind::RoyaltyUpdated, &call.caller);
+            event.to = Some(receiver);
+            event.token_id = Some(token_id);
+            event.amount = Some(basis_points);
+            this.emit(event);
+            Ok(this.receipt(Vec::new()))
+        })
+    }
+
+    /// Removes the royalty of `token_id`, which falls back to the default royalty (creator or
+    /// admins only).
+    pub fn delete_token_royalty(&mut self, caller: &str, token_id: u32) -> Result<Receipt> {
+        self.enter("delete_token_royalty", CallKind::Admin, caller, &[], |this, call| {
+            this.check_token_manager(&call.caller, token_id)?;
+            if !this.has_token_royalty(token_id) {
+                return Err(ContractError::new(ErrorKind::InvalidInput, "Token has no royalty of its own."));
+            }
+            this.charge_gas(GasCost {
+                reads: 1,
+                writes: 1,
+                events: 1,
+                ..GasCost::default()
+            })?;
+
+            this.token_config_mut(token_id).royalty = None;
+            let mut event = EventRecord::new(this.next_event_seq(), EventKind::RoyaltyUpdated, &call.caller);
+            event.token_id = Some(token_id);
+            this.emit(event);
+            Ok(this.receipt(Vec::new()))
+        })
+    }
+
+    /// Returns whether `token_id` has a royalty of its own rather than the default.
+    pub fn has_token_royalty(&self, token_id: u32) -> bool {
+        self.tokens.get(token_id).is_some_and(|config| config.royalty.is_some())
+    }
+
+    /// Sets the royalty of every token without one of its own, or removes it when `receiver`
+    /// is `undefined` (owner or admins only).
+    pub fn set_default_royalty(
+        &mut self,
+        caller: &str,
+        receiver: Option<String>,
+        basis_points: u64,
+    ) -> Result<Receipt> {
+        let receivers: Vec<&str> = receiver.as_deref().into_iter().collect();
+        self.enter("set_default_royalty", CallKind::Admin, caller, &receivers, |this, call| {
+            this.check_royalty_admin(&call.caller)?;
+            let royalty = match call.accounts.first() {
+                Some(receiver) => {
+                    this.check_royalty(basis_points)?;
+                    Some((receiver.clone(), basis_points))
+                }
+                None => None,
+            };
+
+            let mut event = EventRecord::new(this.next_event_seq(), EventKind::RoyaltyUpdated, &call.caller);
+            event.to = royalty.as_ref().map(|(receiver, _)| receiver.clone());
+            event.amount = royalty.as_ref().map(|(_, basis_points)| *basis_points);
+            this.tokens.default_royalty = royalty;
+            this.emit(event);
+            Ok(this.receipt(Vec::new()))
+        })
+    }
+
+    /// Sets the highest royalty, in basis points, that the default and per-token royalties
+    /// may charge (only the owner can set it). It cannot be above 10000 nor below a royalty
+    /// that is already set.
+    pub fn set_royalty_ceiling(&mut self, caller: &str, basis_points: u64) -> Result<Receipt> {
+        self.enter("set_royalty_ceiling", CallKind::Admin, caller, &[], |this, call| {
+            if !this.access_control.is_owner(&call.caller) {
+                return Err(ContractError::new(ErrorKind::Unauthorized, "Only the owner can set the royalty ceiling."));
+            }
+            if basis_points > BASIS_POINTS {
+                return Err(ContractError::new(ErrorKind::InvalidInput, "Royalty cannot exceed 10000 basis points."));
+            }
+            let highest = this
+                .tokens
+                .configs
+                .values()
+                .filter_map(|config| config.royalty.as_ref())
+                .chain(this.tokens.default_royalty.as_ref())
+                .map(|(_, basis_points)| *basis_points)
+                .max();
+            if highest.is_some_and(|highest| highest > basis_points) {
+                return Err(ContractError::new(
+                    ErrorKind::InvalidInput,
+                    "Royalty ceiling cannot be below a royalty that is already set.",
+                ));
+            }
+
+            this.tokens.royalty_ceiling = Some(basis_points);
+            let mut event = EventRecord::new(this.next_event_seq(), EventKind::RoyaltyCeilingUpdated, &call.caller);
+            event.amount = Some(basis_points);
+            this.emit(event);
+            Ok(this.receipt(Vec::new()))
+        })
+    }
+
+    /// Returns the highest royalty allowed, in basis points.
+    pub fn royalty_ceiling(&self) -> u64 {
+        self.tokens.royalty_ceiling()
+    }
+
+    /// Returns the royalty owed on a sale of `token_id` for `sale_price`, from its own royalty
+    /// or else the default one; `undefined` if neither is set.
+    pub fn royalty_info(&self, token_id: u32, sale_price: u64) -> Option<RoyaltyInfo> {
+        let (receiver, basis_points) = self.tokens.royalty(token_id)?;
+        Some(RoyaltyInfo {
+            receiver: receiver.clone(),
+            amount: (u128::from(sale_price) * u128::from(*basis_points) / u128::from(BASIS_POINTS)) as u64,
+        })
+    }
+
+    /// Caps the total supply of `token_id` (creator or admins only). The cap cannot be
+    /// below the current supply; `undefined` removes it.
+    pub fn set_max_supply(&mut self, caller: &str, token_id: u32, max_supply: Option<u64>) -> Result<Receipt> {
+        self.enter("set_max_supply", CallKind::Admin, caller, &[], |this, call| {
+            this.check_token_manager(&call.caller, token_id)?;
+            if let Some(max_supply) = max_supply {
+                if u128::from(max_supply) < this.balances.total(token_id) {
+                    return Err(ContractError::new(
+                        ErrorKind::InvalidInput,
+                        "Maximum supply cannot be below the current supply.",
+                    ));
+                }
+            }
+            this.charge_gas(GasCost {
+                reads: 2,
+                writes: 1,
+                events: 1,
+                ..GasCost::default()
+            })?;
+
+            this.token_config_mut(token_id).max_supply = max_supply;
+            let mut event = EventRecord::new(this.next_event_seq(), EventKind::MaxSupplyUpdated, &call.caller);
+            event.token_id = Some(token_id);
+            event.amount = max_supply;
+            this.emit(event);
+            Ok(this.receipt(Vec::new()))
+        })
+    }
+
+    /// Returns the supply cap of `token_id`, if any.
+    pub fn max_supply(&self, token_id: u32) -> Option<u64> {
+        self.tokens.get(token_id).and_then(|config| config.max_supply)
+    }
+
+    /// Returns the total supply of `token_id`, saturating at `u64::MAX` for uncapped
+    /// tokens minted past it.
+    pub fn total_supply(&self, token_id: u32) -> u64 {
+        u64::try_from(self.balances.total(token_id)).unwrap_or(u64::MAX)
+    }
+
+    /// Returns whether any of `token_id` is in circulation. A token whose whole supply was
+    /// burned no longer exists, even though its configuration is kept.
+    pub fn exists(&self, token_id: u32) -> bool {
+        self.balances.total(token_id) > 0
+    }
+
+    /// Registers the display name, symbol and decimals of `token_id`. Each token can be
+    /// registered once, by its creator or an admin; registering a token that was never
+    /// minted creates it, with the same rules as minting.
+    pub fn register_token(
+        &mut self,
+        caller: &str,
+        token_id: u32,
+        name: &str,
+        symbol: &str,
+        decimals: u8,
+    ) -> Result<Receipt> {
+        self.enter("register_token", CallKind::Admin, caller, &[], |this, call| {
+            if !this.can_issue(&call.caller, token_id) {
+                return Err(ContractError::new(
+                    ErrorKind::Unauthorized,
+                    "Caller is not authorized to register this token.",
+                ));
+            }
+            if this.token_class(token_id).is_some() {
+                return Err(ContractError::new(ErrorKind::InvalidInput, "Token is already registered."));
+            }
+            if name.trim().is_empty() || symbol.trim().is_empty() {
+                return Err(ContractError::new(ErrorKind::InvalidInput, "Token name and symbol cannot be empty."));
+            }
+            this.charge_gas(GasCost {
+                reads: 1,
+                writes: 1,
+                events: 1,
+                ..GasCost::default()
+            })?;
+
+            this.tokens.create(token_id, &call.caller);
+            this.token_config_mut(token_id).class = Some(TokenClass {
+                name: name.to_string(),
+                symbol: symbol.to_string(),
+                decimals,
+            });
+            let mut event = EventRecord::new(this.next_event_seq(), EventKind::TokenRegistered, &call.caller);
+            event.token_id = Some(token_id);
+            event.amount = Some(u64::from(decimals));
+            event.value = Some(symbol.to_string());
+            this.emit(event);
+            Ok(this.receipt(Vec::new()))
+        })
+    }
+
+    /// Returns the registered display metadata of `token_id`, if any.
+    pub fn token_class(&self, token_id: u32) -> Option<TokenClass> {
+        self.tokens.get(token_id).and_then(|config| config.class.clone())
+    }
+
+    /// Returns the registered name of `token_id`, if any.
+    pub fn token_name(&self, token_id: u32) -> Option<String> {
+        self.token_class(token_id).map(|class| class.name)
+    }
+
+    /// Returns the registered symbol of `token_id`, if any.
+    pub fn token_symbol(&self, token_id: u32) -> Option<String> {
+        self.token_class(token_id).map(|class| class.symbol)
+    }
+
+    /// Returns the registered decimals of `token_id`, if any.
+    pub fn token_decimals(&self, token_id: u32) -> Option<u8> {
+        self.token_class(token_id).map(|class| class.decimals)
+    }
+}
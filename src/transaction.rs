@@ -0,0 +1,72 @@
+//! Sandbox transactions: trial-execute a sequence of calls and keep or discard it.
+//!
+//! `begin_transaction` checkpoints the whole contract (balances, approvals,
+//! roles, policies, event and audit logs); `rollback` restores that checkpoint
+//! and `commit` drops it. These are host-side controls with no caller
+//! argument, like `apply_diff`, so do not expose them to untrusted callers.
+
+use wasm_bindgen::prelude::*;
+
+use crate::{ContractError, ErrorKind, Result, ERC1155};
+
+impl ERC1155 {
+    pub(crate) fn check_not_in_call(&self) -> Result<()> {
+        if self.reentrancy_guard.is_locked() {
+            return Err(ContractError::new(ErrorKind::Reentrancy, "Reentrancy detected."));
+        }
+        Ok(())
+    }
+
+    /// Runs `f` inside a transaction that is always rolled back and returns its result.
+    pub fn simulate<T>(&mut self, f: impl FnOnce(&mut ERC1155) -> T) -> Result<T> {
+        self.begin_transaction()?;
+        let result = f(self);
+        self.rollback()?;
+        Ok(result)
+    }
+}
+
+#[wasm_bindgen]
+impl ERC1155 {
+    /// Checkpoints the contract so later calls can be discarded with `rollback`.
+    /// Transactions do not nest.
+    pub fn begin_transaction(&mut self) -> Result<()> {
+        self.check_not_in_call()?;
+        if self.transaction.is_some() {
+            return Err(ContractError::new(ErrorKind::InvalidInput, "A transaction is already open."));
+        }
+        self.transaction = Some(Box::new(self.clone()));
+        Ok(())
+    }
+
+    /// Keeps every change made since `begin_transaction`.
+    pub fn commit(&mut self) -> Result<()> {
+        self.check_not_in_call()?;
+        self.transaction
+            .take()
+            .map(|_| ())
+            .ok_or_else(|| ContractError::new(ErrorKind::InvalidInput, "No transaction is open."))
+    }
+
+    /// Discards every change made since `begin_transaction`.
+    pub fn rollback(&mut self) -> Result<()> {
+        self.check_not_in_call()?;
+        let checkpoint = self
+            .transaction
+            .take()
+            .ok_or_else(|| ContractError::new(ErrorKind::InvalidInput, "No transaction is open."))?;
+        // The outbox never delivers past the checkpoint, so its cursors stay valid and
+        // restoring them would redeliver events drained during the transaction.
+        let outbox = std::mem::take(&mut self.outbox);
+        let error_stats = std::mem::take(&mut self.error_stats);
+        *self = *checkpoint;
+        self.outbox = outbox;
+        self.error_stats = error_stats;
+        Ok(())
+    }
+
+    /// Returns whether a transaction is open.
+    pub fn in_transaction(&self) -> bool {
+        self.transaction.is_some()
+    }
+}
@@ -0,0 +1,266 @@
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen(typescript_custom_section)]
+const TS_ERRORS: &'static str = r#"
+/** Stable codes carried in the `name` of errors thrown by the contract. */
+export type ContractErrorCode =
+    | "Unauthorized"
+    | "InsufficientBalance"
+    | "Reentrancy"
+    | "InvalidInput"
+    | "TransferRejected"
+    | "Overflow"
+    | "Paused"
+    | "Blacklisted"
+    | "OutOfGas"
+    | "Expired";
+
+/** Shape of every error thrown by a contract call. */
+export interface ContractError extends Error {
+    name: ContractErrorCode;
+}
+"#;
+
+/// Balance of a single `(owner, token_id)` pair.
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BalanceEntry {
+    #[wasm_bindgen(readonly)]
+    pub owner: String,
+    #[wasm_bindgen(readonly)]
+    pub token_id: u32,
+    #[wasm_bindgen(readonly)]
+    pub balance: u64,
+}
+
+/// Outcome of a successful mint or transfer.
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TransferReceipt {
+    /// ID of the audit log operation the transfer belongs to.
+    #[wasm_bindgen(readonly)]
+    pub operation_id: u64,
+    /// Sequence number of the `TransferSingle` event emitted for this call.
+    #[wasm_bindgen(readonly)]
+    pub event_seq: u64,
+    #[wasm_bindgen(readonly)]
+    pub operator: String,
+    /// Sender of the tokens; `undefined` for mints.
+    #[wasm_bindgen(readonly)]
+    pub from: Option<String>,
+    #[wasm_bindgen(readonly)]
+    pub to: String,
+    #[wasm_bindgen(readonly)]
+    pub token_id: u32,
+    #[wasm_bindgen(readonly)]
+    pub amount: u64,
+    /// Sender balance after the call; `undefined` for mints.
+    #[wasm_bindgen(readonly)]
+    pub from_balance: Option<u64>,
+    /// Recipient balance after the call.
+    #[wasm_bindgen(readonly)]
+    pub to_balance: u64,
+    /// Gas consumed by the call; zero unless metering is enabled.
+    #[wasm_bindgen(readonly)]
+    pub gas_used: u64,
+}
+
+/// Balance of one `(owner, token_id)` pair around a call.
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BalanceChange {
+    #[wasm_bindgen(readonly)]
+    pub owner: String,
+    #[wasm_bindgen(readonly)]
+    pub token_id: u32,
+    #[wasm_bindgen(readonly)]
+    pub before: u64,
+    #[wasm_bindgen(readonly)]
+    pub after: u64,
+}
+
+/// Outcome of a successful mutating call other than a single mint or transfer.
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Receipt {
+    /// ID of the call's entry in the audit log (see `get_operations`).
+    #[wasm_bindgen(readonly)]
+    pub operation_id: u64,
+    #[wasm_bindgen(readonly)]
+    pub method: String,
+    /// Balances the call changed; empty for calls that only touch approvals or roles.
+    #[wasm_bindgen(readonly)]
+    pub balances: Vec<BalanceChange>,
+    #[wasm_bindgen(readonly)]
+    pub events: Vec<EventRecord>,
+    /// Gas consumed by the call; zero unless metering is enabled.
+    #[wasm_bindgen(readonly)]
+    pub gas_used: u64,
+}
+
+/// Kind of an emitted contract event.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum EventKind {
+    TransferSingle,
+    ApprovalForAll,
+    AdminAdded,
+    OwnershipTransferred,
+    Paused,
+    Unpaused,
+    BlacklistUpdated,
+    GuardiansUpdated,
+    RecoverySupported,
+    RecoveryInitiated,
+    RecoveryCancelled,
+    CreatorRoleUpdated,
+    Uri,
+    RoyaltyUpdated,
+    MaxSupplyUpdated,
+    TokenRegistered,
+    TokenTypeCreated,
+    WrapOperatorUpdated,
+    AssetMapped,
+    Deposited,
+    WithdrawalRequested,
+    SnapshotTaken,
+    ProposalCreated,
+    VoteCast,
+    DelegateChanged,
+    RewardsDistributed,
+    RewardClaimed,
+    MetadataFrozen,
+    AttributeUpdated,
+    SvgTemplateUpdated,
+    BaseUriUpdated,
+    RoyaltyCeilingUpdated,
+    SupplyDepleted,
+    OwnershipRenounced,
+    BridgeOperatorUpdated,
+    BridgedOut,
+    BridgedIn,
+    NameUpdated,
+    TreasuryAccountUpdated,
+    TokenExpiryUpdated,
+    TokenUsesUpdated,
+    Consumed,
+    MaxBatchSizeUpdated,
+    MintAuthorizerUpdated,
+    ScopedApproval,
+    DropCreated,
+    DropRegistered,
+    DropRevealed,
+    DropCancelled,
+    GasScheduleUpdated,
+    GasLimitUpdated,
+}
+
+/// A single entry of the contract event log.
+///
+/// Fields that do not apply to the event kind are `undefined`:
+/// - `TransferSingle`: `from` (absent for mints), `to` (absent for burns), `token_id`, `amount`.
+/// - `ApprovalForAll`: `from` is the account granting or revoking approval for `operator`, plus
+///   `approved` and, for approvals with a deadline, `expires_at`.
+/// - `AdminAdded`: `to` is the new admin.
+/// - `OwnershipTransferred`: `from` is the previous owner, `to` the new one.
+/// - `Paused` / `Unpaused`: only `operator`.
+/// - `BlacklistUpdated`: `to` is the account, `approved` whether it is now blacklisted.
+/// - `GuardiansUpdated`: `amount` is the new recovery threshold.
+/// - `RecoverySupported`: `operator` is the guardian, `to` the new owner it backs.
+/// - `RecoveryInitiated`: `to` is the new owner, `expires_at` when the recovery becomes executable.
+/// - `RecoveryCancelled`: only `operator`.
+/// - `CreatorRoleUpdated`: `to` is the account, `approved` whether it may now create tokens.
+/// - `Uri`: `token_id` and its new URI in `value`, also emitted when generated metadata is switched on or off.
+/// - `RoyaltyUpdated`: `token_id`, absent for the default royalty, `to` is the royalty receiver
+///   and `amount` the basis points; `to` and `amount` are absent when the royalty is removed.
+/// - `MaxSupplyUpdated`: `token_id` and the new cap in `amount`, absent when the cap is removed.
+/// - `TokenRegistered`: `token_id`, its symbol in `value` and its decimals in `amount`.
+/// - `TokenTypeCreated`: `token_id` is the new type ID, `approved` whether the type is fungible.
+/// - `WrapOperatorUpdated`: `to` is the account, `approved` whether it may now record deposits.
+/// - `AssetMapped`: `token_id` and the external asset it mirrors in `value`.
+/// - `Deposited`: `to`, `token_id`, `amount` and the external deposit reference in `value`.
+/// - `WithdrawalRequested`: `from`, `token_id`, `amount` and the external recipient in `value`.
+/// - `SnapshotTaken`: `amount` is the snapshot ID.
+/// - `ProposalCreated`: `value` is the proposal ID, `token_id` the weight token, `amount` the
+///   quorum and `expires_at` when voting closes.
+/// - `VoteCast`: `operator` is the voter, `value` the proposal ID, `token_id` the weight token,
+///   `amount` the weight and `approved` whether it supports the proposal.
+/// - `DelegateChanged`: `from` is the delegator, `token_id` the token and `to` the new delegatee,
+///   absent when the delegator takes its weight back.
+/// - `RewardsDistributed`: `value` is the distribution ID, `token_id` the reward token, `amount`
+///   the total minted and `approved` whether holders have to claim their shares.
+/// - `RewardClaimed`: `to` is the holder, `value` the distribution ID, `token_id` the reward token
+///   and `amount` the share claimed.
+/// - `MetadataFrozen`: `token_id`, absent when the whole collection was frozen.
+/// - `AttributeUpdated`: `token_id`, the attribute key in `value` and `approved` whether it was
+///   set (`false` when it was removed).
+/// - `SvgTemplateUpdated`: `approved` is whether a template is now set.
+/// - `BaseUriUpdated`: the new base URI in `value`, absent when it was removed.
+/// - `RoyaltyCeilingUpdated`: `amount` is the new ceiling in basis points.
+/// - `SupplyDepleted`: `token_id`, whose last tokens were just burned.
+/// - `OwnershipRenounced`: `from` is the last owner, `approved` whether the configuration was
+///   frozen as well.
+/// - `BridgeOperatorUpdated`: `to` is the account, `approved` whether it may now call `bridge_in`.
+/// - `BridgedOut`: `from`, `token_id`, `amount` and the outbound transfer ID in `value`.
+/// - `BridgedIn`: `to`, `token_id`, `amount` and the proof ID in `value`.
+/// - `NameUpdated`: `to` is the account, the name in `value` and `approved` whether it was
+///   registered (`false` when it was released).
+/// - `TreasuryAccountUpdated`: `to` is the account, `approved` whether its balances are now left
+///   out of the circulating supply.
+/// - `TokenExpiryUpdated`: `token_id` and its new expiry in `expires_at`, absent when removed.
+/// - `TokenUsesUpdated`: `token_id` and the uses each of its tokens is worth in `amount`.
+/// - `Consumed`: `from` is the holder, `token_id`, `amount` the uses spent and the context in `value`.
+/// - `MaxBatchSizeUpdated`: `amount` is the new maximum number of items per batch call.
+/// - `MintAuthorizerUpdated`: the authorizer's hex-encoded public key in `value`, absent when removed.
+/// - `ScopedApproval`: `from` is the account granting `operator` an allowance of `amount` of
+///   `token_id`; zero when revoked.
+/// - `DropCreated`: `value` is the drop ID, `amount` the number of token IDs in its table and
+///   `expires_at` when registration closes.
+/// - `DropRegistered`: `operator` is the registrant and `value` the drop ID.
+/// - `DropRevealed`: `value` is the drop ID and `amount` the number of registrants minted.
+/// - `DropCancelled`: `value` is the drop ID.
+/// - `GasScheduleUpdated`: the new schedule as JSON in `value`, absent when metering was disabled.
+/// - `GasLimitUpdated`: `amount` is the new per-call limit, absent when it was removed.
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EventRecord {
+    #[wasm_bindgen(readonly)]
+    pub seq: u64,
+    #[wasm_bindgen(readonly)]
+    pub kind: EventKind,
+    #[wasm_bindgen(readonly)]
+    pub operator: String,
+    #[wasm_bindgen(readonly)]
+    pub from: Option<String>,
+    #[wasm_bindgen(readonly)]
+    pub to: Option<String>,
+    #[wasm_bindgen(readonly)]
+    pub token_id: Option<u32>,
+    #[wasm_bindgen(readonly)]
+    pub amount: Option<u64>,
+    #[wasm_bindgen(readonly)]
+    pub approved: Option<bool>,
+    #[wasm_bindgen(readonly)]
+    pub expires_at: Option<u64>,
+    #[wasm_bindgen(readonly)]
+    pub value: Option<String>,
+}
+
+impl EventRecord {
+    /// Creates an event of `kind` with every optional field unset.
+    pub(crate) fn new(seq: u64, kind: EventKind, operator: &str) -> Self {
+        Self {
+            seq,
+            kind,
+            operator: operator.to_string(),
+            from: None,
+            to: None,
+            token_id: None,
+            amount: None,
+            approved: None,
+            expires_at: None,
+            value: None,
+        }
+    }
+}
@@ -0,0 +1,193 @@
+//! Wrapped external assets.
+//!
+//! Admins map an external asset (an ERC-20 or ERC-721 contract, say) to a
+//! token ID. Wrap operators, the off-chain bridge, record deposits of that
+//! asset as mints; holders burn wrapped tokens with `withdraw`, and the
+//! resulting `WithdrawalRequested` event tells the bridge to release the
+//! asset. Wrapped tokens cannot be minted any other way.
+
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::escrow::ESCROW_ACCOUNT;
+use crate::gas::GasCost;
+use crate::pipeline::CallKind;
+use crate::{ContractError, ErrorKind, EventKind, EventRecord, Receipt, Result, ERC1155};
+
+/// Asset mappings and processed deposits.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub(crate) struct Wrapping {
+    #[serde(serialize_with = "crate::canonical::sorted")]
+    operators: HashSet<String>,
+    /// Token ID -> external asset.
+    assets: HashMap<u32, String>,
+    /// External asset -> token ID.
+    tokens: HashMap<String, u32>,
+    /// External references of the deposits already minted.
+    #[serde(serialize_with = "crate::canonical::sorted")]
+    deposits: HashSet<String>,
+}
+
+impl Wrapping {
+    /// Returns whether `token_id` mirrors an external asset.
+    pub fn is_wrapped(&self, token_id: u32) -> bool {
+        self.assets.contains_key(&token_id)
+    }
+}
+
+impl ERC1155 {
+    fn check_wrap_admin(&self, caller: &str) -> Result<()> {
+        if !self.access_control.is_owner(caller) && !self.access_control.is_admin(caller) {
+            return Err(ContractError::new(ErrorKind::Unauthorized, "Caller is not authorized to manage wrapping."));
+        }
+        Ok(())
+    }
+}
+
+#[wasm_bindgen]
+impl ERC1155 {
+    /// Grants or revokes the right to record deposits (owner or admins only).
+    pub fn set_wrap_operator(&mut self, caller: &str, account: &str, enabled: bool) -> Result<Receipt> {
+        self.enter("set_wrap_operator", CallKind::Admin, caller, &[account], |this, call| {
+            this.check_wrap_admin(&call.caller)?;
+
+            let account = &call.accounts[0];
+            if enabled {
+                this.wrapping.operators.insert(account.clone());
+            } else {
+                this.wrapping.operators.remove(account);
+            }
+            let mut event = EventRecord::new(this.next_event_seq(), EventKind::WrapOperatorUpdated, &call.caller);
+            event.to = Some(account.clone());
+            event.approved = Some(enabled);
+            this.emit(event);
+            Ok(this.receipt(Vec::new()))
+        })
+    }
+
+    /// Returns whether `account` may record deposits.
+    pub fn is_wrap_operator(&self, account: &str) -> bool {
+        self.wrapping.operators.contains(self.resolve_account(account).as_ref())
+    }
+
+    /// Maps `external_asset` to the unused `token_id` (owner or admins only). Each asset
+    /// and each token ID can be mapped once.
+    pub fn map_wrapped_asset(&mut self, caller: &str, token_id: u32, external_asset: &str) -> Result<Receipt> {
+        self.enter("map_wrapped_asset", CallKind::Admin, caller, &[], |this, call| {
+            this.check_wrap_admin(&call.caller)?;
+            let external_asset = external_asset.trim();
+            if external_asset.is_empty() {
+                return Err(ContractError::new(ErrorKind::InvalidInput, "External asset must not be empty."));
+            }
+            if this.tokens.get(token_id).is_some() {
+                return Err(ContractError::new(ErrorKind::InvalidInput, "Token ID is already in use."));
+            }
+            if this.wrapping.tokens.contains_key(external_asset) {
+                return Err(ContractError::new(ErrorKind::InvalidInput, "External asset is already mapped."));
+            }
+
+            this.tokens.create(token_id, ESCROW_ACCOUNT);
+            this.wrapping.assets.insert(token_id, external_asset.to_string());
+            this.wrapping.tokens.insert(external_asset.to_string(), token_id);
+            let mut event = EventRecord::new(this.next_event_seq(), EventKind::AssetMapped, &call.caller);
+            event.token_id = Some(token_id);
+            event.value = Some(external_asset.to_string());
+            this.emit(event);
+            Ok(this.receipt(Vec::new()))
+        })
+    }
+
+    /// Returns the external asset `token_id` mirrors, if any.
+    pub fn wrapped_asset(&self, token_id: u32) -> Option<String> {
+        self.wrapping.assets.get(&token_id).cloned()
+    }
+
+    /// Returns the token ID mirroring `external_asset`, if any.
+    pub fn wrapped_token(&self, external_asset: &str) -> Option<u32> {
+        self.wrapping.tokens.get(external_asset.trim()).copied()
+    }
+
+    /// Mints wrapped tokens for a deposit of the external asset (wrap operators and admins only).
+    /// # Parameters
+    /// - `to`: The account credited with the deposit.
+    /// - `external_ref`: Identifies the deposit on the external chain, such as a transaction
+    ///   hash; each reference is minted at most once.
+    pub fn deposit(
+        &mut self,
+        caller: &str,
+        token_id: u32,
+        to: &str,
+        amount: u64,
+        external_ref: &str,
+    ) -> Result<Receipt> {
+        self.enter("deposit", CallKind::User, caller, &[to], |this, call| {
+            this.check_configurable()?;
+            if !this.wrapping.operators.contains(&call.caller) && !this.access_control.is_admin(&call.caller) {
+                return Err(ContractError::new(ErrorKind::Unauthorized, "Caller is not a wrap operator."));
+            }
+            if !this.wrapping.is_wrapped(token_id) {
+                return Err(ContractError::new(ErrorKind::InvalidInput, "Token does not wrap an external asset."));
+            }
+            if external_ref.trim().is_empty() || this.wrapping.deposits.contains(external_ref.trim()) {
+                return Err(ContractError::new(
+                    ErrorKind::InvalidInput,
+                    "Deposit reference is empty or was already processed.",
+                ));
+            }
+            if amount == 0 {
+                return Err(ContractError::new(ErrorKind::InvalidInput, "Deposit amount must be positive."));
+            }
+            this.check_split_id_mint(token_id, amount)?;
+            this.charge_gas(GasCost {
+                reads: 2,
+                writes: 2,
+                events: 2,
+                ..GasCost::default()
+            })?;
+
+            let to = call.accounts[0].clone();
+            let changes = this.move_tokens(&call.caller, None, Some(&to), token_id, amount)?;
+            this.wrapping.deposits.insert(external_ref.trim().to_string());
+            let mut event = EventRecord::new(this.next_event_seq(), EventKind::Deposited, &call.caller);
+            event.to = Some(to);
+            event.token_id = Some(token_id);
+            event.amount = Some(amount);
+            event.value = Some(external_ref.trim().to_string());
+            this.emit(event);
+            Ok(this.receipt(changes))
+        })
+    }
+
+    /// Burns the caller's wrapped tokens and asks the bridge to release the external asset
+    /// to `external_recipient`.
+    pub fn withdraw(&mut self, caller: &str, token_id: u32, amount: u64, external_recipient: &str) -> Result<Receipt> {
+        self.enter("withdraw", CallKind::User, caller, &[], |this, call| {
+            if !this.wrapping.is_wrapped(token_id) {
+                return Err(ContractError::new(ErrorKind::InvalidInput, "Token does not wrap an external asset."));
+            }
+            if amount == 0 || external_recipient.trim().is_empty() {
+                return Err(ContractError::new(
+                    ErrorKind::InvalidInput,
+                    "Withdrawals need a positive amount and a recipient.",
+                ));
+            }
+            this.charge_gas(GasCost {
+                reads: 1,
+                writes: 1,
+                events: 2,
+                ..GasCost::default()
+            })?;
+
+            let changes = this.move_tokens(&call.caller, Some(&call.caller), None, token_id, amount)?;
+            let mut event = EventRecord::new(this.next_event_seq(), EventKind::WithdrawalRequested, &call.caller);
+            event.from = Some(call.caller.clone());
+            event.token_id = Some(token_id);
+            event.amount = Some(amount);
+            event.value = Some(external_recipient.trim().to_string());
+            this.emit(event);
+            Ok(this.receipt(changes))
+        })
+    }
+}
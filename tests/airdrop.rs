@@ -0,0 +1,56 @@
+mod common;
+
+use common::{setup, ADMIN, OWNER};
+use erc1155_wasm::ErrorKind;
+
+const TOKEN: u32 = 1;
+
+fn recipients(count: usize) -> Vec<String> {
+    (0..count).map(|index| format!("0x{:040x}", index)).collect()
+}
+
+#[test]
+fn airdrop_credits_every_recipient() {
+    let mut contract = setup();
+    let err = contract.mint_to_many(OWNER, TOKEN, recipients(2), vec![1, 2]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Unauthorized);
+    let err = contract.mint_to_many(ADMIN, TOKEN, recipients(2), vec![1]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    let err = contract.mint_to_many(ADMIN, TOKEN, Vec::new(), Vec::new()).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+
+    let receipt = contract.mint_to_many(ADMIN, TOKEN, recipients(3), vec![10, 20, 30]).unwrap();
+    assert_eq!(receipt.events.len(), 3);
+    assert_eq!(contract.balance_of(&recipients(3)[2], TOKEN), 30);
+    assert_eq!(contract.total_supply(TOKEN), 60);
+    assert_eq!(contract.creator_of(TOKEN).as_deref(), Some(ADMIN));
+
+    let err = contract.mint_to_many_same(ADMIN, TOKEN, recipients(10_000), 5).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    assert_eq!(contract.max_batch_size(), 1_000);
+    contract.set_max_batch_size(ADMIN, 10_000).unwrap();
+    contract.mint_to_many_same(ADMIN, TOKEN, recipients(10_000), 5).unwrap();
+    assert_eq!(contract.balance_of(&recipients(1)[0], TOKEN), 15);
+    assert_eq!(contract.balance_of(&recipients(10_000)[9_999], TOKEN), 5);
+    assert_eq!(contract.total_supply(TOKEN), 60 + 50_000);
+}
+
+#[test]
+fn failed_airdrop_changes_nothing() {
+    let mut contract = setup();
+    let mut list = recipients(2);
+    contract.mint(ADMIN, TOKEN, u64::MAX - 1).unwrap();
+    contract.transfer(ADMIN, &list[1], TOKEN, u64::MAX - 1).unwrap();
+
+    // Duplicates are summed before checking for overflow.
+    list.push(list[1].clone());
+    let err = contract.mint_to_many(ADMIN, TOKEN, list.clone(), vec![5, 1, 1]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Overflow);
+    assert_eq!(contract.balance_of(&list[0], TOKEN), 0);
+
+    contract.set_max_supply(ADMIN, TOKEN, Some(u64::MAX)).unwrap();
+    let err = contract.mint_to_many_same(ADMIN, TOKEN, recipients(1), 2).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    let err = contract.mint_to_many(ADMIN, TOKEN, list, vec![1, 0, 0]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+}
@@ -0,0 +1,86 @@
+mod common;
+
+use common::{ADMIN, ALICE};
+use erc1155_wasm::{ErrorKind, EventKind, ERC1155};
+
+const MARKET: &str = "market";
+const BUYER: &str = "buyer";
+
+/// Contract where ALICE holds 10 of tokens 1 and 2.
+fn setup() -> ERC1155 {
+    let mut contract = common::setup();
+    for token_id in [1, 2] {
+        contract.mint(ADMIN, token_id, 10).unwrap();
+        contract.transfer(ADMIN, ALICE, token_id, 10).unwrap();
+    }
+    contract
+}
+
+#[test]
+fn scoped_allowances_are_spent_by_transfers() {
+    let mut contract = setup();
+    let receipt = contract.approve_scoped(ALICE, MARKET, vec![1], vec![5]).unwrap();
+    assert_eq!(receipt.events[0].kind, EventKind::ScopedApproval);
+    assert!(!contract.is_approved(ALICE, MARKET));
+
+    contract.transfer_from(MARKET, ALICE, BUYER, 1, 3).unwrap();
+    assert_eq!(contract.scoped_allowance(ALICE, MARKET, 1), 2);
+    let err = contract.transfer_from(MARKET, ALICE, BUYER, 1, 3).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Unauthorized);
+    let err = contract.transfer_from(MARKET, ALICE, BUYER, 2, 1).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Unauthorized);
+    assert_eq!(contract.burn_from(MARKET, ALICE, 1, 1).unwrap_err().kind(), ErrorKind::Unauthorized);
+
+    contract.transfer_from(MARKET, ALICE, BUYER, 1, 2).unwrap();
+    assert_eq!(contract.scoped_allowance(ALICE, MARKET, 1), 0);
+    assert_eq!(contract.balance_of(BUYER, 1), 5);
+}
+
+#[test]
+fn scoped_allowances_can_be_replaced_and_revoked() {
+    let mut contract = setup();
+    contract.approve_scoped(ALICE, MARKET, vec![1, 2], vec![5, 5]).unwrap();
+    contract.approve_scoped(ALICE, MARKET, vec![1, 2], vec![1, 0]).unwrap();
+    assert_eq!(contract.scoped_allowance(ALICE, MARKET, 1), 1);
+    assert_eq!(contract.scoped_allowance(ALICE, MARKET, 2), 0);
+
+    let err = contract.approve_scoped(ALICE, MARKET, vec![1, 2], vec![1]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    // Blanket approvals do not consume the allowance.
+    contract.approve(ALICE, MARKET, 1).unwrap();
+    contract.transfer_from(MARKET, ALICE, BUYER, 1, 4).unwrap();
+    assert_eq!(contract.scoped_allowance(ALICE, MARKET, 1), 1);
+}
+
+#[test]
+fn bulk_transfers_spend_allowances() {
+    let mut contract = setup();
+    contract.approve_scoped(ALICE, MARKET, vec![1], vec![5]).unwrap();
+    let accounts = vec![ALICE.to_string(), BUYER.to_string()];
+
+    // The allowance covers the records of one call together.
+    let err = contract.apply_transfers_bulk(MARKET, accounts.clone(), &[0, 1, 1, 3, 0, 1, 1, 3]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Unauthorized);
+    assert_eq!(contract.balance_of(BUYER, 1), 0);
+    let err = contract.apply_transfers_bulk(MARKET, accounts.clone(), &[0, 1, 2, 1]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Unauthorized);
+
+    contract.apply_transfers_bulk(MARKET, accounts, &[0, 1, 1, 2, 0, 1, 1, 3]).unwrap();
+    assert_eq!(contract.scoped_allowance(ALICE, MARKET, 1), 0);
+    assert_eq!(contract.balance_of(BUYER, 1), 5);
+}
+
+#[test]
+fn allowances_are_part_of_the_state() {
+    let mut contract = setup();
+    let before = contract.fork();
+    contract.approve_scoped(ALICE, MARKET, vec![1], vec![5]).unwrap();
+    assert_ne!(contract.state_hash(), before.state_hash());
+
+    let diff = before.diff(&contract);
+    assert_eq!(diff.changed_sections(), vec!["allowances".to_string()]);
+    let mut replica = before.fork();
+    replica.apply_diff(&diff).unwrap();
+    assert_eq!(replica.scoped_allowance(ALICE, MARKET, 1), 5);
+    assert_eq!(replica.state_hash(), contract.state_hash());
+}
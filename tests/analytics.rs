@@ -0,0 +1,68 @@
+mod common;
+
+use common::{ADMIN, ALICE, BOB, CAROL, OWNER};
+use erc1155_wasm::{ErrorKind, ERC1155};
+
+const TREASURY: &str = "treasury";
+
+/// Contract where ADMIN minted 100 of token 1 and 10 of token 2 and handed them out.
+fn setup() -> ERC1155 {
+    let mut contract = common::setup();
+    contract.mint(ADMIN, 1, 100).unwrap();
+    contract.mint(ADMIN, 2, 10).unwrap();
+    contract.transfer(ADMIN, ALICE, 1, 50).unwrap();
+    contract.transfer(ADMIN, BOB, 1, 30).unwrap();
+    contract.transfer(ADMIN, TREASURY, 1, 20).unwrap();
+    contract.transfer(ADMIN, ALICE, 2, 10).unwrap();
+    contract
+}
+
+#[test]
+fn holder_counts_follow_transfers() {
+    let mut contract = setup();
+    assert_eq!(contract.holder_count(1), 3);
+    assert_eq!(contract.holder_count(2), 1);
+    assert_eq!(contract.unique_holders_total(), 3);
+
+    contract.transfer(BOB, CAROL, 1, 30).unwrap();
+    assert_eq!(contract.holder_count(1), 3);
+    contract.burn(ALICE, 2, 10).unwrap();
+    assert_eq!(contract.holder_count(2), 0);
+    assert_eq!(contract.unique_holders_total(), 3);
+    contract.transfer(CAROL, ALICE, 1, 30).unwrap();
+    assert_eq!(contract.unique_holders_total(), 2);
+
+    // Tokens locked in escrow have no holder.
+    contract.bridge_out(ALICE, 1, 80, "elsewhere").unwrap();
+    assert_eq!(contract.holder_count(1), 1);
+}
+
+#[test]
+fn top_holders_are_ordered_by_balance() {
+    let mut contract = setup();
+    contract.transfer(ALICE, CAROL, 1, 30).unwrap();
+    let top: Vec<(String, u64)> = contract
+        .top_holders(1, 3)
+        .into_iter()
+        .map(|entry| (entry.owner, entry.balance))
+        .collect();
+    let expected = [(BOB, 30), (CAROL, 30), (ALICE, 20)];
+    assert_eq!(top, expected.map(|(owner, balance)| (owner.to_string(), balance)));
+    assert_eq!(contract.top_holders(1, 1).len(), 1);
+    assert!(contract.top_holders(9, 5).is_empty());
+}
+
+#[test]
+fn circulating_supply_leaves_out_treasury_accounts() {
+    let mut contract = setup();
+    assert_eq!(contract.circulating_supply(1), 100);
+    let err = contract.set_treasury_account(ALICE, TREASURY, true).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Unauthorized);
+
+    contract.set_treasury_account(ADMIN, TREASURY, true).unwrap();
+    assert!(contract.is_treasury_account(TREASURY));
+    assert_eq!(contract.circulating_supply(1), 80);
+    assert_eq!(contract.circulating_supply(2), 10);
+    contract.set_treasury_account(OWNER, TREASURY, false).unwrap();
+    assert_eq!(contract.circulating_supply(1), 100);
+}
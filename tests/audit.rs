@@ -0,0 +1,122 @@
+mod common;
+
+use common::{ADMIN, ALICE, BOB, OWNER};
+use erc1155_wasm::{EventKind, OperationFilter, ERC1155};
+
+const DAY: u64 = 86_400_000;
+
+/// Contract with a pinned clock where `ADMIN` minted token 42 on day 1 and
+/// moved it to `ALICE` on day 2, and `ALICE` sent some to `BOB` on day 8.
+fn setup() -> ERC1155 {
+    let mut contract = common::setup();
+    contract.set_time(OWNER, Some(DAY)).unwrap();
+    contract.mint(ADMIN, 42, 100).unwrap();
+    contract.mint(ADMIN, 7, 1).unwrap();
+    contract.set_time(OWNER, Some(2 * DAY)).unwrap();
+    contract.transfer(ADMIN, ALICE, 42, 100).unwrap();
+    contract.set_time(OWNER, Some(8 * DAY)).unwrap();
+    contract.transfer(ALICE, BOB, 42, 10).unwrap();
+    contract
+}
+
+#[test]
+fn successful_calls_are_logged_in_order() {
+    let contract = setup();
+    let operations = contract.get_operations(&OperationFilter::new());
+    let methods: Vec<&str> = operations.iter().map(|op| op.method.as_str()).collect();
+    assert_eq!(methods, vec!["add_admin", "mint", "mint", "transfer_from", "transfer_from"]);
+    assert_eq!(contract.operation_count(), 5);
+
+    let last = &operations[4];
+    assert_eq!(last.id, 4);
+    assert_eq!(last.caller, ALICE);
+    assert_eq!(last.accounts, vec![ALICE.to_string(), BOB.to_string()]);
+    assert_eq!(last.timestamp, 8 * DAY);
+
+    let events = contract.operation_events(last.id);
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].kind, EventKind::TransferSingle);
+    assert_eq!(events[0].seq, last.first_event_seq);
+}
+
+#[test]
+fn failed_calls_are_not_logged() {
+    let mut contract = setup();
+    let count = contract.operation_count();
+    assert!(contract.transfer(BOB, ALICE, 42, 1_000).is_err());
+    assert!(contract.mint(BOB, 1, 1).is_err());
+    assert_eq!(contract.operation_count(), count);
+}
+
+#[test]
+fn successful_calls_without_events_are_logged() {
+    let mut contract = setup();
+    let receipt = contract.revoke_all_approvals(BOB).unwrap();
+    assert!(receipt.events.is_empty());
+
+    let logged = contract.get_operations(&OperationFilter::new()).pop().unwrap();
+    assert_eq!(logged.id, receipt.operation_id);
+    assert_eq!(logged.method, "revoke_all_approvals");
+    assert_eq!(logged.event_count, 0);
+    assert!(contract.operation_events(logged.id).is_empty());
+}
+
+#[test]
+fn operations_filter_by_token_account_and_time() {
+    let contract = setup();
+    let who_moved_42_last_week = OperationFilter {
+        token_id: Some(42),
+        since: Some(2 * DAY),
+        until: Some(8 * DAY),
+        ..OperationFilter::default()
+    };
+    let found = contract.get_operations(&who_moved_42_last_week);
+    assert_eq!(found.iter().map(|op| op.id).collect::<Vec<_>>(), vec![3, 4]);
+
+    let bob = OperationFilter {
+        account: Some(BOB.into()),
+        ..OperationFilter::default()
+    };
+    assert_eq!(contract.get_operations(&bob).len(), 1);
+
+    let paged = OperationFilter {
+        from_id: Some(1),
+        limit: Some(2),
+        ..OperationFilter::default()
+    };
+    let found = contract.get_operations(&paged);
+    assert_eq!(found.iter().map(|op| op.id).collect::<Vec<_>>(), vec![1, 2]);
+}
+
+#[test]
+fn batch_operations_group_their_events() {
+    let mut contract = setup();
+    contract.set_time(OWNER, None).unwrap();
+    contract.transfer_batch(ADMIN, BOB, vec![7], vec![1]).unwrap();
+    contract.transfer_batch(ALICE, BOB, vec![42, 42], vec![1, 2]).unwrap();
+
+    let last = contract.get_operations(&OperationFilter::new()).pop().unwrap();
+    assert_eq!(last.method, "transfer_batch");
+    assert_eq!(last.event_count, 2);
+    assert!(last.timestamp > 8 * DAY);
+}
+
+#[test]
+fn receipts_correlate_with_the_audit_log() {
+    let mut contract = setup();
+    let receipt = contract.approve(ALICE, BOB, 42).unwrap();
+    assert_eq!(receipt.method, "approve");
+    assert!(receipt.balances.is_empty());
+    assert_eq!(receipt.events.len(), 1);
+    assert_eq!(receipt.events[0].kind, EventKind::ApprovalForAll);
+    assert_eq!(contract.operation_events(receipt.operation_id), receipt.events);
+
+    let transfer = contract.transfer_from(BOB, ALICE, BOB, 42, 1).unwrap();
+    assert_eq!(transfer.operation_id, receipt.operation_id + 1);
+    let batch = contract.transfer_batch(ALICE, BOB, vec![42, 42], vec![1, 1]).unwrap();
+    assert!(batch.iter().all(|item| item.operation_id == transfer.operation_id + 1));
+
+    let logged = contract.get_operations(&OperationFilter::new()).pop().unwrap();
+    assert_eq!(logged.id, batch[0].operation_id);
+    assert_eq!(contract.pause(OWNER).unwrap().operation_id, logged.id + 1);
+}
@@ -0,0 +1,74 @@
+mod common;
+
+use common::{ADMIN, OWNER};
+use erc1155_wasm::{ErrorKind, ERC1155};
+use ring::signature::{Ed25519KeyPair, KeyPair};
+
+const PLAYER: &str = "player";
+const SWORD: u32 = 3;
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Contract with SWORD created and the backend's key registered as mint authorizer.
+fn setup() -> (ERC1155, Ed25519KeyPair) {
+    let backend = Ed25519KeyPair::from_seed_unchecked(&[7; 32]).unwrap();
+    let mut contract = common::setup();
+    contract.mint(ADMIN, SWORD, 1).unwrap();
+    contract.set_time(OWNER, Some(1_000)).unwrap();
+    let key = hex(backend.public_key().as_ref());
+    assert_eq!(contract.set_mint_authorizer(ADMIN, Some(key.clone())).unwrap_err().kind(), ErrorKind::Unauthorized);
+    contract.set_mint_authorizer(OWNER, Some(key.clone())).unwrap();
+    assert_eq!(contract.mint_authorizer(), Some(key));
+    (contract, backend)
+}
+
+fn sign(contract: &ERC1155, backend: &Ed25519KeyPair, to: &str, amount: u64, expiry: u64) -> String {
+    hex(backend.sign(&contract.mint_authorization_message(to, SWORD, amount, expiry)).as_ref())
+}
+
+#[test]
+fn signed_mints_are_honored_once() {
+    let (mut contract, backend) = setup();
+    let signature = sign(&contract, &backend, PLAYER, 2, 5_000);
+
+    let receipt = contract.mint_with_authorization(PLAYER, PLAYER, SWORD, 2, 5_000, &signature).unwrap();
+    assert_eq!(receipt.balances[0].after, 2);
+    assert_eq!(contract.balance_of(PLAYER, SWORD), 2);
+
+    let err = contract.mint_with_authorization(PLAYER, PLAYER, SWORD, 2, 5_000, &signature).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    assert_eq!(contract.balance_of(PLAYER, SWORD), 2);
+}
+
+#[test]
+fn forged_or_expired_authorizations_are_rejected() {
+    let (mut contract, backend) = setup();
+    let signature = sign(&contract, &backend, PLAYER, 2, 5_000);
+
+    // Any change to the signed terms invalidates the signature.
+    let err = contract.mint_with_authorization(PLAYER, PLAYER, SWORD, 20, 5_000, &signature).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Unauthorized);
+    let err = contract.mint_with_authorization(PLAYER, "mallory", SWORD, 2, 5_000, &signature).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Unauthorized);
+    let err = contract.mint_with_authorization(PLAYER, PLAYER, SWORD, 2, 5_000, "abcd").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+
+    contract.set_time(OWNER, Some(5_000)).unwrap();
+    let err = contract.mint_with_authorization(PLAYER, PLAYER, SWORD, 2, 5_000, &signature).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    assert_eq!(contract.balance_of(PLAYER, SWORD), 0);
+}
+
+#[test]
+fn signatures_only_mint_on_the_contract_they_name() {
+    let (contract, backend) = setup();
+    let (mut other, _) = setup();
+    assert_ne!(contract.instance_id(), other.instance_id());
+
+    let signature = sign(&contract, &backend, PLAYER, 2, 5_000);
+    let err = other.mint_with_authorization(PLAYER, PLAYER, SWORD, 2, 5_000, &signature).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Unauthorized);
+    assert_eq!(contract.fork().instance_id(), contract.instance_id());
+}
@@ -0,0 +1,71 @@
+mod common;
+
+use common::{ADMIN, ALICE, BOB, OWNER};
+use erc1155_wasm::{ErrorKind, EventKind, ERC1155};
+
+const RELAYER: &str = "relayer";
+
+/// Contract where `ALICE` holds 100 of token 1 and `RELAYER` has the bridge role.
+fn setup() -> ERC1155 {
+    let mut contract = common::setup();
+    contract.mint(ADMIN, 1, 100).unwrap();
+    contract.transfer(ADMIN, ALICE, 1, 100).unwrap();
+    contract.set_bridge_operator(OWNER, RELAYER, true).unwrap();
+    contract
+}
+
+#[test]
+fn round_trip_keeps_supply_constant() {
+    let mut source = setup();
+    let mut target = ERC1155::new(OWNER).unwrap();
+    target.set_bridge_operator(OWNER, RELAYER, true).unwrap();
+
+    let id = source.bridge_out(ALICE, 1, 40, "target:bob").unwrap();
+    let transfer = source.bridge_transfer(id).unwrap();
+    assert_eq!((transfer.from.as_str(), transfer.amount), (ALICE, 40));
+    assert_eq!(transfer.destination, "target:bob");
+    assert_eq!(source.balance_of(ALICE, 1), 60);
+    assert_eq!(source.bridge_locked(1), 40);
+    assert_eq!(source.total_supply(1), 100);
+
+    // Nothing is locked on the target, so the tokens are minted there.
+    target.bridge_in(RELAYER, "source:1", BOB, 1, 40).unwrap();
+    assert_eq!(target.balance_of(BOB, 1), 40);
+    let err = target.bridge_in(RELAYER, "source:1", BOB, 1, 40).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+
+    // Coming back releases the locked tokens instead of minting.
+    let back = target.bridge_out(BOB, 1, 40, "source:bob").unwrap();
+    let receipt = source.bridge_in(RELAYER, &format!("target:{}", back), BOB, 1, 40).unwrap();
+    assert_eq!(receipt.events.last().unwrap().kind, EventKind::BridgedIn);
+    assert_eq!(source.balance_of(BOB, 1), 40);
+    assert_eq!(source.bridge_locked(1), 0);
+    assert_eq!(source.total_supply(1), 100);
+    assert!(source.is_bridge_proof_processed("target:1"));
+}
+
+#[test]
+fn only_bridge_operators_credit_transfers() {
+    let mut contract = setup();
+    let err = contract.bridge_in(ADMIN, "p", BOB, 1, 1).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Unauthorized);
+    let err = contract.set_bridge_operator(ALICE, ALICE, true).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Unauthorized);
+    contract.set_bridge_operator(ADMIN, RELAYER, false).unwrap();
+    assert!(!contract.is_bridge_operator(RELAYER));
+    let err = contract.bridge_in(RELAYER, "p", BOB, 1, 1).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Unauthorized);
+}
+
+#[test]
+fn outbound_transfers_are_validated() {
+    let mut contract = setup();
+    let err = contract.bridge_out(ALICE, 1, 0, "target").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    let err = contract.bridge_out(ALICE, 1, 10, " ").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    let err = contract.bridge_out(ALICE, 1, 101, "target").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InsufficientBalance);
+    assert_eq!(contract.bridge_transfer_count(), 0);
+    assert!(contract.bridge_transfer(1).is_none());
+}
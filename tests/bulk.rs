@@ -0,0 +1,67 @@
+mod common;
+
+use common::{ADMIN, ALICE, BOB, CAROL};
+use erc1155_wasm::{ErrorKind, ERC1155};
+
+fn setup() -> ERC1155 {
+    let mut contract = common::setup();
+    contract.mint(ADMIN, 1, 100).unwrap();
+    contract.mint(ADMIN, 2, 50).unwrap();
+    contract.transfer(ADMIN, ALICE, 1, 30).unwrap();
+    contract
+}
+
+fn accounts() -> Vec<String> {
+    vec![ADMIN.to_string(), ALICE.to_string(), BOB.to_string(), CAROL.to_string()]
+}
+
+#[test]
+fn balances_bulk_reads_in_order() {
+    let contract = setup();
+    assert_eq!(contract.balances_bulk(ADMIN, &[2, 1, 3]).unwrap(), vec![50, 70, 0]);
+    assert_eq!(contract.balances_bulk(ALICE, &[]).unwrap(), Vec::<u64>::new());
+}
+
+#[test]
+fn apply_transfers_bulk_applies_records_in_order() {
+    let mut contract = setup();
+    contract.approve(ALICE, ADMIN, 1).unwrap();
+    let events = contract.event_count();
+    // Admin -> Bob, then Alice -> Carol as Alice's operator, then Carol -> Bob out of
+    // what Carol just received.
+    let packed = [0, 2, 2, 20, 1, 3, 1, 30, 3, 2, 1, 5];
+    // Carol has not approved the admin, so the last record is rejected and nothing applies.
+    let err = contract.apply_transfers_bulk(ADMIN, accounts(), &packed).err().unwrap();
+    assert_eq!(err.kind(), ErrorKind::Unauthorized);
+    assert_eq!(contract.balance_of(BOB, 2), 0);
+    assert_eq!(contract.event_count(), events);
+
+    contract.approve(CAROL, ADMIN, 1).unwrap();
+    assert_eq!(contract.apply_transfers_bulk(ADMIN, accounts(), &packed).unwrap(), 3);
+    assert_eq!(contract.balances_bulk(BOB, &[1, 2]).unwrap(), vec![5, 20]);
+    assert_eq!(contract.balances_bulk(CAROL, &[1]).unwrap(), vec![25]);
+    assert_eq!(contract.balance_of(ALICE, 1), 0);
+    assert_eq!(contract.event_count(), events + 4);
+}
+
+#[test]
+fn apply_transfers_bulk_rejects_malformed_buffers() {
+    let mut contract = setup();
+    let cases: [&[u64]; 5] = [
+        &[],
+        &[0, 2, 1],
+        &[0, 4, 1, 1],
+        &[0, 2, u64::from(u32::MAX) + 1, 1],
+        &[0, 2, 1, 0],
+    ];
+    for packed in cases.iter() {
+        let err = contract.apply_transfers_bulk(ADMIN, accounts(), packed).err().unwrap();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    }
+    let err = contract
+        .apply_transfers_bulk(ADMIN, accounts(), &[0, 2, 1, 60, 0, 3, 1, 20])
+        .err()
+        .unwrap();
+    assert_eq!(err.kind(), ErrorKind::InsufficientBalance);
+    assert_eq!(contract.balance_of(BOB, 1), 0);
+}
@@ -0,0 +1,102 @@
+mod common;
+
+use common::{ADMIN, ALICE, BOB, OWNER};
+use erc1155_wasm::{CollectionManager, ErrorKind};
+use serde_json::{json, Value};
+
+#[test]
+fn collections_are_independent() {
+    let mut manager = CollectionManager::new();
+    let config = json!({ "name": "Swords", "admins": [ADMIN], "base_uri": "ipfs://swords/{id}" }).to_string();
+    let swords = manager.create_collection(OWNER, &config).unwrap();
+    let shields = manager.create_collection(BOB, "").unwrap();
+    assert_eq!(manager.collection_ids(), vec![swords, shields]);
+    assert_eq!(manager.collection_name(swords).as_deref(), Some("Swords"));
+    assert_eq!(manager.collection_name(shields), None);
+
+    manager.mint(swords, ADMIN, 1, 10).unwrap();
+    manager.transfer(swords, ADMIN, ALICE, 1, 4).unwrap();
+    // ADMIN is only an admin of the first collection.
+    let err = manager.mint(shields, ADMIN, 1, 10).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Unauthorized);
+    manager.add_admin(shields, BOB, ADMIN).unwrap();
+    manager.mint(shields, ADMIN, 1, 3).unwrap();
+
+    assert_eq!(manager.balance_of(swords, ALICE, 1).unwrap(), 4);
+    assert_eq!(manager.total_supply(shields, 1).unwrap(), 3);
+    assert_eq!(manager.uri(swords, 7).unwrap(), format!("ipfs://swords/{:064x}", 7));
+    assert_eq!(manager.collections_owned_by(BOB), vec![shields]);
+    assert_eq!(manager.collection(swords).unwrap().event_count(), 4);
+    assert_eq!(manager.collection(shields).unwrap().event_count(), 2);
+
+    let balances = manager.balances_of(ADMIN);
+    let summary: Vec<(u64, u32, u64)> = balances
+        .iter()
+        .map(|entry| (entry.collection_id, entry.token_id, entry.balance))
+        .collect();
+    assert_eq!(summary, vec![(swords, 1, 6), (shields, 1, 3)]);
+}
+
+#[test]
+fn collections_route_json_requests() {
+    let mut manager = CollectionManager::new();
+    let id = manager
+        .create_collection(OWNER, &json!({ "admins": [ADMIN] }).to_string())
+        .unwrap();
+    let request = json!({ "id": 7, "method": "mint", "caller": ADMIN, "params": { "token_id": 2, "amount": 5 } });
+    let response: Value = serde_json::from_str(&manager.call(id, &request.to_string())).unwrap();
+    assert_eq!(response["ok"], json!(true));
+    assert_eq!(manager.balance_of(id, ADMIN, 2).unwrap(), 5);
+
+    let response: Value = serde_json::from_str(&manager.call(id + 1, &request.to_string())).unwrap();
+    assert_eq!(response["id"], json!(7));
+    assert_eq!(response["error"]["code"], json!("InvalidInput"));
+    assert_eq!(manager.balance_of(id + 1, ADMIN, 2).unwrap_err().kind(), ErrorKind::InvalidInput);
+
+    let err = manager.create_collection(OWNER, "{\"admins\": 1}").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    assert_eq!(manager.collection_count(), 1);
+}
+
+#[test]
+fn collection_owners_are_normalized() {
+    let mut manager = CollectionManager::new();
+    let id = manager
+        .create_collection(" 0xABCDEF ", &json!({ "admins": [ADMIN] }).to_string())
+        .unwrap();
+    assert_eq!(manager.owner(id).unwrap().as_deref(), Some("0xabcdef"));
+    assert_eq!(manager.collections_owned_by("0xAbCdEf"), vec![id]);
+
+    manager.register_name(id, "0xabcdef", "maker").unwrap();
+    assert_eq!(manager.collections_owned_by("@maker"), vec![id]);
+    assert_eq!(manager.collections_owned_by("@nobody"), Vec::<u64>::new());
+
+    let err = manager.create_collection("  ", "").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    assert_eq!(manager.collection_count(), 1);
+}
+
+#[test]
+fn every_call_has_a_collection_scoped_variant() {
+    let mut manager = CollectionManager::new();
+    let id = manager
+        .create_collection(OWNER, &json!({ "admins": [ADMIN] }).to_string())
+        .unwrap();
+    manager.mint(id, ADMIN, 1, 10).unwrap();
+    manager.transfer_batch(id, ADMIN, ALICE, vec![1], vec![4]).unwrap();
+    manager.burn_from(id, ALICE, ALICE, 1, 1).unwrap();
+    manager.pause(id, OWNER).unwrap();
+    assert!(manager.is_paused(id).unwrap());
+    manager.unpause(id, OWNER).unwrap();
+    manager.set_attribute(id, ADMIN, 1, "rarity", "rare").unwrap();
+    assert_eq!(manager.attribute(id, 1, "rarity").unwrap().as_deref(), Some("rare"));
+
+    let balances = manager.collection_balances_of(id, ALICE).unwrap();
+    assert_eq!(balances.len(), 1);
+    assert_eq!((balances[0].token_id, balances[0].balance), (1, 3));
+    assert_eq!(manager.total_supply(id, 1).unwrap(), 9);
+    assert_eq!(manager.event_count(id).unwrap(), manager.collection(id).unwrap().event_count());
+    assert_eq!(manager.state_hash(id).unwrap(), manager.collection(id).unwrap().state_hash());
+
+    assert_eq!(manager.pause(id + 1, OWNER).unwrap_err().kind(), ErrorKind::InvalidInput);
+}
@@ -0,0 +1,19 @@
+//! Accounts and the base fixture shared by the integration tests.
+
+// Each test crate uses its own subset of these.
+#![allow(dead_code)]
+
+use erc1155_wasm::ERC1155;
+
+pub const OWNER: &str = "owner";
+pub const ADMIN: &str = "admin";
+pub const ALICE: &str = "alice";
+pub const BOB: &str = "bob";
+pub const CAROL: &str = "carol";
+
+/// Contract owned by `OWNER` with `ADMIN` as its admin.
+pub fn setup() -> ERC1155 {
+    let mut contract = ERC1155::new(OWNER).unwrap();
+    contract.add_admin(OWNER, ADMIN).unwrap();
+    contract
+}
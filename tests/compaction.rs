@@ -0,0 +1,56 @@
+mod common;
+
+use common::{ADMIN, ALICE, BOB, OWNER};
+use erc1155_wasm::ERC1155;
+
+fn setup() -> ERC1155 {
+    let mut contract = common::setup();
+    contract.set_time(OWNER, Some(0)).unwrap();
+    contract.mint(ADMIN, 1, 100).unwrap();
+    contract.mint(ADMIN, 2, 10).unwrap();
+    contract
+}
+
+#[test]
+fn zero_balances_are_removed_as_written() {
+    let mut contract = setup();
+    let snapshot = contract.take_snapshot(ADMIN).unwrap();
+    contract.transfer(ADMIN, ALICE, 1, 100).unwrap();
+    contract.burn(ADMIN, 2, 10).unwrap();
+
+    let stats = contract.state_stats();
+    assert_eq!(stats.balance_entries, 1);
+    assert_eq!(stats.balance_tables, 1);
+    assert_eq!(contract.holder_count(1), 1);
+    assert_eq!(contract.total_supply(2), 0);
+    // Pruned holders still answer for the snapshots they held a balance in.
+    assert_eq!(contract.balance_of_at(ADMIN, 1, snapshot).unwrap(), 100);
+    assert_eq!(contract.total_supply_at(2, snapshot).unwrap(), 10);
+}
+
+#[test]
+fn compact_sweeps_expired_approvals_in_steps() {
+    let mut contract = setup();
+    for operator in ["op1", "op2", "op3"] {
+        contract.approve_until(ALICE, operator, 100).unwrap();
+    }
+    contract.approve_until(BOB, "op1", 100).unwrap();
+    contract.approve(BOB, "op2", 1).unwrap();
+    assert_eq!(contract.compact(10).unwrap(), 0);
+
+    contract.set_time(OWNER, Some(100)).unwrap();
+    let hash = contract.state_hash();
+    let stats = contract.state_stats();
+    assert_eq!((stats.approval_entries, stats.expired_approvals), (5, 4));
+    let events = contract.event_count();
+    assert_eq!(contract.compact(3).unwrap(), 3);
+    assert_eq!(contract.compact(3).unwrap(), 1);
+    assert_eq!(contract.compact(3).unwrap(), 0);
+
+    let stats = contract.state_stats();
+    assert_eq!((stats.approval_entries, stats.expired_approvals), (1, 0));
+    assert!(contract.is_approved(BOB, "op2"));
+    assert_eq!(contract.event_count(), events);
+    // The hash covers approvals as stored, so it tells compacted state apart.
+    assert_ne!(contract.state_hash(), hash);
+}
@@ -0,0 +1,55 @@
+mod common;
+
+use common::{ADMIN, ALICE};
+use erc1155_wasm::{ErrorKind, EventKind, ERC1155};
+
+const POTION: u32 = 1;
+const CREDITS: u32 = 2;
+
+/// Contract where ALICE holds 3 single-use potions and 2 packs of 10 API credits.
+fn setup() -> ERC1155 {
+    let mut contract = common::setup();
+    contract.mint(ADMIN, POTION, 3).unwrap();
+    contract.mint(ADMIN, CREDITS, 2).unwrap();
+    contract.set_token_uses(ADMIN, CREDITS, 10).unwrap();
+    contract.transfer(ADMIN, ALICE, POTION, 3).unwrap();
+    contract.transfer(ADMIN, ALICE, CREDITS, 2).unwrap();
+    contract
+}
+
+#[test]
+fn single_use_tokens_are_burned_when_consumed() {
+    let mut contract = setup();
+    assert_eq!(contract.uses_remaining(ALICE, POTION), 3);
+
+    let receipt = contract.consume(ALICE, POTION, 2, "heal").unwrap();
+    assert_eq!(receipt.balances[0].after, 1);
+    let consumed = receipt.events.last().unwrap();
+    assert_eq!(consumed.kind, EventKind::Consumed);
+    assert_eq!(consumed.value.as_deref(), Some("heal"));
+    assert_eq!(consumed.amount, Some(2));
+    assert_eq!(contract.total_supply(POTION), 1);
+
+    let err = contract.consume(ALICE, POTION, 2, "heal").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InsufficientBalance);
+    let err = contract.consume(ALICE, POTION, 1, &"x".repeat(257)).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+}
+
+#[test]
+fn multi_use_tokens_are_opened_on_first_use() {
+    let mut contract = setup();
+    assert_eq!(contract.uses_remaining(ALICE, CREDITS), 20);
+
+    let receipt = contract.consume(ALICE, CREDITS, 3, "GET /quote").unwrap();
+    assert_eq!(receipt.balances[0].after, 1);
+    assert_eq!(contract.uses_remaining(ALICE, CREDITS), 17);
+
+    // The 7 uses left of the opened pack are spent first, then the next pack is opened.
+    let receipt = contract.consume(ALICE, CREDITS, 7, "GET /quote").unwrap();
+    assert!(receipt.balances.is_empty());
+    contract.consume(ALICE, CREDITS, 4, "GET /quote").unwrap();
+    assert_eq!(contract.balance_of(ALICE, CREDITS), 0);
+    assert_eq!(contract.uses_remaining(ALICE, CREDITS), 6);
+    assert_eq!(contract.uses_remaining(ADMIN, CREDITS), 0);
+}
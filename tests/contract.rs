@@ -0,0 +1,354 @@
+mod common;
+
+use common::{ADMIN, ALICE, BOB, OWNER};
+use erc1155_wasm::{BalanceReplica, ErrorKind, EventKind, ERC1155};
+
+/// Contract where `ADMIN` is an admin and `ALICE` holds 100 of token 1 and 50 of token 2.
+fn setup() -> ERC1155 {
+    let mut contract = common::setup();
+    contract.mint(ADMIN, 1, 100).unwrap();
+    contract.mint(ADMIN, 2, 50).unwrap();
+    contract.transfer_batch(ADMIN, ALICE, vec![1, 2], vec![100, 50]).unwrap();
+    contract
+}
+
+#[test]
+fn only_owner_can_add_admins() {
+    let mut contract = ERC1155::new(OWNER).unwrap();
+    let err = contract.add_admin(ALICE, BOB).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Unauthorized);
+    contract.add_admin(OWNER, ADMIN).unwrap();
+}
+
+#[test]
+fn owner_is_normalized_like_callers() {
+    let mut contract = ERC1155::new(" 0xABCDEF ").unwrap();
+    assert_eq!(contract.owner().as_deref(), Some("0xabcdef"));
+    contract.add_admin("0xABCDEF", ADMIN).unwrap();
+    contract.add_admin(" 0xabcdef", BOB).unwrap();
+
+    assert_eq!(ERC1155::new("  ").err().unwrap().kind(), ErrorKind::InvalidInput);
+    assert_eq!(ERC1155::new("erc1155:escrow").err().unwrap().kind(), ErrorKind::InvalidInput);
+}
+
+#[test]
+fn only_admins_can_mint() {
+    let mut contract = ERC1155::new(OWNER).unwrap();
+    assert_eq!(contract.mint(OWNER, 1, 10).unwrap_err().kind(), ErrorKind::Unauthorized);
+
+    contract.add_admin(OWNER, ADMIN).unwrap();
+    let receipt = contract.mint(ADMIN, 1, 10).unwrap();
+    assert_eq!(receipt.from, None);
+    assert_eq!(receipt.to, ADMIN);
+    assert_eq!(receipt.to_balance, 10);
+    assert_eq!(contract.balance_of(ADMIN, 1), 10);
+}
+
+#[test]
+fn mint_overflow_is_rejected() {
+    let mut contract = common::setup();
+    contract.mint(ADMIN, 1, u64::MAX).unwrap();
+
+    assert_eq!(contract.mint(ADMIN, 1, 1).unwrap_err().kind(), ErrorKind::Overflow);
+    assert_eq!(contract.balance_of(ADMIN, 1), u64::MAX);
+    // The failed mint must not leave the reentrancy guard locked.
+    contract.mint(ADMIN, 2, 1).unwrap();
+}
+
+#[test]
+fn transfer_overflow_is_rejected() {
+    let mut contract = common::setup();
+    contract.add_admin(OWNER, ALICE).unwrap();
+    contract.mint(ADMIN, 1, u64::MAX).unwrap();
+    contract.mint(ALICE, 1, 1).unwrap();
+
+    assert_eq!(contract.transfer(ALICE, ADMIN, 1, 1).unwrap_err().kind(), ErrorKind::Overflow);
+    assert_eq!(contract.balance_of(ALICE, 1), 1);
+    assert_eq!(contract.balance_of(ADMIN, 1), u64::MAX);
+}
+
+#[test]
+fn holders_can_transfer_their_own_tokens() {
+    let mut contract = setup();
+    let receipt = contract.transfer(ALICE, BOB, 1, 30).unwrap();
+
+    assert_eq!(receipt.operator, ALICE);
+    assert_eq!(receipt.from.as_deref(), Some(ALICE));
+    assert_eq!(receipt.from_balance, Some(70));
+    assert_eq!(receipt.to_balance, 30);
+    assert_eq!(contract.balance_of(ALICE, 1), 70);
+    assert_eq!(contract.balance_of(BOB, 1), 30);
+}
+
+#[test]
+fn transfer_requires_sufficient_balance() {
+    let mut contract = setup();
+    let err = contract.transfer(ALICE, BOB, 1, 101).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InsufficientBalance);
+    assert_eq!(contract.balance_of(ALICE, 1), 100);
+    assert_eq!(contract.balance_of(BOB, 1), 0);
+}
+
+#[test]
+fn self_transfer_keeps_balance() {
+    let mut contract = setup();
+    let receipt = contract.transfer(ALICE, ALICE, 1, 40).unwrap();
+    assert_eq!(receipt.from_balance, Some(100));
+    assert_eq!(receipt.to_balance, 100);
+    assert_eq!(contract.balance_of(ALICE, 1), 100);
+}
+
+#[test]
+fn operators_need_approval() {
+    let mut contract = setup();
+    let err = contract.transfer_from(BOB, ALICE, BOB, 1, 10).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Unauthorized);
+
+    contract.approve(ALICE, BOB, 1).unwrap();
+    assert!(contract.is_approved(ALICE, BOB));
+    assert!(!contract.is_approved(BOB, ALICE));
+
+    let receipt = contract.transfer_from(BOB, ALICE, OWNER, 1, 10).unwrap();
+    assert_eq!(receipt.operator, BOB);
+    assert_eq!(receipt.from.as_deref(), Some(ALICE));
+    assert_eq!(contract.balance_of(ALICE, 1), 90);
+    assert_eq!(contract.balance_of(OWNER, 1), 10);
+}
+
+#[test]
+fn approval_does_not_leak_to_other_holders() {
+    let mut contract = setup();
+    contract.transfer(ALICE, OWNER, 1, 10).unwrap();
+    contract.approve(ALICE, BOB, 1).unwrap();
+
+    let err = contract.transfer_from(BOB, OWNER, BOB, 1, 1).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Unauthorized);
+}
+
+#[test]
+fn batch_transfer_is_all_or_nothing() {
+    let mut contract = setup();
+    let err = contract.transfer_batch(ALICE, BOB, vec![1, 2], vec![10, 51]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InsufficientBalance);
+    assert_eq!(contract.balance_of(ALICE, 1), 100);
+    assert_eq!(contract.balance_of(BOB, 1), 0);
+
+    let receipts = contract.transfer_batch(ALICE, BOB, vec![1, 2], vec![10, 50]).unwrap();
+    assert_eq!(receipts.len(), 2);
+    assert_eq!(contract.balance_of(BOB, 1), 10);
+    assert_eq!(contract.balance_of(BOB, 2), 50);
+}
+
+#[test]
+fn batch_transfer_accounts_for_repeated_ids() {
+    let mut contract = setup();
+    let err = contract.transfer_batch(ALICE, BOB, vec![1, 1], vec![60, 60]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InsufficientBalance);
+    assert_eq!(contract.balance_of(ALICE, 1), 100);
+}
+
+#[test]
+fn batch_length_mismatch_is_rejected() {
+    let mut contract = setup();
+    let err = contract.transfer_batch(ALICE, BOB, vec![1, 2], vec![10]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+
+    let err = contract.balance_of_batch(vec![ALICE.into()], vec![1, 2]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+}
+
+#[test]
+fn balance_queries_return_entries() {
+    let contract = setup();
+    let entries = contract
+        .balance_of_batch(vec![ALICE.into(), BOB.into()], vec![2, 2])
+        .unwrap();
+    assert_eq!(entries[0].balance, 50);
+    assert_eq!(entries[1].balance, 0);
+
+    let held: Vec<(u32, u64)> = contract
+        .balances_of(ALICE)
+        .iter()
+        .map(|entry| (entry.token_id, entry.balance))
+        .collect();
+    assert_eq!(held, vec![(1, 100), (2, 50)]);
+}
+
+#[test]
+fn events_are_recorded_in_order() {
+    let mut contract = setup();
+    let start = contract.event_count();
+    contract.approve(ALICE, BOB, 1).unwrap();
+    contract.transfer_from(BOB, ALICE, BOB, 2, 5).unwrap();
+
+    let events = contract.get_events(start, 10);
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].kind, EventKind::ApprovalForAll);
+    assert_eq!(events[0].seq, start);
+    assert_eq!(events[1].kind, EventKind::TransferSingle);
+    assert_eq!(events[1].operator, BOB);
+    assert_eq!(events[1].amount, Some(5));
+}
+
+#[test]
+fn ownership_transfer() {
+    let mut contract = ERC1155::new(OWNER).unwrap();
+    let err = contract.transfer_ownership(ALICE, ALICE).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Unauthorized);
+
+    contract.transfer_ownership(OWNER, ALICE).unwrap();
+    assert_eq!(contract.add_admin(OWNER, BOB).unwrap_err().kind(), ErrorKind::Unauthorized);
+    contract.add_admin(ALICE, BOB).unwrap();
+}
+
+#[test]
+fn replica_follows_snapshot_and_events() {
+    let mut contract = setup();
+    let mut replica = BalanceReplica::from_snapshot_json(&contract.snapshot_json()).unwrap();
+    assert_eq!(replica.balance_of(ALICE, 1), 100);
+
+    let seq = replica.seq();
+    contract.transfer(ALICE, BOB, 1, 25).unwrap();
+    contract.approve(BOB, ALICE, 1).unwrap();
+    let next = replica.apply_events_json(&contract.events_json(seq, 100)).unwrap();
+
+    assert_eq!(next, contract.event_count());
+    assert_eq!(replica.balance_of(ALICE, 1), 75);
+    assert_eq!(replica.balance_of(BOB, 1), 25);
+
+    contract.transfer(ALICE, BOB, 1, 1).unwrap();
+    contract.transfer(ALICE, BOB, 1, 1).unwrap();
+    let err = replica.apply_events_json(&contract.events_json(next + 1, 100)).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    assert_eq!(replica.balance_of(BOB, 1), 25);
+}
+
+#[test]
+fn failed_calls_release_the_reentrancy_guard() {
+    let mut contract = setup();
+    assert!(contract.mint(BOB, 1, 1).is_err());
+    assert!(contract.transfer(ALICE, BOB, 1, 1_000).is_err());
+    assert!(contract.transfer_from(BOB, ALICE, BOB, 1, 1).is_err());
+    assert!(contract.transfer_batch(ALICE, BOB, vec![1], vec![]).is_err());
+    assert!(contract.add_admin(BOB, BOB).is_err());
+    assert!(contract.transfer_ownership(BOB, BOB).is_err());
+
+    contract.mint(ADMIN, 1, 1).unwrap();
+    contract.transfer(ALICE, BOB, 1, 1).unwrap();
+    contract.approve(ALICE, BOB, 1).unwrap();
+    contract.transfer_batch(ALICE, BOB, vec![1, 2], vec![1, 1]).unwrap();
+}
+
+#[test]
+fn approvals_can_be_listed_and_revoked() {
+    let mut contract = setup();
+    contract.approve(ALICE, BOB, 1).unwrap();
+    contract.approve(ALICE, OWNER, 1).unwrap();
+    assert_eq!(contract.get_approvals(ALICE), vec![BOB.to_string(), OWNER.to_string()]);
+
+    let receipt = contract.revoke_approval(ALICE, BOB).unwrap();
+    assert_eq!(receipt.events[0].approved, Some(false));
+    assert!(!contract.is_approved(ALICE, BOB));
+    assert_eq!(contract.transfer_from(BOB, ALICE, BOB, 1, 1).unwrap_err().kind(), ErrorKind::Unauthorized);
+    assert_eq!(contract.revoke_approval(ALICE, BOB).unwrap_err().kind(), ErrorKind::InvalidInput);
+
+    contract.approve(ALICE, BOB, 1).unwrap();
+    let receipt = contract.revoke_all_approvals(ALICE).unwrap();
+    assert_eq!(receipt.events.len(), 2);
+    assert!(contract.get_approvals(ALICE).is_empty());
+    assert!(contract.revoke_all_approvals(ALICE).unwrap().events.is_empty());
+}
+
+#[test]
+fn approvals_with_a_deadline_expire() {
+    let mut contract = setup();
+    contract.set_time(OWNER, Some(1_000)).unwrap();
+    assert_eq!(contract.approve_until(ALICE, BOB, 1_000).unwrap_err().kind(), ErrorKind::InvalidInput);
+
+    let receipt = contract.approve_until(ALICE, BOB, 2_000).unwrap();
+    assert_eq!(receipt.events[0].expires_at, Some(2_000));
+    assert_eq!(contract.approval_expiry(ALICE, BOB), Some(2_000));
+    contract.transfer_from(BOB, ALICE, BOB, 1, 10).unwrap();
+
+    contract.set_time(OWNER, Some(2_000)).unwrap();
+    assert!(!contract.is_approved(ALICE, BOB));
+    assert!(contract.get_approvals(ALICE).is_empty());
+    assert_eq!(contract.approval_expiry(ALICE, BOB), None);
+    let err = contract.transfer_from(BOB, ALICE, BOB, 1, 10).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Unauthorized);
+
+    // Re-approving indefinitely replaces the expired entry.
+    contract.approve(ALICE, BOB, 1).unwrap();
+    assert_eq!(contract.approval_expiry(ALICE, BOB), None);
+    contract.transfer_from(BOB, ALICE, BOB, 1, 10).unwrap();
+    assert_eq!(contract.balance_of(BOB, 1), 20);
+}
+
+#[test]
+fn burns_reduce_supply_until_the_token_is_gone() {
+    let mut contract = setup();
+    let err = contract.burn_from(BOB, ALICE, 1, 10).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Unauthorized);
+    assert_eq!(contract.burn(ALICE, 1, 0).unwrap_err().kind(), ErrorKind::InvalidInput);
+    assert_eq!(contract.burn(ALICE, 1, 101).unwrap_err().kind(), ErrorKind::InsufficientBalance);
+
+    let receipt = contract.burn(ALICE, 1, 40).unwrap();
+    assert_eq!((receipt.balances[0].before, receipt.balances[0].after), (100, 60));
+    assert_eq!(receipt.events.len(), 1);
+    assert_eq!(receipt.events[0].to, None);
+    assert_eq!(contract.total_supply(1), 60);
+    assert!(contract.exists(1));
+
+    contract.approve(ALICE, BOB, 1).unwrap();
+    let receipt = contract.burn_from(BOB, ALICE, 1, 60).unwrap();
+    assert_eq!(receipt.events[1].kind, EventKind::SupplyDepleted);
+    assert_eq!(receipt.events[1].token_id, Some(1));
+    assert_eq!(contract.total_supply(1), 0);
+    assert!(!contract.exists(1));
+    assert!(contract.exists(2));
+    assert!(!contract.exists(3));
+}
+
+#[test]
+fn renouncing_ownership_disables_owner_calls() {
+    let mut contract = setup();
+    let err = contract.renounce_ownership(ADMIN, false).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Unauthorized);
+    assert_eq!(contract.owner().as_deref(), Some(OWNER));
+
+    let receipt = contract.renounce_ownership(OWNER, false).unwrap();
+    assert_eq!(receipt.events[0].kind, EventKind::OwnershipRenounced);
+    assert_eq!(contract.owner(), None);
+    assert!(!contract.is_immutable());
+    assert_eq!(contract.pause(OWNER).unwrap_err().kind(), ErrorKind::Unauthorized);
+    assert_eq!(contract.add_admin(OWNER, BOB).unwrap_err().kind(), ErrorKind::Unauthorized);
+    assert_eq!(
+        contract.transfer_ownership("erc1155:renounced", BOB).unwrap_err().kind(),
+        ErrorKind::InvalidInput
+    );
+    // Admins keep their rights unless the configuration is frozen.
+    contract.mint(ADMIN, 3, 1).unwrap();
+}
+
+#[test]
+fn immutable_contract_only_moves_tokens() {
+    let mut contract = setup();
+    contract.set_guardians(OWNER, vec![BOB.to_string()], 1, 0).unwrap();
+    contract.renounce_ownership(OWNER, true).unwrap();
+    assert!(contract.is_immutable());
+    assert!(contract.guardians().is_empty());
+
+    assert_eq!(contract.mint(ADMIN, 1, 1).unwrap_err().kind(), ErrorKind::Unauthorized);
+    assert_eq!(contract.mint(ADMIN, 9, 1).unwrap_err().kind(), ErrorKind::Unauthorized);
+    let err = contract.set_token_uri(ADMIN, 1, "ipfs://x").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Unauthorized);
+    let err = contract.set_blacklisted(ADMIN, ALICE, true).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Unauthorized);
+    let err = contract.support_recovery(BOB, BOB).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Unauthorized);
+
+    contract.transfer(ALICE, BOB, 1, 10).unwrap();
+    contract.burn(BOB, 1, 5).unwrap();
+    contract.approve(ALICE, BOB, 0).unwrap();
+    assert_eq!(contract.total_supply(1), 95);
+}
@@ -0,0 +1,93 @@
+mod common;
+
+use common::{ADMIN, ALICE, BOB, CAROL, OWNER};
+use erc1155_wasm::{ErrorKind, EventKind, ERC1155};
+
+const POWER: u32 = 1;
+
+/// Contract where ALICE holds 60 and BOB 40 power tokens.
+fn setup() -> ERC1155 {
+    let mut contract = common::setup();
+    contract.mint(ADMIN, POWER, 100).unwrap();
+    contract.transfer(ADMIN, ALICE, POWER, 60).unwrap();
+    contract.transfer(ADMIN, BOB, POWER, 40).unwrap();
+    contract
+}
+
+#[test]
+fn delegation_moves_weight_not_tokens() {
+    let mut contract = setup();
+    contract.delegate(ALICE, POWER, BOB).unwrap();
+    assert_eq!(contract.balance_of(ALICE, POWER), 60);
+    assert_eq!(contract.delegated_weight_of(ALICE, POWER), 0);
+    assert_eq!(contract.delegated_weight_of(BOB, POWER), 100);
+    assert_eq!(contract.delegate_of(ALICE, POWER), Some(BOB.to_string()));
+    assert_eq!(contract.delegators_of(BOB, POWER), vec![ALICE.to_string()]);
+
+    let err = contract.delegate(ALICE, POWER, BOB).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    let event = contract.get_events(contract.event_count() - 1, 1).remove(0);
+    assert_eq!(event.kind, EventKind::DelegateChanged);
+    assert_eq!(event.to.as_deref(), Some(BOB));
+
+    contract.delegate(ALICE, POWER, CAROL).unwrap();
+    assert_eq!(contract.delegated_weight_of(BOB, POWER), 40);
+    assert_eq!(contract.delegated_weight_of(CAROL, POWER), 60);
+    assert!(contract.delegators_of(BOB, POWER).is_empty());
+
+    contract.undelegate(ALICE, POWER).unwrap();
+    assert_eq!(contract.delegated_weight_of(ALICE, POWER), 60);
+    assert_eq!(contract.delegated_weight_of(CAROL, POWER), 0);
+    assert_eq!(contract.delegate_of(ALICE, POWER), None);
+    let err = contract.undelegate(ALICE, POWER).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+}
+
+#[test]
+fn delegated_weight_follows_transfers() {
+    let mut contract = setup();
+    contract.delegate(ALICE, POWER, CAROL).unwrap();
+    contract.transfer(BOB, ALICE, POWER, 15).unwrap();
+    assert_eq!(contract.delegated_weight_of(CAROL, POWER), 75);
+    contract.transfer(ALICE, CAROL, POWER, 25).unwrap();
+    assert_eq!(contract.delegated_weight_of(CAROL, POWER), 75);
+    assert_eq!(contract.delegated_weight_of(BOB, POWER), 25);
+}
+
+#[test]
+fn delegation_is_per_token_and_not_transitive() {
+    let mut contract = setup();
+    contract.mint(ADMIN, 2, 10).unwrap();
+    contract.delegate(ALICE, POWER, BOB).unwrap();
+    contract.delegate(BOB, POWER, CAROL).unwrap();
+    assert_eq!(contract.delegated_weight_of(BOB, POWER), 60);
+    assert_eq!(contract.delegated_weight_of(CAROL, POWER), 40);
+    assert_eq!(contract.delegate_of(ALICE, 2), None);
+    assert_eq!(contract.delegated_weight_of(ADMIN, 2), 10);
+
+    // Delegating to oneself takes the weight back.
+    contract.delegate(ALICE, POWER, ALICE).unwrap();
+    assert_eq!(contract.delegated_weight_of(ALICE, POWER), 60);
+}
+
+#[test]
+fn undelegating_leaves_no_trace_in_the_state() {
+    let mut contract = setup();
+    let before = contract.state_hash();
+    contract.delegate(ALICE, POWER, BOB).unwrap();
+    contract.undelegate(ALICE, POWER).unwrap();
+    assert_eq!(contract.state_hash(), before);
+}
+
+#[test]
+fn expired_tokens_carry_no_weight() {
+    let mut contract = setup();
+    contract.set_time(OWNER, Some(0)).unwrap();
+    contract.delegate(ALICE, POWER, BOB).unwrap();
+    contract.set_token_expiry(ADMIN, POWER, Some(1_000)).unwrap();
+    assert_eq!(contract.delegated_weight_of(BOB, POWER), 100);
+
+    contract.set_time(OWNER, Some(1_000)).unwrap();
+    assert_eq!(contract.delegated_weight_of(BOB, POWER), 0);
+    assert_eq!(contract.delegated_weight_of(ALICE, POWER), 0);
+}
@@ -0,0 +1,237 @@
+mod common;
+
+use common::{ADMIN, ALICE, BOB, OWNER};
+use erc1155_wasm::{BalanceReplica, ErrorKind, StateDiff, ERC1155};
+
+fn setup() -> ERC1155 {
+    let mut contract = common::setup();
+    contract.mint(ADMIN, 1, 100).unwrap();
+    contract.transfer(ADMIN, ALICE, 1, 100).unwrap();
+    contract
+}
+
+#[test]
+fn diff_of_identical_contracts_is_empty() {
+    let contract = setup();
+    assert!(contract.diff(&contract.clone()).is_empty());
+}
+
+#[test]
+fn applying_a_diff_converges_replicas() {
+    let mut replica = setup();
+    let mut server = replica.clone();
+    server.transfer(ALICE, BOB, 1, 100).unwrap();
+    server.mint(ADMIN, 2, 5).unwrap();
+    server.approve(BOB, ALICE, 1).unwrap();
+    server.transfer_ownership(OWNER, BOB).unwrap();
+
+    let diff = replica.diff(&server);
+    assert_eq!(diff.balance_changes(), 3);
+    assert_eq!(diff.approval_changes(), 1);
+
+    let diff = StateDiff::from_json(&diff.to_json()).unwrap();
+    replica.apply_diff(&diff).unwrap();
+    assert!(replica.diff(&server).is_empty());
+    assert_eq!(replica.balance_of(ALICE, 1), 0);
+    assert_eq!(replica.balance_of(BOB, 1), 100);
+    assert_eq!(replica.balance_of(ADMIN, 2), 5);
+    assert!(replica.is_approved(BOB, ALICE));
+    replica.add_admin(BOB, ALICE).unwrap();
+}
+
+#[test]
+fn diffs_are_directional() {
+    let before = setup();
+    let mut after = before.clone();
+    after.transfer(ALICE, BOB, 1, 40).unwrap();
+
+    let mut rolled_back = after.clone();
+    rolled_back.apply_diff(&after.diff(&before)).unwrap();
+    assert_eq!(rolled_back.balance_of(ALICE, 1), 100);
+    assert_eq!(rolled_back.balance_of(BOB, 1), 0);
+    assert!(rolled_back.diff(&before).is_empty());
+}
+
+#[test]
+fn diffs_carry_policies_roles_and_metadata() {
+    let mut replica = setup();
+    let mut server = replica.clone();
+    server.set_blacklisted(ADMIN, BOB, true).unwrap();
+    server.set_max_batch_size(ADMIN, 7).unwrap();
+    server.set_creator_role(OWNER, ALICE, true).unwrap();
+    server.set_attribute(ADMIN, 1, "rarity", "legendary").unwrap();
+    server.register_name(ALICE, "alice.eth").unwrap();
+    server.pause(OWNER).unwrap();
+
+    let diff = replica.diff(&server);
+    assert_eq!(diff.balance_changes(), 0);
+    assert_eq!(diff.changed_sections(), vec!["metadata", "names", "policy", "tokens"]);
+
+    let diff = StateDiff::from_json(&diff.to_json()).unwrap();
+    replica.apply_diff(&diff).unwrap();
+    assert!(replica.diff(&server).is_empty());
+    assert!(replica.is_paused());
+    assert!(replica.is_blacklisted(BOB));
+    assert_eq!(replica.max_batch_size(), 7);
+    assert_eq!(replica.resolve("alice.eth").as_deref(), Some(ALICE));
+    let query = r#"[{"key": "rarity", "op": "==", "value": "legendary"}]"#;
+    assert_eq!(replica.find_tokens(query, 0, 10).unwrap(), vec![1]);
+}
+
+#[test]
+fn applying_a_diff_keeps_event_followers_in_sync() {
+    let mut replica = setup();
+    let mut follower = BalanceReplica::from_snapshot_json(&replica.snapshot_json()).unwrap();
+    let mut server = replica.clone();
+    server.transfer(ALICE, BOB, 1, 30).unwrap();
+    server.mint(ADMIN, 2, 5).unwrap();
+
+    let seq = follower.seq();
+    replica.apply_diff(&replica.diff(&server)).unwrap();
+    follower.apply_events_json(&replica.events_json(seq, 100)).unwrap();
+    assert_eq!(follower.balance_of(ALICE, 1), 70);
+    assert_eq!(follower.balance_of(BOB, 1), 30);
+    assert_eq!(follower.balance_of(ADMIN, 2), 5);
+}
+
+#[test]
+fn diff_with_an_invalid_section_changes_nothing() {
+    let mut replica = setup();
+    let diff = StateDiff::from_json(r#"{"balances": [["bob", 1, 5]], "sections": {"policy": 3}}"#).unwrap();
+    assert_eq!(replica.apply_diff(&diff).unwrap_err().kind(), ErrorKind::InvalidInput);
+    let diff = StateDiff::from_json(r#"{"sections": {"unknown": {}}}"#).unwrap();
+    assert_eq!(replica.apply_diff(&diff).unwrap_err().kind(), ErrorKind::InvalidInput);
+    assert_eq!(replica.balance_of(BOB, 1), 0);
+}
+
+#[test]
+fn malformed_diff_json_is_rejected() {
+    assert!(StateDiff::from_json("{\"balances\": 3}").is_err());
+    assert!(StateDiff::from_json("{}").unwrap().is_empty());
+}
+
+#[test]
+fn state_hash_ignores_operation_order() {
+    let mut a = common::setup();
+    a.add_admin(OWNER, BOB).unwrap();
+    a.mint(ADMIN, 1, 10).unwrap();
+    a.mint(ADMIN, 2, 20).unwrap();
+
+    let mut b = ERC1155::new(OWNER).unwrap();
+    b.add_admin(OWNER, BOB).unwrap();
+    b.add_admin(OWNER, ADMIN).unwrap();
+    b.mint(ADMIN, 2, 20).unwrap();
+    b.mint(ADMIN, 1, 4).unwrap();
+    b.mint(ADMIN, 1, 6).unwrap();
+
+    assert_eq!(a.state_hash(), b.state_hash());
+    assert_eq!(a.state_hash().len(), 64);
+
+    b.transfer(ADMIN, ALICE, 1, 1).unwrap();
+    assert_ne!(a.state_hash(), b.state_hash());
+}
+
+#[test]
+fn state_hash_covers_every_section() {
+    type Change = fn(&mut ERC1155);
+    let changes: [Change; 12] = [
+        |c| {
+            c.approve_scoped(ALICE, BOB, vec![1], vec![5]).unwrap();
+        },
+        |c| {
+            c.register_name(ALICE, "alice.eth").unwrap();
+        },
+        |c| {
+            c.set_blacklisted(ADMIN, BOB, true).unwrap();
+        },
+        |c| {
+            c.pause(OWNER).unwrap();
+        },
+        |c| {
+            c.set_max_batch_size(ADMIN, 7).unwrap();
+        },
+        |c| {
+            c.renounce_ownership(OWNER, true).unwrap();
+        },
+        |c| {
+            c.set_royalty(ADMIN, 1, BOB, 250).unwrap();
+        },
+        |c| {
+            c.set_creator_role(OWNER, BOB, true).unwrap();
+        },
+        |c| {
+            c.set_guardians(OWNER, vec![BOB.to_string()], 1, 0).unwrap();
+        },
+        |c| {
+            c.set_attribute(ADMIN, 1, "rarity", "rare").unwrap();
+        },
+        |c| {
+            c.delegate(ALICE, 1, BOB).unwrap();
+        },
+        |c| {
+            c.set_bridge_operator(OWNER, BOB, true).unwrap();
+        },
+    ];
+    let base = setup();
+    for (index, change) in changes.iter().enumerate() {
+        let mut changed = base.clone();
+        change(&mut changed);
+        assert_ne!(changed.state_hash(), base.state_hash(), "change {}", index);
+
+        let mut replica = base.clone();
+        replica.apply_diff(&replica.diff(&changed)).unwrap();
+        assert_eq!(replica.state_hash(), changed.state_hash(), "change {}", index);
+    }
+}
+
+#[test]
+fn state_hash_matches_after_applying_a_diff() {
+    let mut replica = setup();
+    let mut server = replica.clone();
+    server.transfer(ALICE, BOB, 1, 100).unwrap();
+    server.approve(ALICE, BOB, 1).unwrap();
+    assert_ne!(replica.state_hash(), server.state_hash());
+
+    replica.apply_diff(&replica.diff(&server)).unwrap();
+    assert_eq!(replica.state_hash(), server.state_hash());
+}
+
+#[test]
+fn diffs_carry_approval_deadlines() {
+    let mut replica = setup();
+    let mut server = replica.clone();
+    server.approve_until(ALICE, BOB, u64::MAX).unwrap();
+
+    replica.apply_diff(&replica.diff(&server)).unwrap();
+    assert_eq!(replica.approval_expiry(ALICE, BOB), Some(u64::MAX));
+    assert_eq!(replica.state_hash(), server.state_hash());
+}
+
+#[test]
+fn diffs_carry_snapshot_history() {
+    let mut replica = setup();
+    let mut server = replica.clone();
+    let snapshot = server.take_snapshot(ADMIN).unwrap();
+    server.transfer(ALICE, BOB, 1, 40).unwrap();
+    assert_ne!(replica.state_hash(), server.state_hash());
+
+    let diff = replica.diff(&server);
+    assert_eq!(diff.changed_sections(), vec!["snapshots".to_string()]);
+    replica.apply_diff(&diff).unwrap();
+    assert_eq!(replica.state_hash(), server.state_hash());
+    assert_eq!(replica.balance_of_at(ALICE, 1, snapshot).unwrap(), 100);
+    assert_eq!(replica.balance_of_at(BOB, 1, snapshot).unwrap(), 0);
+    assert_eq!(replica.total_supply_at(1, snapshot).unwrap(), 100);
+}
+
+#[test]
+fn state_hash_does_not_change_with_the_clock() {
+    let mut contract = setup();
+    contract.set_time(OWNER, Some(0)).unwrap();
+    contract.approve_until(ALICE, BOB, 100).unwrap();
+    let (hash, replica) = (contract.state_hash(), contract.clone());
+    contract.set_time(OWNER, Some(200)).unwrap();
+    assert!(!contract.is_approved(ALICE, BOB));
+    assert_eq!(contract.state_hash(), hash);
+    assert!(contract.diff(&replica).is_empty());
+}
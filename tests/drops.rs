@@ -0,0 +1,108 @@
+mod common;
+
+use common::{ADMIN, ALICE, BOB, OWNER};
+use erc1155_wasm::{ErrorKind, EventKind, ERC1155};
+
+const SEED: &str = "0f1e2d3c4b5a69788796a5b4c3d2e1f00f1e2d3c4b5a6978";
+const PERIOD: u64 = 1_000;
+
+/// Contract at time zero with an open drop of a common token 1 and a rare token 2.
+fn setup() -> (ERC1155, u64) {
+    let mut contract = common::setup();
+    contract.set_time(OWNER, Some(0)).unwrap();
+    let commitment = contract.drop_commitment(SEED);
+    let drop_id = contract.create_drop(ADMIN, &commitment, vec![1, 2], vec![9, 1], PERIOD).unwrap();
+    (contract, drop_id)
+}
+
+#[test]
+fn drops_mint_each_registrant_a_committed_draw() {
+    let (mut contract, drop_id) = setup();
+    let registrants: Vec<String> = (0..40).map(|i| format!("user{}", i)).collect();
+    for registrant in &registrants {
+        contract.register_for_drop(registrant, drop_id).unwrap();
+    }
+    let err = contract.register_for_drop(&registrants[0], drop_id).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    let err = contract.reveal_drop(ADMIN, drop_id, SEED).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+
+    contract.set_time(OWNER, Some(PERIOD)).unwrap();
+    let err = contract.register_for_drop(ALICE, drop_id).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    let err = contract.reveal_drop(ALICE, drop_id, SEED).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Unauthorized);
+    let err = contract.reveal_drop(ADMIN, drop_id, "guess").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+
+    // The same seed and registrations always produce the same draws.
+    let mut replica = contract.fork();
+    let receipt = contract.reveal_drop(ADMIN, drop_id, SEED).unwrap();
+    replica.reveal_drop(ADMIN, drop_id, SEED).unwrap();
+    assert_eq!(receipt.balances.len(), registrants.len());
+    assert_eq!(receipt.events.last().unwrap().kind, EventKind::DropRevealed);
+    assert_eq!(contract.total_supply(1) + contract.total_supply(2), 40);
+    assert!(contract.total_supply(1) > contract.total_supply(2));
+    for registrant in &registrants {
+        let token_id = contract.drop_assignment(drop_id, registrant).unwrap();
+        assert_eq!(contract.balance_of(registrant, token_id), 1);
+        assert_eq!(replica.drop_assignment(drop_id, registrant), Some(token_id));
+    }
+    assert_eq!(contract.drop_assignment(drop_id, ALICE), None);
+    assert_eq!(contract.drop_info(drop_id).unwrap().seed.as_deref(), Some(SEED));
+    let err = contract.reveal_drop(ADMIN, drop_id, SEED).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+}
+
+#[test]
+fn drops_validate_their_table() {
+    let (mut contract, drop_id) = setup();
+    let info = contract.drop_info(drop_id).unwrap();
+    assert_eq!((info.token_ids, info.weights), (vec![1, 2], vec![9, 1]));
+    assert_eq!(info.commitment, contract.drop_commitment(SEED));
+    contract.register_for_drop(BOB, drop_id).unwrap();
+    assert!(contract.is_registered_for_drop(drop_id, BOB));
+    assert!(!contract.is_registered_for_drop(drop_id, ALICE));
+
+    let commitment = contract.drop_commitment(SEED);
+    let err = contract.create_drop(ALICE, &commitment, vec![1], vec![1], PERIOD).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Unauthorized);
+    for (token_ids, weights) in [(vec![], vec![]), (vec![1, 1], vec![1, 1]), (vec![1], vec![0])] {
+        let err = contract.create_drop(ADMIN, &commitment, token_ids, weights, PERIOD).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    }
+    let err = contract.create_drop(ADMIN, "not a hash", vec![1], vec![1], PERIOD).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    assert_eq!(contract.drop_count(), 1);
+}
+
+#[test]
+fn drops_only_draw_tokens_every_registrant_can_receive() {
+    let (mut contract, drop_id) = setup();
+    let commitment = contract.drop_commitment(SEED);
+    let nft_type = contract.create_token_type(ADMIN, false).unwrap();
+    let err = contract.create_drop(ADMIN, &commitment, vec![1, nft_type + 1], vec![1, 1], PERIOD).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    contract.mint(ADMIN, 3, 1).unwrap();
+    contract.set_max_supply(ADMIN, 3, Some(10)).unwrap();
+    let err = contract.create_drop(ADMIN, &commitment, vec![3], vec![1], PERIOD).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+
+    // A drop that can no longer be revealed is cancelled instead.
+    contract.register_for_drop(ALICE, drop_id).unwrap();
+    contract.mint(ADMIN, 2, 1).unwrap();
+    contract.set_max_supply(ADMIN, 2, Some(1)).unwrap();
+    let err = contract.cancel_drop(BOB, drop_id).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Unauthorized);
+    let receipt = contract.cancel_drop(ADMIN, drop_id).unwrap();
+    assert_eq!(receipt.events.last().unwrap().kind, EventKind::DropCancelled);
+    assert!(contract.drop_info(drop_id).unwrap().cancelled);
+    let err = contract.register_for_drop(BOB, drop_id).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    contract.set_time(OWNER, Some(PERIOD)).unwrap();
+    let err = contract.reveal_drop(ADMIN, drop_id, SEED).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    let err = contract.cancel_drop(ADMIN, drop_id).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    assert_eq!(contract.balance_of(ALICE, 1) + contract.balance_of(ALICE, 2), 0);
+}
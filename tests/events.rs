@@ -0,0 +1,70 @@
+mod common;
+
+use common::{ADMIN, ALICE, BOB};
+use erc1155_wasm::{EventFilter, EventKind, ERC1155};
+
+/// Contract where `ADMIN` minted tokens 1 and 2 and sent some of each to `ALICE`,
+/// who passed part of token 1 on to `BOB`.
+fn setup() -> ERC1155 {
+    let mut contract = common::setup();
+    contract.mint(ADMIN, 1, 100).unwrap();
+    contract.mint(ADMIN, 2, 100).unwrap();
+    contract.transfer(ADMIN, ALICE, 1, 50).unwrap();
+    contract.transfer(ADMIN, ALICE, 2, 50).unwrap();
+    contract.transfer(ALICE, BOB, 1, 10).unwrap();
+    contract
+}
+
+fn seqs(contract: &ERC1155, filter: &EventFilter) -> Vec<u64> {
+    contract.query_events(filter).iter().map(|event| event.seq).collect()
+}
+
+#[test]
+fn criteria_are_combined() {
+    let contract = setup();
+    assert_eq!(seqs(&contract, &EventFilter::new()), vec![0, 1, 2, 3, 4, 5]);
+
+    let mut filter = EventFilter::new();
+    filter.account = Some(ALICE.to_string());
+    assert_eq!(seqs(&contract, &filter), vec![3, 4, 5]);
+    filter.token_id = Some(1);
+    assert_eq!(seqs(&contract, &filter), vec![3, 5]);
+    filter.kind = Some(EventKind::AdminAdded);
+    assert!(contract.query_events(&filter).is_empty());
+
+    let mut filter = EventFilter::new();
+    filter.kind = Some(EventKind::TransferSingle);
+    filter.token_id = Some(2);
+    let events = contract.query_events(&filter);
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[1].to.as_deref(), Some(ALICE));
+}
+
+#[test]
+fn ranges_and_limits_page_through_results() {
+    let contract = setup();
+    let mut filter = EventFilter::new();
+    filter.token_id = Some(1);
+    filter.from_seq = Some(2);
+    assert_eq!(seqs(&contract, &filter), vec![3, 5]);
+    filter.to_seq = Some(4);
+    assert_eq!(seqs(&contract, &filter), vec![3]);
+
+    let mut filter = EventFilter::new();
+    filter.account = Some(ADMIN.to_string());
+    filter.limit = Some(2);
+    assert_eq!(seqs(&contract, &filter), vec![0, 1]);
+    filter.from_seq = Some(2);
+    assert_eq!(seqs(&contract, &filter), vec![2, 3]);
+}
+
+#[test]
+fn unknown_keys_match_nothing() {
+    let contract = setup();
+    let mut filter = EventFilter::new();
+    filter.account = Some("nobody".to_string());
+    assert!(contract.query_events(&filter).is_empty());
+    let mut filter = EventFilter::new();
+    filter.token_id = Some(9);
+    assert!(contract.query_events(&filter).is_empty());
+}
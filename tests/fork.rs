@@ -0,0 +1,71 @@
+mod common;
+
+use common::{ADMIN, ALICE, BOB};
+use erc1155_wasm::{EventFilter, OperationFilter, ERC1155};
+
+fn setup() -> ERC1155 {
+    let mut contract = common::setup();
+    contract.mint(ADMIN, 1, 100).unwrap();
+    contract.transfer(ADMIN, ALICE, 1, 40).unwrap();
+    contract.set_attribute(ADMIN, 1, "rarity", "common").unwrap();
+    contract
+}
+
+#[test]
+fn fork_and_parent_diverge_independently() {
+    let mut parent = setup();
+    let mut child = parent.fork();
+
+    child.transfer(ALICE, BOB, 1, 10).unwrap();
+    child.approve(BOB, ALICE, 1).unwrap();
+    child.set_attribute(ADMIN, 1, "rarity", "rare").unwrap();
+    parent.mint(ADMIN, 2, 5).unwrap();
+
+    assert_eq!(parent.balance_of(ALICE, 1), 40);
+    assert_eq!(parent.balance_of(BOB, 1), 0);
+    assert!(!parent.is_approved(BOB, ALICE));
+    assert_eq!(parent.attribute(1, "rarity").as_deref(), Some("common"));
+    assert_eq!(parent.holder_count(1), 2);
+
+    assert_eq!(child.balance_of(ALICE, 1), 30);
+    assert_eq!(child.balance_of(BOB, 1), 10);
+    assert!(child.is_approved(BOB, ALICE));
+    assert_eq!(child.attribute(1, "rarity").as_deref(), Some("rare"));
+    assert_eq!(child.holder_count(1), 3);
+    assert_eq!(child.total_supply(2), 0);
+
+    // Both logs continue from the shared prefix with their own events.
+    assert_eq!(parent.event_count(), 5);
+    assert_eq!(child.event_count(), 7);
+    let mut filter = EventFilter::new();
+    filter.account = Some(BOB.to_string());
+    assert!(parent.query_events(&filter).is_empty());
+    assert_eq!(child.query_events(&filter).len(), 2);
+}
+
+#[test]
+fn forks_share_logs_longer_than_a_chunk() {
+    let mut parent = setup();
+    for _ in 0..1500 {
+        parent.approve(ALICE, BOB, 1).unwrap();
+    }
+    let mut child = parent.fork();
+    child.transfer(ALICE, BOB, 1, 1).unwrap();
+    parent.approve(BOB, ALICE, 1).unwrap();
+
+    let count = parent.event_count();
+    assert_eq!(child.event_count(), count);
+    let last = count - 1;
+    assert_eq!(parent.get_events(last, 10)[0].from.as_deref(), Some(BOB));
+    assert_eq!(child.get_events(last, 10)[0].from.as_deref(), Some(ALICE));
+    assert_eq!(parent.get_events(1024, 1), child.get_events(1024, 1));
+
+    let mut filter = OperationFilter::new();
+    filter.from_id = Some(parent.operation_count() - 1);
+    let parent_ops = parent.get_operations(&filter);
+    let child_ops = child.get_operations(&filter);
+    assert_eq!(parent_ops.len(), 1);
+    assert_eq!(parent_ops[0].caller, BOB);
+    assert_eq!(child_ops[0].caller, ALICE);
+    assert_eq!(child.operation_events(child_ops[0].id)[0].amount, Some(1));
+}
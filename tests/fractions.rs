@@ -0,0 +1,94 @@
+mod common;
+
+use common::{ADMIN, ALICE, BOB, OWNER};
+use erc1155_wasm::{ErrorKind, Fraction, ERC1155};
+
+const ESCROW: &str = "erc1155:escrow";
+const SHARES: u32 = 500;
+
+/// Contract where ALICE holds one NFT; returns its ID.
+fn setup() -> (ERC1155, u32) {
+    let mut contract = common::setup();
+    let nft = contract.create_token_type(ADMIN, false).unwrap() + 1;
+    contract.mint(ADMIN, nft, 1).unwrap();
+    contract.transfer_from(ADMIN, ADMIN, ALICE, nft, 1).unwrap();
+    (contract, nft)
+}
+
+#[test]
+fn fractionalize_escrows_nft_and_mints_shares() {
+    let (mut contract, nft) = setup();
+    let err = contract.fractionalize(BOB, nft, SHARES, 100).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InsufficientBalance);
+    let err = contract.fractionalize(ALICE, 7, SHARES, 100).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    let err = contract.fractionalize(ALICE, nft, SHARES, 0).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    contract.mint(ADMIN, 9, 1).unwrap();
+    let err = contract.fractionalize(ALICE, nft, 9, 100).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+
+    let receipt = contract.fractionalize(ALICE, nft, SHARES, 100).unwrap();
+    assert_eq!(receipt.balances.len(), 3);
+    assert_eq!(contract.balance_of(ALICE, nft), 0);
+    assert_eq!(contract.balance_of(ESCROW, nft), 1);
+    assert_eq!(contract.total_supply(nft), 1);
+    assert_eq!(contract.balance_of(ALICE, SHARES), 100);
+    assert_eq!(
+        contract.fraction_of(nft),
+        Some(Fraction {
+            share_token_id: SHARES,
+            total_shares: 100,
+        })
+    );
+
+    // The supply of shares is fixed, even for admins.
+    let err = contract.mint(ADMIN, SHARES, 1).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    // Nobody can act as the escrow account.
+    let err = contract.transfer_from(ESCROW, ESCROW, BOB, nft, 1).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+}
+
+#[test]
+fn redeem_requires_every_share() {
+    let (mut contract, nft) = setup();
+    contract.fractionalize(ALICE, nft, SHARES, 100).unwrap();
+    contract.transfer_from(ALICE, ALICE, BOB, SHARES, 60).unwrap();
+
+    let err = contract.redeem(BOB, nft).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InsufficientBalance);
+    let err = contract.redeem(BOB, nft + 1).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+
+    contract.transfer_from(ALICE, ALICE, BOB, SHARES, 40).unwrap();
+    contract.redeem(BOB, nft).unwrap();
+    assert_eq!(contract.balance_of(BOB, nft), 1);
+    assert_eq!(contract.balance_of(ESCROW, nft), 0);
+    assert_eq!(contract.total_supply(SHARES), 0);
+    assert_eq!(contract.fraction_of(nft), None);
+
+    // The NFT can be fractionalized again, under a fresh share token.
+    let err = contract.fractionalize(BOB, nft, SHARES, 10).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    contract.fractionalize(BOB, nft, SHARES + 1, 10).unwrap();
+}
+
+#[test]
+fn redeeming_an_expired_nft_burns_it_with_the_shares() {
+    let (mut contract, nft) = setup();
+    contract.set_time(OWNER, Some(1_000)).unwrap();
+    contract.fractionalize(ALICE, nft, SHARES, 100).unwrap();
+    contract.set_token_expiry(ADMIN, nft, Some(2_000)).unwrap();
+    contract.set_time(OWNER, Some(3_000)).unwrap();
+
+    // Sweeping leaves the escrowed NFT to the fraction.
+    assert!(contract.sweep_expired(BOB, nft, 10).unwrap().balances.is_empty());
+    assert_eq!(contract.total_supply(nft), 1);
+
+    contract.redeem(ALICE, nft).unwrap();
+    assert_eq!(contract.total_supply(nft), 0);
+    assert_eq!(contract.total_supply(SHARES), 0);
+    assert_eq!(contract.balance_of(ESCROW, nft), 0);
+    assert_eq!(contract.fraction_of(nft), None);
+}
@@ -0,0 +1,89 @@
+mod common;
+
+use common::{setup, ADMIN, ALICE, BOB, OWNER};
+use erc1155_wasm::{ErrorKind, EventKind, GasSchedule, ERC1155};
+
+fn schedule() -> GasSchedule {
+    GasSchedule {
+        base: 1_000,
+        read: 10,
+        write: 100,
+        event: 50,
+        iteration: 1,
+    }
+}
+
+fn metered() -> ERC1155 {
+    let mut contract = setup();
+    contract.mint(ADMIN, 1, 100).unwrap();
+    contract.transfer(ADMIN, ALICE, 1, 100).unwrap();
+    contract.set_gas_schedule(OWNER, Some(schedule())).unwrap();
+    contract
+}
+
+#[test]
+fn gas_is_free_until_a_schedule_is_set() {
+    let mut contract = setup();
+    assert_eq!(contract.mint(ADMIN, 1, 1).unwrap().gas_used, 0);
+    assert_eq!(contract.gas_used(), 0);
+}
+
+#[test]
+fn receipts_report_gas_used() {
+    let mut contract = metered();
+    let transfer = contract.transfer(ALICE, BOB, 1, 10).unwrap();
+    assert_eq!(transfer.gas_used, 1_000 + 3 * 10 + 2 * 100 + 50);
+    assert_eq!(contract.gas_used(), transfer.gas_used);
+
+    let batch = contract.transfer_batch(ALICE, BOB, vec![1, 1], vec![1, 1]).unwrap();
+    assert_eq!(batch[1].gas_used, 1_000 + 5 * 10 + 4 * 100 + 2 * 50 + 2);
+
+    let approval = contract.approve(ALICE, BOB, 1).unwrap();
+    assert_eq!(approval.gas_used, 1_000 + 100 + 50);
+}
+
+#[test]
+fn out_of_gas_rejects_without_changing_state() {
+    let mut contract = metered();
+    contract.set_gas_limit(OWNER, Some(1_500)).unwrap();
+    contract.transfer(ALICE, BOB, 1, 1).unwrap();
+
+    let err = contract.transfer_batch(ALICE, BOB, vec![1, 1], vec![1, 1]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::OutOfGas);
+    assert_eq!(contract.balance_of(ALICE, 1), 99);
+    assert_eq!(contract.gas_used(), 1_000);
+
+    contract.set_gas_limit(OWNER, Some(999)).unwrap();
+    assert_eq!(contract.transfer(ALICE, BOB, 1, 1).unwrap_err().kind(), ErrorKind::OutOfGas);
+    // Configuration calls are not metered, so the limit can always be lifted.
+    contract.set_gas_limit(OWNER, None).unwrap();
+    contract.transfer(ALICE, BOB, 1, 1).unwrap();
+}
+
+#[test]
+fn only_the_owner_configures_gas() {
+    let mut contract = metered();
+    assert_eq!(contract.set_gas_limit(ALICE, Some(1)).unwrap_err().kind(), ErrorKind::Unauthorized);
+    assert_eq!(contract.set_gas_schedule(ALICE, None).unwrap_err().kind(), ErrorKind::Unauthorized);
+
+    contract.set_gas_schedule(OWNER, None).unwrap();
+    assert_eq!(contract.gas_schedule(), None);
+    assert_eq!(contract.transfer(ALICE, BOB, 1, 1).unwrap().gas_used, 0);
+}
+
+#[test]
+fn gas_configuration_is_announced() {
+    let mut contract = ERC1155::new(OWNER).unwrap();
+    let receipt = contract.set_gas_schedule(OWNER, Some(schedule())).unwrap();
+    assert_eq!(receipt.method, "set_gas_schedule");
+    assert_eq!(receipt.events[0].kind, EventKind::GasScheduleUpdated);
+    let announced: GasSchedule = serde_json::from_str(receipt.events[0].value.as_deref().unwrap()).unwrap();
+    assert_eq!(announced, schedule());
+
+    let receipt = contract.set_gas_limit(OWNER, Some(5_000)).unwrap();
+    assert_eq!(receipt.events[0].kind, EventKind::GasLimitUpdated);
+    assert_eq!(receipt.events[0].amount, Some(5_000));
+    let receipt = contract.set_gas_limit(OWNER, None).unwrap();
+    assert_eq!(receipt.events[0].amount, None);
+    assert_eq!(contract.operation_count(), 3);
+}
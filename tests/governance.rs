@@ -0,0 +1,120 @@
+mod common;
+
+use common::{ADMIN, ALICE, BOB, CAROL, OWNER};
+use erc1155_wasm::{ErrorKind, ERC1155};
+
+const VOTES: u32 = 1;
+const PERIOD: u64 = 1_000;
+
+/// Contract at time zero where ALICE holds 60 and BOB 40 voting tokens.
+fn setup() -> ERC1155 {
+    let mut contract = common::setup();
+    contract.set_time(OWNER, Some(0)).unwrap();
+    contract.mint(ADMIN, VOTES, 100).unwrap();
+    contract.transfer_from(ADMIN, ADMIN, ALICE, VOTES, 60).unwrap();
+    contract.transfer_from(ADMIN, ADMIN, BOB, VOTES, 40).unwrap();
+    contract
+}
+
+#[test]
+fn snapshots_freeze_balances() {
+    let mut contract = setup();
+    let err = contract.balance_of_at(ALICE, VOTES, 1).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    let err = contract.take_snapshot(ALICE).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Unauthorized);
+
+    let first = contract.take_snapshot(ADMIN).unwrap();
+    contract.transfer_from(ALICE, ALICE, CAROL, VOTES, 10).unwrap();
+    contract.transfer_from(ALICE, ALICE, CAROL, VOTES, 10).unwrap();
+    let second = contract.take_snapshot(OWNER).unwrap();
+    contract.mint(ADMIN, VOTES, 5).unwrap();
+    assert_eq!((first, second, contract.last_snapshot_id()), (1, 2, 2));
+
+    assert_eq!(contract.balance_of_at(ALICE, VOTES, first).unwrap(), 60);
+    assert_eq!(contract.balance_of_at(CAROL, VOTES, first).unwrap(), 0);
+    assert_eq!(contract.balance_of_at(ALICE, VOTES, second).unwrap(), 40);
+    assert_eq!(contract.balance_of_at(CAROL, VOTES, second).unwrap(), 20);
+    assert_eq!(contract.balance_of_at(ADMIN, VOTES, second).unwrap(), 0);
+    assert_eq!(contract.balance_of(ADMIN, VOTES), 5);
+    assert_eq!(contract.total_supply_at(VOTES, first).unwrap(), 100);
+    assert_eq!(contract.total_supply_at(VOTES, second).unwrap(), 100);
+    assert_eq!(contract.total_supply(VOTES), 105);
+}
+
+#[test]
+fn balance_changes_compare_two_snapshots() {
+    let mut contract = setup();
+    let first = contract.take_snapshot(ADMIN).unwrap();
+    contract.transfer_from(ALICE, ALICE, CAROL, VOTES, 10).unwrap();
+    contract.transfer_from(BOB, BOB, CAROL, VOTES, 5).unwrap();
+    contract.transfer_from(BOB, BOB, ALICE, VOTES, 5).unwrap();
+    let second = contract.take_snapshot(ADMIN).unwrap();
+    contract.transfer_from(CAROL, CAROL, ALICE, VOTES, 15).unwrap();
+    let third = contract.take_snapshot(ADMIN).unwrap();
+
+    let changes = contract.balance_changes(first, second, VOTES, 0, 10).unwrap();
+    let summary: Vec<(&str, u64, u64)> = changes
+        .iter()
+        .map(|change| (change.owner.as_str(), change.before, change.after))
+        .collect();
+    assert_eq!(summary, vec![(ALICE, 60, 55), (BOB, 40, 30), (CAROL, 0, 15)]);
+
+    // CAROL's balance ends where it started, so it is left out.
+    let changes = contract.balance_changes(first, third, VOTES, 0, 10).unwrap();
+    let owners: Vec<&str> = changes.iter().map(|change| change.owner.as_str()).collect();
+    assert_eq!(owners, vec![ALICE, BOB]);
+    assert_eq!((changes[0].before, changes[0].after), (60, 70));
+
+    let page = contract.balance_changes(first, second, VOTES, 1, 1).unwrap();
+    assert_eq!(page[0].owner, BOB);
+    assert!(contract.balance_changes(first, second, 9, 0, 10).unwrap().is_empty());
+    let err = contract.balance_changes(first, third + 1, VOTES, 0, 10).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+}
+
+#[test]
+fn votes_are_weighted_by_snapshot_balance() {
+    let mut contract = setup();
+    let snapshot = contract.take_snapshot(OWNER).unwrap();
+    let err = contract.create_proposal(CAROL, "Raise fees", VOTES, snapshot, 50, PERIOD).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Unauthorized);
+    let err = contract.create_proposal(ALICE, "Raise fees", VOTES, snapshot + 1, 50, PERIOD).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    let id = contract.create_proposal(ALICE, "Raise fees", VOTES, snapshot, 50, PERIOD).unwrap();
+
+    // Tokens moved after the snapshot carry no extra weight.
+    contract.transfer_from(ALICE, ALICE, CAROL, VOTES, 60).unwrap();
+    let err = contract.vote(CAROL, id, true).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Unauthorized);
+    contract.vote(BOB, id, false).unwrap();
+    assert!(!contract.quorum_reached(id).unwrap());
+    contract.vote(ALICE, id, true).unwrap();
+    let err = contract.vote(ALICE, id, false).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+
+    let proposal = contract.proposal(id).unwrap();
+    assert_eq!((proposal.votes_for, proposal.votes_against, proposal.voters), (60, 40, 2));
+    assert_eq!(contract.vote_of(id, ALICE), Some(true));
+    assert_eq!(contract.vote_of(id, CAROL), None);
+    assert!(contract.quorum_reached(id).unwrap());
+    assert!(!contract.proposal_passed(id).unwrap());
+
+    contract.set_time(OWNER, Some(PERIOD)).unwrap();
+    assert!(contract.proposal_passed(id).unwrap());
+    let err = contract.vote(ADMIN, id, false).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+}
+
+#[test]
+fn proposals_fail_without_quorum() {
+    let mut contract = setup();
+    let snapshot = contract.take_snapshot(OWNER).unwrap();
+    let id = contract.create_proposal(ADMIN, "Burn treasury", VOTES, snapshot, 70, PERIOD).unwrap();
+    contract.vote(ALICE, id, true).unwrap();
+    contract.set_time(OWNER, Some(PERIOD)).unwrap();
+    assert!(!contract.proposal_passed(id).unwrap());
+    assert_eq!(contract.proposal_count(), 1);
+    assert!(contract.proposal(2).is_none());
+    assert_eq!(contract.quorum_reached(2).unwrap_err().kind(), ErrorKind::InvalidInput);
+}
@@ -0,0 +1,94 @@
+mod common;
+
+use common::OWNER;
+use erc1155_wasm::{ErrorKind, ERC1155};
+
+const NEW_OWNER: &str = "new-owner";
+const GUARDIANS: [&str; 3] = ["g1", "g2", "g3"];
+const DELAY: u64 = 1_000;
+
+/// Contract at time zero with three guardians, two of which must agree.
+fn setup() -> ERC1155 {
+    let mut contract = ERC1155::new(OWNER).unwrap();
+    contract.set_time(OWNER, Some(0)).unwrap();
+    let guardians = GUARDIANS.iter().map(|guardian| guardian.to_string()).collect();
+    contract.set_guardians(OWNER, guardians, 2, DELAY).unwrap();
+    contract
+}
+
+#[test]
+fn guardian_configuration_is_validated() {
+    let mut contract = ERC1155::new(OWNER).unwrap();
+    let err = contract.set_guardians("g1", vec!["g2".into()], 1, 0).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Unauthorized);
+    for (guardians, threshold) in [(vec!["g1"], 2), (vec!["g1"], 0), (vec![OWNER], 1), (vec![""], 1)] {
+        let guardians = guardians.into_iter().map(String::from).collect();
+        let err = contract.set_guardians(OWNER, guardians, threshold, 0).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    }
+
+    contract.set_guardians(OWNER, vec!["g2".into(), "g1".into(), "g1".into()], 2, 0).unwrap();
+    assert_eq!(contract.guardians(), vec!["g1".to_string(), "g2".to_string()]);
+    assert_eq!(contract.guardian_threshold(), 2);
+}
+
+#[test]
+fn guardians_may_be_given_by_name() {
+    let mut contract = ERC1155::new(OWNER).unwrap();
+    contract.register_name("g1", "alice").unwrap();
+    let err = contract.set_guardians(OWNER, vec!["@bob".into()], 1, 0).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    contract.register_name(OWNER, "owner-name").unwrap();
+    let err = contract.set_guardians(OWNER, vec!["@Owner-Name".into()], 1, 0).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+
+    contract.set_guardians(OWNER, vec!["@Alice".into(), "g2".into()], 1, 0).unwrap();
+    assert_eq!(contract.guardians(), vec!["g1".to_string(), "g2".to_string()]);
+    contract.support_recovery("g1", NEW_OWNER).unwrap();
+}
+
+#[test]
+fn quorum_recovers_ownership_after_the_delay() {
+    let mut contract = setup();
+    contract.support_recovery(GUARDIANS[0], NEW_OWNER).unwrap();
+    let status = contract.recovery_status().unwrap();
+    assert_eq!((status.supporters, status.executable_at), (1, None));
+    assert_eq!(contract.execute_recovery(NEW_OWNER).unwrap_err().kind(), ErrorKind::InvalidInput);
+
+    contract.support_recovery(GUARDIANS[1], NEW_OWNER).unwrap();
+    assert_eq!(contract.recovery_status().unwrap().executable_at, Some(DELAY));
+    contract.set_time(OWNER, Some(DELAY - 1)).unwrap();
+    assert_eq!(contract.execute_recovery(NEW_OWNER).unwrap_err().kind(), ErrorKind::InvalidInput);
+
+    contract.set_time(OWNER, Some(DELAY)).unwrap();
+    contract.execute_recovery(NEW_OWNER).unwrap();
+    assert_eq!(contract.add_admin(OWNER, "admin").unwrap_err().kind(), ErrorKind::Unauthorized);
+    contract.add_admin(NEW_OWNER, "admin").unwrap();
+    assert!(contract.recovery_status().is_none());
+}
+
+#[test]
+fn owner_can_cancel_a_recovery() {
+    let mut contract = setup();
+    contract.support_recovery(GUARDIANS[0], NEW_OWNER).unwrap();
+    contract.support_recovery(GUARDIANS[1], NEW_OWNER).unwrap();
+    assert_eq!(contract.cancel_recovery(GUARDIANS[0]).unwrap_err().kind(), ErrorKind::Unauthorized);
+
+    contract.cancel_recovery(OWNER).unwrap();
+    assert!(contract.recovery_status().is_none());
+    contract.set_time(OWNER, Some(DELAY)).unwrap();
+    assert_eq!(contract.execute_recovery(NEW_OWNER).unwrap_err().kind(), ErrorKind::InvalidInput);
+}
+
+#[test]
+fn only_guardians_back_a_single_candidate() {
+    let mut contract = setup();
+    let err = contract.support_recovery("mallory", "mallory").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Unauthorized);
+    assert_eq!(contract.support_recovery(GUARDIANS[0], OWNER).unwrap_err().kind(), ErrorKind::InvalidInput);
+
+    contract.support_recovery(GUARDIANS[0], NEW_OWNER).unwrap();
+    contract.support_recovery(GUARDIANS[1], NEW_OWNER).unwrap();
+    let err = contract.support_recovery(GUARDIANS[2], "mallory").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+}
@@ -0,0 +1,135 @@
+#![cfg(not(target_arch = "wasm32"))]
+
+mod common;
+
+use common::OWNER;
+use erc1155_wasm::ERC1155;
+use proptest::prelude::*;
+
+const ACCOUNTS: [&str; 4] = ["a0", "a1", "a2", "a3"];
+const TOKENS: [u32; 3] = [0, 1, 2];
+
+#[derive(Debug, Clone)]
+enum Op {
+    Mint { admin: usize, token: usize, amount: u64 },
+    Transfer { from: usize, to: usize, token: usize, amount: u64 },
+    TransferFrom { operator: usize, from: usize, to: usize, token: usize, amount: u64 },
+    TransferBatch { from: usize, to: usize, tokens: Vec<usize>, amounts: Vec<u64> },
+    Approve { owner: usize, operator: usize },
+}
+
+fn account() -> impl Strategy<Value = usize> {
+    0..ACCOUNTS.len()
+}
+
+fn token() -> impl Strategy<Value = usize> {
+    0..TOKENS.len()
+}
+
+fn amount() -> impl Strategy<Value = u64> {
+    prop_oneof![0..1_000u64, Just(u64::MAX), (u64::MAX - 1_000)..=u64::MAX]
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        (account(), token(), amount()).prop_map(|(admin, token, amount)| Op::Mint { admin, token, amount }),
+        (account(), account(), token(), amount()).prop_map(|(from, to, token, amount)| Op::Transfer {
+            from,
+            to,
+            token,
+            amount
+        }),
+        (account(), account(), account(), token(), amount()).prop_map(|(operator, from, to, token, amount)| {
+            Op::TransferFrom {
+                operator,
+                from,
+                to,
+                token,
+                amount,
+            }
+        }),
+        (account(), account(), prop::collection::vec((token(), amount()), 0..4)).prop_map(|(from, to, items)| {
+            let (tokens, amounts) = items.into_iter().unzip();
+            Op::TransferBatch {
+                from,
+                to,
+                tokens,
+                amounts,
+            }
+        }),
+        (account(), account()).prop_map(|(owner, operator)| Op::Approve { owner, operator }),
+    ]
+}
+
+/// Every `(account, token)` balance, in a fixed order.
+fn balances(contract: &ERC1155) -> Vec<u64> {
+    ACCOUNTS
+        .iter()
+        .flat_map(|account| TOKENS.iter().map(move |token| contract.balance_of(account, *token)))
+        .collect()
+}
+
+fn total_supply(contract: &ERC1155, token: u32) -> u128 {
+    ACCOUNTS.iter().map(|account| contract.balance_of(account, token) as u128).sum()
+}
+
+proptest! {
+    #[test]
+    fn supply_is_conserved_and_failures_are_atomic(ops in prop::collection::vec(op(), 1..64)) {
+        let mut contract = ERC1155::new(OWNER).unwrap();
+        for admin in &ACCOUNTS[..2] {
+            contract.add_admin(OWNER, admin).unwrap();
+        }
+        let mut minted = [0u128; TOKENS.len()];
+
+        for op in ops {
+            let before = balances(&contract);
+            let result = match &op {
+                Op::Mint { admin, token, amount } => {
+                    let result = contract.mint(ACCOUNTS[*admin], TOKENS[*token], *amount).map(|_| ());
+                    if result.is_ok() {
+                        minted[*token] += *amount as u128;
+                    }
+                    result
+                }
+                Op::Transfer { from, to, token, amount } => contract
+                    .transfer(ACCOUNTS[*from], ACCOUNTS[*to], TOKENS[*token], *amount)
+                    .map(|_| ()),
+                Op::TransferFrom { operator, from, to, token, amount } => contract
+                    .transfer_from(ACCOUNTS[*operator], ACCOUNTS[*from], ACCOUNTS[*to], TOKENS[*token], *amount)
+                    .map(|_| ()),
+                Op::TransferBatch { from, to, tokens, amounts } => contract
+                    .transfer_batch(
+                        ACCOUNTS[*from],
+                        ACCOUNTS[*to],
+                        tokens.iter().map(|token| TOKENS[*token]).collect(),
+                        amounts.clone(),
+                    )
+                    .map(|_| ()),
+                Op::Approve { owner, operator } => contract.approve(ACCOUNTS[*owner], ACCOUNTS[*operator], 0).map(|_| ()),
+            };
+
+            if result.is_err() {
+                prop_assert_eq!(&before, &balances(&contract), "failed {:?} mutated state", op);
+            }
+            for (index, token) in TOKENS.iter().enumerate() {
+                prop_assert_eq!(total_supply(&contract, *token), minted[index]);
+            }
+        }
+    }
+
+    #[test]
+    fn unapproved_operators_cannot_move_tokens(
+        amount in 1..1_000u64,
+        operator in 1..ACCOUNTS.len(),
+    ) {
+        let mut contract = ERC1155::new(OWNER).unwrap();
+        contract.add_admin(OWNER, ACCOUNTS[0]).unwrap();
+        contract.mint(ACCOUNTS[0], 1, amount).unwrap();
+
+        prop_assert!(contract.transfer_from(ACCOUNTS[operator], ACCOUNTS[0], ACCOUNTS[operator], 1, amount).is_err());
+        contract.approve(ACCOUNTS[0], ACCOUNTS[operator], 1).unwrap();
+        prop_assert!(contract.transfer_from(ACCOUNTS[operator], ACCOUNTS[0], ACCOUNTS[operator], 1, amount).is_ok());
+        prop_assert_eq!(contract.balance_of(ACCOUNTS[operator], 1), amount);
+    }
+}
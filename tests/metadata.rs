@@ -0,0 +1,151 @@
+mod common;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use common::OWNER;
+use erc1155_wasm::{ErrorKind, ERC1155};
+use serde_json::Value;
+
+const CREATOR: &str = "creator";
+const OTHER: &str = "other";
+const TOKEN: u32 = 7;
+
+/// Contract where CREATOR created `TOKEN`.
+fn setup() -> ERC1155 {
+    let mut contract = ERC1155::new(OWNER).unwrap();
+    contract.set_creator_role(OWNER, CREATOR, true).unwrap();
+    contract.mint(CREATOR, TOKEN, 1).unwrap();
+    contract
+}
+
+fn decode(data_uri: &str, prefix: &str) -> String {
+    let encoded = data_uri.strip_prefix(prefix).expect("unexpected data URI prefix");
+    String::from_utf8(STANDARD.decode(encoded).unwrap()).unwrap()
+}
+
+#[test]
+fn attributes_are_managed_by_the_creator() {
+    let mut contract = setup();
+    let err = contract.set_attribute(OTHER, TOKEN, "rarity", "rare").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Unauthorized);
+    let err = contract.set_attribute(CREATOR, TOKEN, " ", "rare").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+
+    contract.set_attribute(CREATOR, TOKEN, "rarity", "rare").unwrap();
+    contract.set_attribute(CREATOR, TOKEN, "level", "3").unwrap();
+    assert_eq!(contract.attribute(TOKEN, "rarity").as_deref(), Some("rare"));
+    assert_eq!(contract.attribute_keys(TOKEN), vec!["level", "rarity"]);
+
+    contract.remove_attribute(CREATOR, TOKEN, "level").unwrap();
+    assert_eq!(contract.attribute(TOKEN, "level"), None);
+    let err = contract.remove_attribute(CREATOR, TOKEN, "level").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+
+    contract.freeze_metadata(CREATOR, TOKEN).unwrap();
+    let err = contract.set_attribute(CREATOR, TOKEN, "rarity", "common").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    let err = contract.set_generative_metadata(CREATOR, TOKEN, true).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+}
+
+#[test]
+fn generative_uri_embeds_json_and_svg() {
+    let mut contract = setup();
+    contract.set_token_uri(CREATOR, TOKEN, "ipfs://static").unwrap();
+    contract.register_token(CREATOR, TOKEN, "Sword & Shield", "SWD", 0).unwrap();
+    contract.set_attribute(CREATOR, TOKEN, "color", "<red>").unwrap();
+    let err = contract.set_svg_template(CREATOR, Some("<svg/>".into())).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Unauthorized);
+    let template = "<svg><style>.a{fill:blue}</style><text>{name} #{token_id} {color} {missing}</text></svg>";
+    contract.set_svg_template(OWNER, Some(template.into())).unwrap();
+
+    assert_eq!(contract.uri(TOKEN), "ipfs://static");
+    contract.set_generative_metadata(CREATOR, TOKEN, true).unwrap();
+    assert!(contract.is_generative_metadata(TOKEN));
+    let json = decode(&contract.uri(TOKEN), "data:application/json;base64,");
+    assert_eq!(json, contract.metadata_json(TOKEN));
+
+    let document: Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(document["name"], "Sword & Shield");
+    assert_eq!(document["decimals"], 0);
+    assert_eq!(document["attributes"][0]["trait_type"], "color");
+    assert_eq!(document["attributes"][0]["value"], "<red>");
+    let svg = decode(document["image"].as_str().unwrap(), "data:image/svg+xml;base64,");
+    assert_eq!(
+        svg,
+        "<svg><style>.a{fill:blue}</style><text>Sword &amp; Shield #7 &lt;red&gt; {missing}</text></svg>"
+    );
+
+    contract.set_svg_template(OWNER, None).unwrap();
+    let document: Value = serde_json::from_str(&contract.metadata_json(TOKEN)).unwrap();
+    assert!(document.get("image").is_none());
+    contract.set_generative_metadata(CREATOR, TOKEN, false).unwrap();
+    assert_eq!(contract.uri(TOKEN), "ipfs://static");
+}
+
+#[test]
+fn base_uri_expands_id_as_hex() {
+    let mut contract = setup();
+    let err = contract.set_base_uri(CREATOR, Some("x".into())).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Unauthorized);
+    assert_eq!(contract.uri(TOKEN), "");
+
+    contract.set_base_uri(OWNER, Some("https://example.com/{id}.json".into())).unwrap();
+    assert_eq!(contract.base_uri().as_deref(), Some("https://example.com/{id}.json"));
+    assert_eq!(
+        contract.uri(0x1a2b),
+        "https://example.com/0000000000000000000000000000000000000000000000000000000000001a2b.json"
+    );
+
+    contract.set_token_uri(CREATOR, TOKEN, "ipfs://dir/{id}").unwrap();
+    assert_eq!(contract.uri(TOKEN), format!("ipfs://dir/{}7", "0".repeat(63)));
+
+    contract.freeze_all_metadata(OWNER).unwrap();
+    let err = contract.set_base_uri(OWNER, None).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+}
+
+#[test]
+fn frozen_tokens_keep_the_collection_settings_their_uri_uses() {
+    let mut contract = setup();
+    contract.set_base_uri(OWNER, Some("ipfs://a/{id}".into())).unwrap();
+    contract.set_svg_template(OWNER, Some("<svg/>".into())).unwrap();
+    contract.freeze_metadata(CREATOR, TOKEN).unwrap();
+    let uri = contract.uri(TOKEN);
+    let err = contract.set_base_uri(OWNER, Some("ipfs://b/{id}".into())).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    assert_eq!(contract.uri(TOKEN), uri);
+    // TOKEN does not use the template until it is generative, and that is frozen too.
+    contract.set_svg_template(OWNER, Some("<svg>{name}</svg>".into())).unwrap();
+
+    contract.mint(CREATOR, 8, 1).unwrap();
+    contract.set_generative_metadata(CREATOR, 8, true).unwrap();
+    contract.freeze_metadata(CREATOR, 8).unwrap();
+    let err = contract.set_svg_template(OWNER, None).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+}
+
+#[test]
+fn tokens_are_found_by_attribute_conditions() {
+    let mut contract = setup();
+    let items = [(1, "legendary", "12"), (2, "common", "30"), (3, "legendary", "5"), (4, "legendary", "10")];
+    for (token_id, rarity, level) in items {
+        contract.mint(CREATOR, token_id, 1).unwrap();
+        contract.set_attribute(CREATOR, token_id, "rarity", rarity).unwrap();
+        contract.set_attribute(CREATOR, token_id, "level", level).unwrap();
+    }
+    let query = r#"[{"key": "rarity", "op": "==", "value": "legendary"}, {"key": "level", "op": ">=", "value": 10}]"#;
+    assert_eq!(contract.find_tokens(query, 0, 10).unwrap(), vec![1, 4]);
+    assert_eq!(contract.find_tokens(query, 1, 10).unwrap(), vec![4]);
+
+    // Rewrites and removals keep the index in step.
+    contract.set_attribute(CREATOR, 4, "level", "9").unwrap();
+    contract.remove_attribute(CREATOR, 1, "rarity").unwrap();
+    assert!(contract.find_tokens(query, 0, 10).unwrap().is_empty());
+    let query = r#"[{"key": "level", "op": "<", "value": "10"}]"#;
+    assert_eq!(contract.find_tokens(query, 0, 10).unwrap(), vec![3, 4]);
+
+    let query = r#"[{"key": "level", "op": "~", "value": 1}]"#;
+    assert_eq!(contract.find_tokens(query, 0, 10).unwrap_err().kind(), ErrorKind::InvalidInput);
+    assert_eq!(contract.find_tokens("[]", 0, 10).unwrap_err().kind(), ErrorKind::InvalidInput);
+}
@@ -0,0 +1,108 @@
+mod common;
+
+use common::{setup, ADMIN, OWNER};
+use erc1155_wasm::{ErrorKind, EventKind, ERC1155};
+
+const ALICE: &str = "0xa11ce";
+const BOB: &str = "0xb0b";
+
+#[test]
+fn imports_json_dump_with_totals() {
+    let mut contract = ERC1155::new(OWNER).unwrap();
+    let dump = r#"{
+        "balances": [
+            { "holder": "0xA11CE", "id": 1, "amount": "100" },
+            { "holder": "0xb0b", "id": "0x1", "amount": 50 },
+            { "holder": "0xa11ce", "id": 1, "amount": "0x0a" },
+            { "holder": "0xb0b", "id": 2, "amount": 0 }
+        ],
+        "approvals": [{ "owner": "0xa11ce", "operator": "0xB0B" }],
+        "totals": { "1": "160" }
+    }"#;
+
+    let report = contract.import_state(OWNER, dump, "json").unwrap();
+    assert!(report.verified());
+    assert_eq!(report.balances_imported, 2);
+    assert_eq!(report.holders, 2);
+    assert_eq!(report.approvals_imported, 1);
+    assert_eq!(report.totals.len(), 1);
+    assert_eq!((report.totals[0].token_id, report.totals[0].total), (1, 160));
+
+    assert_eq!(contract.balance_of(ALICE, 1), 110);
+    assert_eq!(contract.balance_of(BOB, 1), 50);
+    assert!(contract.is_approved(ALICE, BOB));
+    let events = contract.get_events(0, 10);
+    assert_eq!(events.len(), 3);
+    assert_eq!(events[0].kind, EventKind::TransferSingle);
+    assert_eq!(events[0].from, None);
+    assert_eq!(events[2].kind, EventKind::ApprovalForAll);
+
+    contract.transfer_from(BOB, ALICE, BOB, 1, 10).unwrap();
+    assert!(contract.verify_totals(r#"{ "1": 160 }"#).unwrap().is_empty());
+}
+
+#[test]
+fn imports_csv_and_reports_mismatches() {
+    let mut contract = ERC1155::new(OWNER).unwrap();
+    let csv = "holder,id,amount\n# exported at block 123\n0xa11ce,1,100\n\n0xb0b,2,7\n";
+    let report = contract.import_state(OWNER, csv, "csv").unwrap();
+    assert_eq!(report.balances_imported, 2);
+    assert!(report.verified());
+
+    let mismatches = contract.verify_totals(r#"{ "1": "100", "2": "8", "3": "1" }"#).unwrap();
+    let found: Vec<(u32, &str, &str)> = mismatches
+        .iter()
+        .map(|m| (m.token_id, m.expected.as_str(), m.actual.as_str()))
+        .collect();
+    assert_eq!(found, vec![(2, "8", "7"), (3, "1", "0")]);
+}
+
+#[test]
+fn import_is_owner_only_and_requires_empty_state() {
+    let mut contract = ERC1155::new(OWNER).unwrap();
+    let csv = "0xa11ce,1,100";
+    assert_eq!(contract.import_state(ALICE, csv, "csv").unwrap_err().kind(), ErrorKind::Unauthorized);
+
+    contract.import_state(OWNER, csv, "csv").unwrap();
+    let err = contract.import_state(OWNER, csv, "csv").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    assert_eq!(contract.balance_of(ALICE, 1), 100);
+}
+
+#[test]
+fn invalid_dumps_leave_the_contract_untouched() {
+    let mut contract = ERC1155::new(OWNER).unwrap();
+    for (data, format) in [
+        ("0xa11ce,1,100\n0xb0b,4294967296,1", "csv"),
+        ("0xa11ce,1,18446744073709551615\n0xa11ce,1,1", "csv"),
+        ("0xa11ce,1", "csv"),
+        (r#"{ "balances": [{ "holder": " ", "id": 1, "amount": 1 }] }"#, "json"),
+        ("not json", "json"),
+        ("0xa11ce,1,1", "xml"),
+    ] {
+        let err = contract.import_state(OWNER, data, format).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput, "{}", data);
+    }
+    assert_eq!(contract.balance_of(ALICE, 1), 0);
+    assert_eq!(contract.event_count(), 0);
+}
+
+#[test]
+fn imported_supplies_follow_the_mint_rules() {
+    let mut contract = setup();
+    let nft_type = contract.create_token_type(ADMIN, false).unwrap();
+    let unknown_type = nft_type + (1 << 16);
+    for csv in [
+        format!("0xa11ce,{},1", nft_type),
+        format!("0xa11ce,{},2", nft_type + 1),
+        format!("0xa11ce,{},1\n0xb0b,{},1", nft_type + 1, nft_type + 1),
+        format!("0xa11ce,{},1", unknown_type + 1),
+    ] {
+        let err = contract.import_state(OWNER, &csv, "csv").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput, "{}", csv);
+    }
+    assert_eq!(contract.balance_of(ALICE, nft_type + 1), 0);
+
+    contract.import_state(OWNER, &format!("0xa11ce,{},1", nft_type + 1), "csv").unwrap();
+    assert_eq!(contract.balance_of(ALICE, nft_type + 1), 1);
+}
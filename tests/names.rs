@@ -0,0 +1,57 @@
+mod common;
+
+use common::ADMIN;
+use erc1155_wasm::{ErrorKind, EventKind, ERC1155};
+
+const ALICE: &str = "0xa11ce";
+const BOB: &str = "0xb0b";
+
+fn setup() -> ERC1155 {
+    let mut contract = common::setup();
+    contract.mint(ADMIN, 1, 100).unwrap();
+    contract.transfer(ADMIN, ALICE, 1, 100).unwrap();
+    contract
+}
+
+#[test]
+fn names_resolve_in_calls_and_views() {
+    let mut contract = setup();
+    let receipt = contract.register_name(ALICE, "Alice").unwrap();
+    assert_eq!(receipt.events[0].kind, EventKind::NameUpdated);
+    assert_eq!(receipt.events[0].value.as_deref(), Some("alice"));
+    contract.register_name(BOB, "@bob").unwrap();
+    assert_eq!(contract.resolve("@ALICE").as_deref(), Some(ALICE));
+    assert_eq!(contract.resolve("bob").as_deref(), Some(BOB));
+    assert_eq!(contract.name_of(BOB).as_deref(), Some("bob"));
+
+    let receipt = contract.transfer("@alice", "@bob", 1, 30).unwrap();
+    assert_eq!(receipt.from.as_deref(), Some(ALICE));
+    assert_eq!(receipt.to, BOB);
+    assert_eq!(contract.balance_of("@bob", 1), 30);
+    assert_eq!(contract.balance_of(BOB, 1), 30);
+    assert_eq!(contract.balance_of("@nobody", 1), 0);
+
+    let err = contract.transfer(ALICE, "@nobody", 1, 1).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+}
+
+#[test]
+fn names_are_unique_and_can_be_released() {
+    let mut contract = setup();
+    contract.register_name(ALICE, "shared").unwrap();
+    let err = contract.register_name(BOB, "SHARED").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    let err = contract.register_name(BOB, "not a name").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+
+    // Registering a new name frees the previous one.
+    contract.register_name(ALICE, "alice").unwrap();
+    assert_eq!(contract.resolve("shared"), None);
+    contract.register_name(BOB, "shared").unwrap();
+
+    contract.release_name(BOB).unwrap();
+    assert_eq!(contract.resolve("shared"), None);
+    assert_eq!(contract.name_of(BOB), None);
+    let err = contract.release_name(BOB).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+}
@@ -0,0 +1,60 @@
+mod common;
+
+use common::{setup, ADMIN, ALICE};
+use erc1155_wasm::{ErrorKind, EventKind, EventRecord};
+
+fn parse(lines: &str) -> Vec<EventRecord> {
+    lines.lines().map(|line| serde_json::from_str(line).unwrap()).collect()
+}
+
+#[test]
+fn events_are_drained_once_in_order() {
+    let mut contract = setup();
+    contract.mint(ADMIN, 1, 10).unwrap();
+    contract.transfer(ADMIN, ALICE, 1, 5).unwrap();
+    assert_eq!(contract.pending_event_count(), 3);
+
+    let batch = contract.drain_pending_events(2);
+    assert!(batch.ends_with('\n'));
+    let events = parse(&batch);
+    assert_eq!(events.iter().map(|event| event.seq).collect::<Vec<_>>(), vec![0, 1]);
+    assert_eq!(events[0].kind, EventKind::AdminAdded);
+    let events = parse(&contract.drain_pending_events(10));
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].seq, 2);
+    assert_eq!(contract.drain_pending_events(10), "");
+    assert_eq!(contract.unacknowledged_event_count(), 3);
+}
+
+#[test]
+fn unacknowledged_events_can_be_redelivered() {
+    let mut contract = setup();
+    contract.mint(ADMIN, 1, 10).unwrap();
+    contract.drain_pending_events(10);
+    assert_eq!(contract.ack_events(2).unwrap_err().kind(), ErrorKind::InvalidInput);
+    contract.ack_events(0).unwrap();
+
+    assert_eq!(contract.redeliver_unacknowledged(), 1);
+    let events = parse(&contract.drain_pending_events(10));
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].seq, 1);
+    contract.ack_events(1).unwrap();
+    assert_eq!(contract.redeliver_unacknowledged(), 0);
+    assert_eq!(contract.unacknowledged_event_count(), 0);
+}
+
+#[test]
+fn open_transactions_hold_their_events_back() {
+    let mut contract = setup();
+    contract.begin_transaction().unwrap();
+    contract.mint(ADMIN, 1, 10).unwrap();
+    assert_eq!(parse(&contract.drain_pending_events(10)).len(), 1);
+    assert_eq!(contract.pending_event_count(), 0);
+    contract.rollback().unwrap();
+    assert_eq!(contract.drain_pending_events(10), "");
+
+    contract.begin_transaction().unwrap();
+    contract.mint(ADMIN, 1, 10).unwrap();
+    contract.commit().unwrap();
+    assert_eq!(parse(&contract.drain_pending_events(10))[0].kind, EventKind::TransferSingle);
+}
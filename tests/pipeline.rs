@@ -0,0 +1,155 @@
+mod common;
+
+use common::{ADMIN, OWNER};
+use erc1155_wasm::{ContractError, ErrorKind, ERC1155};
+
+const ALICE: &str = "0xa11ce00000000000000000000000000000000000";
+
+fn setup() -> ERC1155 {
+    let mut contract = common::setup();
+    contract.mint(ADMIN, 1, 100).unwrap();
+    contract.transfer(ADMIN, ALICE, 1, 60).unwrap();
+    contract
+}
+
+#[test]
+fn hex_addresses_are_normalized() {
+    let mut contract = setup();
+    let shouting = ALICE.to_uppercase().replacen("0X", "0x", 1);
+
+    assert_eq!(contract.balance_of(&shouting, 1), 60);
+    assert_eq!(contract.balance_of(&format!("  {} ", ALICE), 1), 60);
+
+    let receipt = contract.transfer(&shouting, "bob", 1, 10).unwrap();
+    assert_eq!(receipt.operator, ALICE);
+    assert_eq!(contract.balance_of(ALICE, 1), 50);
+
+    // Non-hex names are only trimmed, never case-folded.
+    assert_eq!(contract.balance_of("Bob", 1), 0);
+    assert_eq!(contract.balance_of(" bob", 1), 10);
+}
+
+#[test]
+fn empty_addresses_are_rejected() {
+    let mut contract = setup();
+    assert_eq!(contract.transfer(ALICE, "  ", 1, 1).unwrap_err().kind(), ErrorKind::InvalidInput);
+    assert_eq!(contract.approve("", ALICE, 1).unwrap_err().kind(), ErrorKind::InvalidInput);
+    assert_eq!(contract.balance_of(ALICE, 1), 60);
+}
+
+#[test]
+fn malformed_inputs_fail_before_touching_state() {
+    let mut contract = setup();
+    let huge = "x".repeat(129);
+    assert_eq!(contract.transfer(ALICE, &huge, 1, 1).unwrap_err().kind(), ErrorKind::InvalidInput);
+    assert_eq!(contract.transfer(ALICE, "bob", 1, 0).unwrap_err().kind(), ErrorKind::InvalidInput);
+    assert_eq!(contract.mint(ADMIN, 2, 0).unwrap_err().kind(), ErrorKind::InvalidInput);
+    let err = contract.transfer_batch(ALICE, "bob", vec![1, 1], vec![1]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    assert!(contract.balances_of("bob").is_empty());
+    assert_eq!(contract.event_count(), 3);
+
+    assert_eq!(contract.set_max_batch_size(ALICE, 2).unwrap_err().kind(), ErrorKind::Unauthorized);
+    contract.set_max_batch_size(OWNER, 2).unwrap();
+    let err = contract.transfer_batch(ALICE, "bob", vec![1, 1, 1], vec![1, 1, 1]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    contract.transfer_batch(ALICE, "bob", vec![1, 1], vec![1, 1]).unwrap();
+    assert_eq!(contract.balance_of("bob", 1), 2);
+}
+
+#[test]
+fn pause_blocks_user_calls_but_not_admin_calls() {
+    let mut contract = setup();
+    assert_eq!(contract.pause(ADMIN).unwrap_err().kind(), ErrorKind::Unauthorized);
+    contract.pause(OWNER).unwrap();
+    assert!(contract.is_paused());
+
+    assert_eq!(contract.transfer(ALICE, "bob", 1, 1).unwrap_err().kind(), ErrorKind::Paused);
+    assert_eq!(contract.mint(ADMIN, 1, 1).unwrap_err().kind(), ErrorKind::Paused);
+    assert_eq!(contract.approve(ALICE, "bob", 1).unwrap_err().kind(), ErrorKind::Paused);
+    assert_eq!(
+        contract.transfer_batch(ALICE, "bob", vec![1], vec![1]).unwrap_err().kind(),
+        ErrorKind::Paused
+    );
+    contract.add_admin(OWNER, "carol").unwrap();
+
+    contract.unpause(OWNER).unwrap();
+    contract.transfer(ALICE, "bob", 1, 1).unwrap();
+}
+
+#[test]
+fn blacklisted_accounts_cannot_take_part_in_transfers() {
+    let mut contract = setup();
+    assert_eq!(
+        contract.set_blacklisted("bob", ALICE, true).unwrap_err().kind(),
+        ErrorKind::Unauthorized
+    );
+    contract.set_blacklisted(ADMIN, "mallory", true).unwrap();
+    assert!(contract.is_blacklisted("mallory"));
+
+    assert_eq!(contract.transfer(ALICE, "mallory", 1, 1).unwrap_err().kind(), ErrorKind::Blacklisted);
+    assert_eq!(contract.approve(ALICE, "mallory", 1).unwrap_err().kind(), ErrorKind::Blacklisted);
+    assert_eq!(contract.approve("mallory", ALICE, 1).unwrap_err().kind(), ErrorKind::Blacklisted);
+
+    contract.set_blacklisted(OWNER, "mallory", false).unwrap();
+    contract.transfer(ALICE, "mallory", 1, 1).unwrap();
+}
+
+#[test]
+fn failures_are_counted_per_method_and_category() {
+    let mut contract = common::setup();
+    contract.mint(ADMIN, 1, 10).unwrap();
+    contract.mint(ALICE, 1, 10).unwrap_err();
+    contract.mint(ALICE, 1, 10).unwrap_err();
+    contract.transfer(ADMIN, ALICE, 1, 11).unwrap_err();
+    contract.transfer(" ", ALICE, 1, 1).unwrap_err();
+    contract.transfer(ADMIN, ALICE, 1, 1).unwrap();
+
+    let stats: Vec<(String, String, u64)> = contract
+        .get_error_stats()
+        .into_iter()
+        .map(|stat| (stat.method, stat.code, stat.count))
+        .collect();
+    let expected = [
+        ("mint", "Unauthorized", 2),
+        ("transfer_from", "InsufficientBalance", 1),
+        ("transfer_from", "InvalidInput", 1),
+    ];
+    assert_eq!(stats, expected.map(|(method, code, count)| (method.to_string(), code.to_string(), count)));
+    assert_eq!(contract.error_count(), 4);
+
+    // Failures inside a rolled-back transaction stay counted.
+    contract.begin_transaction().unwrap();
+    contract.burn(ALICE, 1, 0).unwrap_err();
+    contract.rollback().unwrap();
+    assert_eq!(contract.error_count(), 5);
+    contract.reset_error_stats();
+    assert!(contract.get_error_stats().is_empty());
+}
+
+#[test]
+fn failures_are_counted_under_the_returned_error() {
+    let mut contract = setup();
+    // Errors the host builds itself are not failures of the contract.
+    let _ = ContractError::new(ErrorKind::Overflow, "host-side error");
+    contract.transfer(ALICE, ADMIN, 1, 1).unwrap();
+    assert_eq!(contract.error_count(), 0);
+
+    let errors = [
+        contract.transfer(ALICE, ADMIN, 1, 100).unwrap_err(),
+        contract.burn(ALICE, 2, 1).unwrap_err(),
+        contract.redeem(ALICE, 1).unwrap_err(),
+    ];
+    let stats: Vec<(String, String)> = contract
+        .get_error_stats()
+        .into_iter()
+        .map(|stat| (stat.method, stat.code))
+        .collect();
+    let mut expected: Vec<(String, String)> = ["transfer_from", "burn_from", "redeem"]
+        .iter()
+        .zip(&errors)
+        .map(|(method, err)| (method.to_string(), err.kind().code().to_string()))
+        .collect();
+    expected.sort();
+    assert_eq!(stats, expected);
+}
@@ -0,0 +1,89 @@
+mod common;
+
+use common::{ADMIN, ALICE, BOB, CAROL, OWNER};
+use erc1155_wasm::{ErrorKind, EventKind, ERC1155};
+
+const STAKE: u32 = 1;
+const REWARD: u32 = 2;
+
+/// Contract where ALICE holds 50, BOB 30 and CAROL 20 stake tokens, with a snapshot of that.
+fn setup() -> (ERC1155, u64) {
+    let mut contract = common::setup();
+    contract.mint(ADMIN, STAKE, 100).unwrap();
+    contract.transfer(ADMIN, ALICE, STAKE, 50).unwrap();
+    contract.transfer(ADMIN, BOB, STAKE, 30).unwrap();
+    contract.transfer(ADMIN, CAROL, STAKE, 20).unwrap();
+    let snapshot = contract.take_snapshot(OWNER).unwrap();
+    (contract, snapshot)
+}
+
+#[test]
+fn direct_mode_credits_every_holder_pro_rata() {
+    let (mut contract, snapshot) = setup();
+    // Balances moved after the snapshot do not change the split.
+    contract.transfer(CAROL, ALICE, STAKE, 20).unwrap();
+    let err = contract.distribute_to_holders(BOB, STAKE, snapshot, REWARD, 1_000, false).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Unauthorized);
+    let err = contract.distribute_to_holders(ADMIN, STAKE, snapshot + 1, REWARD, 1_000, false).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+
+    let id = contract.distribute_to_holders(ADMIN, STAKE, snapshot, REWARD, 1_001, false).unwrap();
+    assert_eq!(contract.balance_of(ALICE, REWARD), 500);
+    assert_eq!(contract.balance_of(BOB, REWARD), 300);
+    assert_eq!(contract.balance_of(CAROL, REWARD), 200);
+    assert_eq!(contract.total_supply(REWARD), 1_000);
+
+    let info = contract.distribution(id).unwrap();
+    assert_eq!((info.distributed, info.recipients, info.unclaimed), (1_000, 3, 0));
+    assert!(!info.claimable);
+    let events = contract.get_events(contract.event_count() - 4, 4);
+    assert!(events[..3].iter().all(|event| event.kind == EventKind::TransferSingle));
+    assert_eq!(events[3].kind, EventKind::RewardsDistributed);
+    assert_eq!(events[3].value.as_deref(), Some("1"));
+}
+
+#[test]
+fn claim_mode_holds_rewards_in_escrow() {
+    let (mut contract, snapshot) = setup();
+    let id = contract.distribute_to_holders(ADMIN, STAKE, snapshot, REWARD, 10, true).unwrap();
+    assert_eq!(contract.balance_of("erc1155:escrow", REWARD), 10);
+    assert_eq!(contract.claimable_reward(id, BOB), 3);
+
+    let receipt = contract.claim_reward(BOB, id).unwrap();
+    assert_eq!(receipt.balances[1].after, 3);
+    assert_eq!(contract.balance_of(BOB, REWARD), 3);
+    assert_eq!(contract.claimable_reward(id, BOB), 0);
+    let err = contract.claim_reward(BOB, id).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    let err = contract.claim_reward(ADMIN, id).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    assert_eq!(contract.distribution(id).unwrap().unclaimed, 7);
+    assert_eq!(contract.distribution_count(), 1);
+}
+
+#[test]
+fn distributions_need_a_share_to_give() {
+    let (mut contract, snapshot) = setup();
+    let err = contract.distribute_to_holders(ADMIN, STAKE, snapshot, REWARD, 0, false).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    let err = contract.distribute_to_holders(ADMIN, 9, snapshot, REWARD, 10, false).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    let err = contract.distribute_to_holders(ADMIN, STAKE, snapshot, REWARD, 1, false).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    assert!(contract.distribution(1).is_none());
+}
+
+#[test]
+fn blacklisted_holders_are_left_out_of_the_split() {
+    let (mut contract, snapshot) = setup();
+    contract.set_blacklisted(OWNER, CAROL, true).unwrap();
+    let id = contract.distribute_to_holders(ADMIN, STAKE, snapshot, REWARD, 800, false).unwrap();
+    assert_eq!(contract.balance_of(ALICE, REWARD), 500);
+    assert_eq!(contract.balance_of(BOB, REWARD), 300);
+    assert_eq!(contract.balance_of(CAROL, REWARD), 0);
+    assert_eq!(contract.distribution(id).unwrap().recipients, 2);
+
+    let id = contract.distribute_to_holders(ADMIN, STAKE, snapshot, REWARD, 80, true).unwrap();
+    assert_eq!(contract.claimable_reward(id, CAROL), 0);
+    assert_eq!(contract.distribution(id).unwrap().unclaimed, 80);
+}
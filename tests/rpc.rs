@@ -0,0 +1,151 @@
+mod common;
+
+use common::{ADMIN, OWNER};
+use erc1155_wasm::{ErrorKind, OperationFilter, ERC1155};
+use serde_json::{json, Value};
+
+fn call(contract: &mut ERC1155, request: Value) -> Value {
+    serde_json::from_str(&contract.call(&request.to_string())).unwrap()
+}
+
+#[test]
+fn requests_are_routed_by_method() {
+    let mut contract = ERC1155::new(OWNER).unwrap();
+    let response = call(
+        &mut contract,
+        json!({ "id": 1, "method": "add_admin", "caller": OWNER, "params": { "new_admin": ADMIN } }),
+    );
+    assert_eq!(response["id"], 1);
+    assert_eq!(response["ok"], true);
+    assert_eq!(response["result"]["method"], "add_admin");
+
+    let request = json!({ "method": "mint", "caller": ADMIN, "params": { "token_id": 7, "amount": 50 } });
+    let response = call(&mut contract, request);
+    assert_eq!(response["result"]["to_balance"], 50);
+    let params = json!({ "to": "bob", "token_id": 7, "amount": 20 });
+    let request = json!({ "method": "transfer", "caller": ADMIN, "params": params });
+    call(&mut contract, request);
+
+    let response = call(&mut contract, json!({ "method": "balance_of", "params": { "owner": "bob", "token_id": 7 } }));
+    assert_eq!(response["result"], 20);
+    assert_eq!(contract.balance_of("bob", 7), 20);
+}
+
+#[test]
+fn failures_are_reported_in_the_envelope() {
+    let mut contract = ERC1155::new(OWNER).unwrap();
+    let request = json!({ "id": "a", "method": "mint", "caller": "bob", "params": { "token_id": 1, "amount": 5 } });
+    let response = call(&mut contract, request);
+    assert_eq!(response["id"], "a");
+    assert_eq!(response["ok"], false);
+    assert_eq!(response["error"]["code"], "Unauthorized");
+
+    let response = call(&mut contract, json!({ "method": "mint", "caller": OWNER, "params": { "token_id": -1 } }));
+    assert_eq!(response["error"]["code"], "InvalidInput");
+    let response = call(&mut contract, json!({ "method": "self_destruct" }));
+    assert_eq!(response["error"]["code"], "InvalidInput");
+
+    let response: Value = serde_json::from_str(&contract.call("not json")).unwrap();
+    assert_eq!(response["ok"], false);
+    assert_eq!(response["id"], Value::Null);
+}
+
+#[test]
+fn recorded_calls_replay_to_the_same_state() {
+    let mut contract = ERC1155::new(OWNER).unwrap();
+    contract.set_time(OWNER, Some(1_000)).unwrap();
+    let requests = [
+        json!({ "method": "add_admin", "caller": OWNER, "params": { "new_admin": ADMIN } }),
+        json!({ "method": "mint", "caller": ADMIN, "params": { "token_id": 7, "amount": 50 } }),
+        json!({ "method": "transfer", "caller": ADMIN, "params": { "to": "bob", "token_id": 7, "amount": 20 } }),
+        json!({ "method": "approve", "caller": "bob", "params": { "approved": ADMIN, "token_id": 7 } }),
+    ];
+    let mut operations: Vec<Value> = requests
+        .iter()
+        .map(|request| {
+            let response = call(&mut contract, request.clone());
+            let mut operation = request.clone();
+            operation["timestamp"] = json!(1_000);
+            operation["receipt_hash"] = response["receipt_hash"].clone();
+            operation
+        })
+        .collect();
+    let log = json!({ "owner": OWNER, "operations": operations, "state_hash": contract.state_hash() });
+
+    let replayed = ERC1155::replay(&log.to_string()).unwrap();
+    assert_eq!(replayed.state_hash(), contract.state_hash());
+    assert_eq!(replayed.operation_count(), 4);
+    assert_eq!(replayed.balance_of("bob", 7), 20);
+
+    operations[2]["params"]["amount"] = json!(21);
+    let log = json!({ "owner": OWNER, "operations": operations });
+    let err = ERC1155::replay(&log.to_string()).err().unwrap();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    assert!(err.message().starts_with("Operation 2 "));
+
+    operations[2]["params"]["amount"] = json!(80);
+    let log = json!({ "owner": OWNER, "operations": operations });
+    let err = ERC1155::replay(&log.to_string()).err().unwrap();
+    assert_eq!(err.kind(), ErrorKind::InsufficientBalance);
+}
+
+#[test]
+fn operation_log_exports_as_a_replayable_log() {
+    let mut contract = ERC1155::new(OWNER).unwrap();
+    let requests = [
+        json!({ "method": "add_admin", "caller": OWNER, "params": { "new_admin": ADMIN } }),
+        json!({ "method": "mint", "caller": ADMIN, "params": { "token_id": 7, "amount": 50 } }),
+        json!({ "method": "transfer", "caller": ADMIN, "params": { "to": "bob", "token_id": 7, "amount": 80 } }),
+        json!({ "method": "transfer", "caller": ADMIN, "params": { "to": "bob", "token_id": 7, "amount": 20 } }),
+        json!({ "method": "approve_until", "caller": "bob", "params": { "operator": ADMIN, "expires_at": u64::MAX } }),
+        json!({ "method": "transfer_ownership", "caller": OWNER, "params": { "new_owner": "carol" } }),
+        json!({ "method": "balance_of", "params": { "owner": "bob", "token_id": 7 } }),
+    ];
+    for request in requests {
+        call(&mut contract, request);
+    }
+    let operations = contract.get_operations(&OperationFilter::new());
+    assert_eq!(operations.len(), 5);
+    assert!(operations.iter().all(|operation| operation.request.is_some() && operation.receipt_hash.is_some()));
+
+    let log = contract.export_replay_log().unwrap();
+    let replayed = ERC1155::replay(&log).unwrap();
+    assert_eq!(replayed.state_hash(), contract.state_hash());
+    assert_eq!(replayed.owner(), Some("carol".to_string()));
+    assert_eq!(replayed.export_replay_log().unwrap(), log);
+
+    // Direct calls keep no request to replay.
+    contract.transfer("bob", ADMIN, 7, 1).unwrap();
+    let err = contract.export_replay_log().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    assert_eq!(err.message(), "Operation 5 was not made through `call`.");
+}
+
+#[test]
+fn every_recorded_method_replays_through_call() {
+    let mut contract = ERC1155::new(OWNER).unwrap();
+    let requests = [
+        json!({ "method": "add_admin", "caller": OWNER, "params": { "new_admin": ADMIN } }),
+        json!({ "method": "create_token_type", "caller": ADMIN, "params": { "fungible": true } }),
+        json!({ "method": "mint_to_many_same", "caller": ADMIN,
+                "params": { "token_id": 7, "recipients": ["bob", "carol"], "amount": 10 } }),
+        json!({ "method": "register_name", "caller": "bob", "params": { "name": "bobby" } }),
+        json!({ "method": "approve_scoped", "caller": "@bobby",
+                "params": { "operator": "carol", "token_ids": [7], "amounts": [4] } }),
+        json!({ "method": "set_attribute", "caller": ADMIN,
+                "params": { "token_id": 7, "key": "tier", "value": "gold" } }),
+        json!({ "method": "set_royalty", "caller": ADMIN,
+                "params": { "token_id": 7, "receiver": ADMIN, "basis_points": 250 } }),
+        json!({ "method": "take_snapshot", "caller": ADMIN }),
+    ];
+    for request in requests {
+        let response = call(&mut contract, request);
+        assert_eq!(response["ok"], true, "{}", response);
+    }
+    assert_eq!(contract.operation_count(), 8);
+
+    let replayed = ERC1155::replay(&contract.export_replay_log().unwrap()).unwrap();
+    assert_eq!(replayed.state_hash(), contract.state_hash());
+    assert_eq!(replayed.scoped_allowance("bob", "carol", 7), 4);
+    assert_eq!(replayed.attribute(7, "tier").as_deref(), Some("gold"));
+}
@@ -0,0 +1,70 @@
+mod common;
+
+use common::{ADMIN, OWNER};
+use erc1155_wasm::{ErrorKind, EventKind, ERC1155};
+
+const CREATOR: &str = "creator";
+const OTHER: &str = "other";
+
+fn setup() -> ERC1155 {
+    let mut contract = common::setup();
+    contract.set_creator_role(OWNER, CREATOR, true).unwrap();
+    contract
+}
+
+#[test]
+fn token_types_split_ids() {
+    let mut contract = setup();
+    let err = contract.create_token_type(OTHER, true).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Unauthorized);
+
+    let gold = contract.create_token_type(CREATOR, true).unwrap();
+    let swords = contract.create_token_type(CREATOR, false).unwrap();
+    assert_eq!(gold, 1 << 16);
+    assert_eq!(swords, 1 << 31 | 2 << 16);
+    assert_eq!(contract.creator_of(swords), Some(CREATOR.to_string()));
+    let event = contract.get_events(0, usize::MAX).pop().unwrap();
+    assert_eq!((event.kind, event.token_id, event.approved), (EventKind::TokenTypeCreated, Some(swords), Some(false)));
+
+    assert!(contract.is_fungible(gold) && !contract.is_nft(gold));
+    assert!(contract.is_fungible(7) && !contract.is_nft(7));
+    assert!(!contract.is_fungible(swords) && !contract.is_nft(swords));
+    assert!(contract.is_nft(swords + 3) && !contract.is_fungible(swords + 3));
+    assert_eq!(contract.token_type(swords + 3), swords);
+    assert_eq!(contract.token_index(swords + 3), 3);
+    assert_eq!(contract.token_type(7), 0);
+}
+
+#[test]
+fn plain_ids_in_use_are_skipped() {
+    let mut contract = setup();
+    contract.mint(ADMIN, 1 << 16, 5).unwrap();
+    assert_eq!(contract.create_token_type(ADMIN, true).unwrap(), 2 << 16);
+}
+
+#[test]
+fn nfts_have_a_supply_of_one() {
+    let mut contract = setup();
+    let swords = contract.create_token_type(CREATOR, false).unwrap();
+
+    let err = contract.mint(CREATOR, swords, 1).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    let err = contract.mint(ADMIN, (1 << 31) | (9 << 16) | 1, 1).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    let err = contract.mint(CREATOR, swords + 1, 2).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+
+    contract.mint(CREATOR, swords + 1, 1).unwrap();
+    assert_eq!(contract.total_supply(swords + 1), 1);
+    let err = contract.mint(CREATOR, swords + 1, 1).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    contract.transfer_from(CREATOR, CREATOR, OTHER, swords + 1, 1).unwrap();
+    let err = contract.mint(ADMIN, swords + 1, 1).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+
+    // Items of a type can only be minted by its creator or an admin.
+    contract.set_creator_role(OWNER, OTHER, true).unwrap();
+    let err = contract.mint(OTHER, swords + 2, 1).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Unauthorized);
+    contract.mint(ADMIN, swords + 2, 1).unwrap();
+}
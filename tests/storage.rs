@@ -0,0 +1,131 @@
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+
+use erc1155_wasm::{KeyValueStore, KvStorage, MemoryStorage, Storage, StoreProvider, ERC1155};
+
+/// Runs the same sequence of writes against any backend.
+fn exercise<S: Storage<u64>>(mut storage: S) -> S {
+    assert!(storage.is_empty());
+    storage.set("bob", 2);
+    storage.set("alice", 1);
+    storage.set("bob", 3);
+    assert_eq!(storage.get("bob"), Some(3));
+    assert_eq!(storage.get("carol"), None);
+    assert_eq!(storage.len(), 2);
+    assert_eq!(storage.remove("alice"), Some(1));
+    assert_eq!(storage.remove("alice"), None);
+    storage.set("carol", 0);
+    storage
+}
+
+fn sorted<S: Storage<u64>>(storage: &S) -> Vec<(String, u64)> {
+    let mut entries: Vec<(String, u64)> = storage.iter().map(|(key, value)| (key.into_owned(), value)).collect();
+    entries.sort();
+    entries
+}
+
+#[test]
+fn backends_behave_alike() {
+    let memory = exercise(MemoryStorage::default());
+    let kv = exercise(KvStorage::<u64>::default());
+    assert_eq!(sorted(&memory), sorted(&kv));
+    assert_eq!(sorted(&kv), vec![("bob".to_string(), 3), ("carol".to_string(), 0)]);
+    // The in-memory backend lends its keys rather than copying them.
+    assert!(memory.iter().all(|(key, _)| matches!(key, Cow::Borrowed(_))));
+}
+
+#[test]
+fn kv_backend_encodes_into_the_byte_store() {
+    let mut storage: KvStorage<Option<u64>> = KvStorage::default();
+    storage.set("forever", None);
+    storage.set("until", Some(7));
+    assert_eq!(KeyValueStore::get(storage.store(), b"until"), Some(7u64.to_le_bytes().to_vec()));
+    assert_eq!(KeyValueStore::get(storage.store(), b"forever"), Some(Vec::new()));
+
+    // A store written by one instance can be reopened by another.
+    let reopened: KvStorage<Option<u64>, BTreeMap<Vec<u8>, Vec<u8>>> = KvStorage::new(storage.store().clone());
+    let entries: Vec<(String, Option<u64>)> = reopened.iter().map(|(key, value)| (key.into_owned(), value)).collect();
+    assert_eq!(entries, vec![("forever".to_string(), None), ("until".to_string(), Some(7))]);
+}
+
+type Table = BTreeMap<Vec<u8>, Vec<u8>>;
+
+/// Tables kept by the host, outside the contract, by table ID.
+#[derive(Clone, Default)]
+struct HostStore {
+    tables: Arc<Mutex<HashMap<usize, Table>>>,
+}
+
+impl HostStore {
+    fn entries(&self) -> usize {
+        self.tables.lock().unwrap().values().map(Table::len).sum()
+    }
+}
+
+struct HostTable {
+    host: HostStore,
+    id: usize,
+}
+
+impl KeyValueStore for HostTable {
+    fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        self.host.tables.lock().unwrap()[&self.id].get(key).cloned()
+    }
+
+    fn put(&mut self, key: &[u8], value: Vec<u8>) {
+        self.host.tables.lock().unwrap().get_mut(&self.id).unwrap().insert(key.to_vec(), value);
+    }
+
+    fn delete(&mut self, key: &[u8]) -> Option<Vec<u8>> {
+        self.host.tables.lock().unwrap().get_mut(&self.id).unwrap().remove(key)
+    }
+
+    fn scan(&self) -> Box<dyn Iterator<Item = (Vec<u8>, Vec<u8>)> + '_> {
+        let entries: Vec<(Vec<u8>, Vec<u8>)> = self.host.tables.lock().unwrap()[&self.id].clone().into_iter().collect();
+        Box::new(entries.into_iter())
+    }
+
+    fn len(&self) -> usize {
+        self.host.tables.lock().unwrap()[&self.id].len()
+    }
+}
+
+impl Drop for HostTable {
+    fn drop(&mut self) {
+        self.host.tables.lock().unwrap().remove(&self.id);
+    }
+}
+
+impl StoreProvider for HostStore {
+    fn open(&self) -> Box<dyn KeyValueStore + Send + Sync> {
+        let mut tables = self.tables.lock().unwrap();
+        let id = (0..).find(|id| !tables.contains_key(id)).unwrap();
+        tables.insert(id, BTreeMap::new());
+        Box::new(HostTable { host: self.clone(), id })
+    }
+}
+
+#[test]
+fn contract_keeps_its_tables_in_a_host_store() {
+    let host = HostStore::default();
+    let mut hosted = ERC1155::with_store("owner", Arc::new(host.clone())).unwrap();
+    let mut local = ERC1155::new("owner").unwrap();
+    for contract in [&mut hosted, &mut local] {
+        contract.add_admin("owner", "admin").unwrap();
+        contract.mint("admin", 1, 100).unwrap();
+        contract.transfer("admin", "alice", 1, 40).unwrap();
+        contract.approve("alice", "bob", 1).unwrap();
+        contract.set_attribute("admin", 1, "color", "red").unwrap();
+    }
+    assert_eq!(hosted.state_hash(), local.state_hash());
+    assert_eq!(host.entries(), 4);
+
+    // Forks copy a shared table into a new one when they first write it.
+    let mut fork = hosted.fork();
+    fork.transfer("alice", "bob", 1, 40).unwrap();
+    assert_eq!(hosted.balance_of("alice", 1), 40);
+    assert_eq!(fork.balance_of("alice", 1), 0);
+    drop(fork);
+    assert_eq!(host.entries(), 4);
+}
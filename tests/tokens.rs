@@ -0,0 +1,257 @@
+mod common;
+
+use common::{ADMIN, OWNER};
+use erc1155_wasm::{ErrorKind, EventKind, RoyaltyInfo, ERC1155};
+
+const CREATOR: &str = "creator";
+const OTHER: &str = "other";
+
+/// Contract with one admin and one account holding the creator role.
+fn setup() -> ERC1155 {
+    let mut contract = common::setup();
+    contract.set_creator_role(OWNER, CREATOR, true).unwrap();
+    contract
+}
+
+#[test]
+fn first_minter_becomes_creator() {
+    let mut contract = setup();
+    assert!(contract.has_creator_role(CREATOR));
+    assert_eq!(contract.creator_of(1), None);
+
+    contract.mint(CREATOR, 1, 10).unwrap();
+    contract.mint(ADMIN, 2, 10).unwrap();
+    assert_eq!(contract.creator_of(1), Some(CREATOR.to_string()));
+    assert_eq!(contract.creator_of(2), Some(ADMIN.to_string()));
+
+    // Admins can mint any token; creators only their own.
+    contract.mint(ADMIN, 1, 5).unwrap();
+    assert_eq!(contract.creator_of(1), Some(CREATOR.to_string()));
+    assert_eq!(contract.total_supply(1), 15);
+    let err = contract.mint(CREATOR, 2, 1).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Unauthorized);
+    let err = contract.mint(OTHER, 3, 1).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Unauthorized);
+
+    contract.set_creator_role(ADMIN, CREATOR, false).unwrap();
+    let err = contract.mint(CREATOR, 3, 1).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Unauthorized);
+    contract.mint(CREATOR, 1, 1).unwrap();
+    let err = contract.set_creator_role(CREATOR, OTHER, true).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Unauthorized);
+}
+
+#[test]
+fn only_creator_or_admin_configures_token() {
+    let mut contract = setup();
+    contract.mint(CREATOR, 1, 10).unwrap();
+
+    let err = contract.set_token_uri(OTHER, 1, "ipfs://x").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Unauthorized);
+    let err = contract.set_token_uri(CREATOR, 9, "ipfs://x").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    let err = contract.set_royalty(OTHER, 1, OTHER, 100).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Unauthorized);
+    let err = contract.set_max_supply(OTHER, 1, Some(20)).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Unauthorized);
+
+    let receipt = contract.set_token_uri(CREATOR, 1, "ipfs://one").unwrap();
+    assert_eq!(receipt.events[0].kind, EventKind::Uri);
+    assert_eq!(receipt.events[0].value.as_deref(), Some("ipfs://one"));
+    assert_eq!(contract.uri(1), "ipfs://one");
+    assert_eq!(contract.uri(2), "");
+    contract.set_token_uri(ADMIN, 1, "ipfs://two").unwrap();
+    assert_eq!(contract.uri(1), "ipfs://two");
+}
+
+#[test]
+fn token_configuration_works_while_paused_like_collection_configuration() {
+    let mut contract = setup();
+    contract.mint(CREATOR, 1, 10).unwrap();
+    contract.pause(OWNER).unwrap();
+
+    contract.set_token_uri(CREATOR, 1, "ipfs://one").unwrap();
+    contract.set_royalty(CREATOR, 1, CREATOR, 100).unwrap();
+    contract.set_max_supply(CREATOR, 1, Some(20)).unwrap();
+    contract.set_base_uri(OWNER, Some("ipfs://base/".into())).unwrap();
+    assert!(contract.mint(CREATOR, 1, 1).is_err());
+}
+
+#[test]
+fn royalties_follow_basis_points() {
+    let mut contract = setup();
+    contract.mint(CREATOR, 1, 10).unwrap();
+    assert_eq!(contract.royalty_info(1, 1_000), None);
+
+    let err = contract.set_royalty(CREATOR, 1, CREATOR, 10_001).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    contract.set_royalty(CREATOR, 1, CREATOR, 250).unwrap();
+    assert_eq!(
+        contract.royalty_info(1, 1_000),
+        Some(RoyaltyInfo {
+            receiver: CREATOR.to_string(),
+            amount: 25,
+        })
+    );
+    assert_eq!(contract.royalty_info(1, u64::MAX).unwrap().amount, u64::MAX / 40);
+}
+
+#[test]
+fn default_royalty_applies_until_overridden() {
+    let mut contract = setup();
+    contract.mint(CREATOR, 1, 10).unwrap();
+    contract.mint(CREATOR, 2, 10).unwrap();
+    let err = contract.set_default_royalty(CREATOR, Some(OWNER.into()), 500).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Unauthorized);
+    contract.set_default_royalty(ADMIN, Some(OWNER.into()), 500).unwrap();
+    contract.set_royalty(CREATOR, 1, CREATOR, 100).unwrap();
+    assert_eq!(contract.royalty_info(1, 1_000).unwrap().amount, 10);
+    assert_eq!(contract.royalty_info(2, 1_000).unwrap().receiver, OWNER);
+    assert_eq!(contract.royalty_info(99, 1_000).unwrap().amount, 50);
+    assert!(contract.has_token_royalty(1));
+
+    let err = contract.delete_token_royalty(OTHER, 1).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Unauthorized);
+    contract.delete_token_royalty(CREATOR, 1).unwrap();
+    assert!(!contract.has_token_royalty(1));
+    assert_eq!(contract.royalty_info(1, 1_000).unwrap().receiver, OWNER);
+    let err = contract.delete_token_royalty(CREATOR, 1).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+
+    contract.set_default_royalty(OWNER, None, 0).unwrap();
+    assert_eq!(contract.royalty_info(1, 1_000), None);
+}
+
+#[test]
+fn royalty_ceiling_bounds_every_royalty() {
+    let mut contract = setup();
+    contract.mint(CREATOR, 1, 10).unwrap();
+    contract.set_royalty(CREATOR, 1, CREATOR, 800).unwrap();
+    assert_eq!(contract.royalty_ceiling(), 10_000);
+
+    let err = contract.set_royalty_ceiling(ADMIN, 1_000).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Unauthorized);
+    let err = contract.set_royalty_ceiling(OWNER, 500).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    contract.set_royalty_ceiling(OWNER, 1_000).unwrap();
+    assert_eq!(contract.royalty_ceiling(), 1_000);
+
+    let err = contract.set_royalty(CREATOR, 1, CREATOR, 1_001).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    let err = contract.set_default_royalty(OWNER, Some(OWNER.into()), 1_001).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    contract.set_default_royalty(OWNER, Some(OWNER.into()), 1_000).unwrap();
+}
+
+#[test]
+fn max_supply_caps_minting() {
+    let mut contract = setup();
+    contract.mint(CREATOR, 1, 10).unwrap();
+
+    let err = contract.set_max_supply(CREATOR, 1, Some(9)).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    contract.set_max_supply(CREATOR, 1, Some(15)).unwrap();
+    assert_eq!(contract.max_supply(1), Some(15));
+
+    contract.transfer_from(CREATOR, CREATOR, OTHER, 1, 4).unwrap();
+    let err = contract.mint(ADMIN, 1, 6).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    contract.mint(ADMIN, 1, 5).unwrap();
+    assert_eq!(contract.total_supply(1), 15);
+
+    contract.set_max_supply(ADMIN, 1, None).unwrap();
+    contract.mint(CREATOR, 1, 1).unwrap();
+    assert_eq!(contract.max_supply(1), None);
+    assert_eq!(contract.total_supply(1), 16);
+}
+
+#[test]
+fn token_classes_are_registered_once() {
+    let mut contract = setup();
+    let err = contract.register_token(OTHER, 7, "Gold", "GOLD", 2).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Unauthorized);
+    let err = contract.register_token(CREATOR, 7, "Gold", " ", 2).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+
+    let receipt = contract.register_token(CREATOR, 7, "Gold", "GOLD", 2).unwrap();
+    assert_eq!(receipt.events[0].kind, EventKind::TokenRegistered);
+    assert_eq!(contract.creator_of(7), Some(CREATOR.to_string()));
+    assert_eq!(contract.token_name(7).as_deref(), Some("Gold"));
+    assert_eq!(contract.token_symbol(7).as_deref(), Some("GOLD"));
+    assert_eq!(contract.token_decimals(7), Some(2));
+    assert_eq!(contract.token_class(8), None);
+
+    let err = contract.register_token(ADMIN, 7, "Silver", "SLV", 0).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    // Registering made the caller the creator, so others cannot mint it.
+    let err = contract.mint(OTHER, 7, 1).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Unauthorized);
+    contract.mint(CREATOR, 7, 150).unwrap();
+
+    contract.mint(ADMIN, 8, 1).unwrap();
+    let err = contract.register_token(CREATOR, 8, "Silver", "SLV", 0).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Unauthorized);
+    contract.register_token(ADMIN, 8, "Silver", "SLV", 0).unwrap();
+}
+
+#[test]
+fn frozen_metadata_cannot_change() {
+    let mut contract = setup();
+    contract.mint(CREATOR, 1, 10).unwrap();
+    contract.mint(CREATOR, 2, 10).unwrap();
+    contract.set_token_uri(CREATOR, 1, "ipfs://one").unwrap();
+
+    let err = contract.freeze_metadata(OTHER, 1).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Unauthorized);
+    contract.freeze_metadata(CREATOR, 1).unwrap();
+    assert_eq!(contract.get_events(contract.event_count() - 1, 1)[0].kind, EventKind::MetadataFrozen);
+    assert!(contract.is_metadata_frozen(1));
+    assert!(!contract.is_metadata_frozen(2));
+    let err = contract.set_token_uri(ADMIN, 1, "ipfs://changed").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    assert_eq!(contract.uri(1), "ipfs://one");
+    let err = contract.freeze_metadata(CREATOR, 1).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+
+    let err = contract.freeze_all_metadata(ADMIN).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Unauthorized);
+    contract.freeze_all_metadata(OWNER).unwrap();
+    assert!(contract.is_collection_metadata_frozen());
+    assert!(contract.is_metadata_frozen(3));
+    let err = contract.set_token_uri(CREATOR, 2, "ipfs://two").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+}
+
+#[test]
+fn expired_tokens_count_as_zero_until_swept() {
+    let mut contract = setup();
+    contract.set_time(OWNER, Some(1_000)).unwrap();
+    contract.mint(CREATOR, 1, 10).unwrap();
+    contract.transfer(CREATOR, OTHER, 1, 4).unwrap();
+    assert_eq!(
+        contract.set_token_expiry(OTHER, 1, Some(2_000)).unwrap_err().kind(),
+        ErrorKind::Unauthorized
+    );
+    contract.set_token_expiry(CREATOR, 1, Some(2_000)).unwrap();
+    assert_eq!(contract.sweep_expired(OTHER, 1, 10).unwrap_err().kind(), ErrorKind::InvalidInput);
+
+    contract.set_time(OWNER, Some(2_000)).unwrap();
+    assert!(contract.is_token_expired(1));
+    assert_eq!(contract.balance_of(OTHER, 1), 0);
+    assert!(contract.balances_of(OTHER).is_empty());
+    let err = contract.transfer(OTHER, CREATOR, 1, 1).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Expired);
+    assert_eq!(contract.mint(CREATOR, 1, 1).unwrap_err().kind(), ErrorKind::Expired);
+    assert_eq!(contract.burn(CREATOR, 1, 1).unwrap_err().kind(), ErrorKind::Expired);
+    assert_eq!(contract.set_token_expiry(CREATOR, 1, None).unwrap_err().kind(), ErrorKind::InvalidInput);
+    assert_eq!(contract.total_supply(1), 10);
+
+    let receipt = contract.sweep_expired(OTHER, 1, 1).unwrap();
+    assert_eq!(receipt.balances.len(), 1);
+    assert_eq!(receipt.balances[0].owner, CREATOR);
+    assert_eq!(contract.total_supply(1), 4);
+    let receipt = contract.sweep_expired(OTHER, 1, 10).unwrap();
+    assert_eq!(receipt.balances[0].owner, OTHER);
+    assert_eq!(receipt.events.last().unwrap().kind, EventKind::SupplyDepleted);
+    assert!(contract.sweep_expired(OTHER, 1, 10).unwrap().balances.is_empty());
+}
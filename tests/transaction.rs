@@ -0,0 +1,65 @@
+mod common;
+
+use common::{ADMIN, ALICE, BOB};
+use erc1155_wasm::{ErrorKind, ERC1155};
+
+fn setup() -> ERC1155 {
+    let mut contract = common::setup();
+    contract.mint(ADMIN, 1, 100).unwrap();
+    contract.transfer(ADMIN, ALICE, 1, 100).unwrap();
+    contract
+}
+
+#[test]
+fn rollback_discards_every_change() {
+    let mut contract = setup();
+    let hash = contract.state_hash();
+    let events = contract.event_count();
+
+    contract.begin_transaction().unwrap();
+    assert!(contract.in_transaction());
+    contract.transfer(ALICE, BOB, 1, 60).unwrap();
+    contract.approve(BOB, ALICE, 1).unwrap();
+    assert!(contract.transfer(ALICE, BOB, 1, 60).is_err());
+    contract.rollback().unwrap();
+
+    assert!(!contract.in_transaction());
+    assert_eq!(contract.state_hash(), hash);
+    assert_eq!(contract.event_count(), events);
+    assert_eq!(contract.balance_of(BOB, 1), 0);
+}
+
+#[test]
+fn commit_keeps_changes() {
+    let mut contract = setup();
+    contract.begin_transaction().unwrap();
+    contract.transfer(ALICE, BOB, 1, 60).unwrap();
+    contract.commit().unwrap();
+
+    assert_eq!(contract.balance_of(BOB, 1), 60);
+    assert_eq!(contract.rollback().unwrap_err().kind(), ErrorKind::InvalidInput);
+}
+
+#[test]
+fn transactions_do_not_nest() {
+    let mut contract = setup();
+    contract.begin_transaction().unwrap();
+    assert_eq!(contract.begin_transaction().unwrap_err().kind(), ErrorKind::InvalidInput);
+    contract.commit().unwrap();
+    assert_eq!(contract.commit().unwrap_err().kind(), ErrorKind::InvalidInput);
+}
+
+#[test]
+fn simulate_reports_the_outcome_and_leaves_state_alone() {
+    let mut contract = setup();
+    let outcome = contract
+        .simulate(|sandbox| {
+            sandbox.transfer(ALICE, BOB, 1, 70)?;
+            sandbox.transfer(BOB, ALICE, 1, 80)
+        })
+        .unwrap();
+
+    assert_eq!(outcome.unwrap_err().kind(), ErrorKind::InsufficientBalance);
+    assert_eq!(contract.balance_of(ALICE, 1), 100);
+    assert!(!contract.in_transaction());
+}
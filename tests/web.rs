@@ -0,0 +1,99 @@
+//! Browser tests for the bindgen layer. Run with `wasm-pack test --headless --firefox`.
+#![cfg(target_arch = "wasm32")]
+
+use erc1155_wasm::{AsyncERC1155, ERC1155};
+use js_sys::{Array, Error, Function, Reflect};
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+fn funded_contract() -> ERC1155 {
+    let mut contract = ERC1155::new("owner").unwrap();
+    contract.add_admin("owner", "alice").unwrap();
+    contract.mint("alice", 1, 100).unwrap();
+    contract
+}
+
+#[wasm_bindgen_test]
+fn errors_cross_the_boundary_as_named_js_errors() {
+    let mut contract = ERC1155::new("owner").unwrap();
+    let err: JsValue = contract.mint("mallory", 1, 1).unwrap_err().into();
+    let err: Error = err.dyn_into().expect("contract errors are JS Error objects");
+
+    assert_eq!(err.name(), "Unauthorized");
+    assert_eq!(err.message(), "Caller is not authorized to mint tokens.");
+}
+
+#[wasm_bindgen_test]
+fn receipts_are_js_objects() {
+    let mut contract = funded_contract();
+    let receipt: JsValue = contract.transfer("alice", "bob", 1, 40).unwrap().into();
+
+    let to_balance = Reflect::get(&receipt, &"to_balance".into()).unwrap();
+    assert_eq!(to_balance, JsValue::from(40u64));
+    let from = Reflect::get(&receipt, &"from".into()).unwrap();
+    assert_eq!(from.as_string().as_deref(), Some("alice"));
+}
+
+#[wasm_bindgen_test]
+async fn async_transfer_resolves_to_receipt() {
+    let facade = AsyncERC1155::from_contract(funded_contract());
+    let value = JsFuture::from(facade.transfer_async("alice".into(), "bob".into(), 1, 25, None))
+        .await
+        .unwrap();
+
+    let to_balance = Reflect::get(&value, &"to_balance".into()).unwrap();
+    assert_eq!(to_balance, JsValue::from(25u64));
+    assert_eq!(facade.balance_of("bob", 1), 25);
+}
+
+#[wasm_bindgen_test]
+async fn async_receiver_hook_can_reject() {
+    let facade = AsyncERC1155::from_contract(funded_contract());
+    let hook = Function::new_no_args("return Promise.resolve(false);");
+    let err = JsFuture::from(facade.transfer_async("alice".into(), "bob".into(), 1, 25, Some(hook)))
+        .await
+        .unwrap_err();
+
+    assert_eq!(err.unchecked_into::<Error>().name(), "TransferRejected");
+    assert_eq!(facade.balance_of("alice", 1), 100);
+}
+
+#[wasm_bindgen_test]
+async fn async_batch_transfer_applies_every_chunk() {
+    let mut contract = funded_contract();
+    contract.mint("alice", 2, 10).unwrap();
+    let facade = AsyncERC1155::from_contract(contract);
+
+    let value = JsFuture::from(facade.transfer_batch_async("alice".into(), "bob".into(), vec![1, 2, 1], vec![5, 10, 5], 1))
+        .await
+        .unwrap();
+
+    assert_eq!(value.unchecked_into::<Array>().length(), 3);
+    assert_eq!(facade.balance_of("bob", 1), 10);
+    assert_eq!(facade.balance_of("bob", 2), 10);
+}
+
+
+#[wasm_bindgen_test]
+async fn async_batch_transfer_resolves_names_and_needs_no_open_transaction() {
+    let mut contract = funded_contract();
+    contract.register_name("bob", "bobby").unwrap();
+    let facade = AsyncERC1155::from_contract(contract);
+
+    let batch = facade.transfer_batch_async(" alice ".into(), "@bobby".into(), vec![1, 1], vec![5, 5], 1);
+    let value = JsFuture::from(batch).await.unwrap();
+    assert_eq!(value.unchecked_into::<Array>().length(), 2);
+    assert_eq!(facade.balance_of("bob", 1), 10);
+
+    let mut contract = funded_contract();
+    contract.begin_transaction().unwrap();
+    let facade = AsyncERC1155::from_contract(contract);
+    let err = JsFuture::from(facade.transfer_batch_async("alice".into(), "bob".into(), vec![1], vec![5], 1))
+        .await
+        .unwrap_err();
+    assert_eq!(err.unchecked_into::<Error>().name(), "InvalidInput");
+    assert_eq!(facade.balance_of("bob", 1), 0);
+}
@@ -0,0 +1,79 @@
+mod common;
+
+use common::{ADMIN, ALICE, OWNER};
+use erc1155_wasm::{ErrorKind, EventKind, ERC1155};
+
+const BRIDGE: &str = "bridge";
+const GOLD: u32 = 7;
+const ASSET: &str = "erc20:0xgold";
+
+/// Contract with GOLD mapped to an external asset and BRIDGE as wrap operator.
+fn setup() -> ERC1155 {
+    let mut contract = common::setup();
+    contract.set_wrap_operator(OWNER, BRIDGE, true).unwrap();
+    contract.map_wrapped_asset(ADMIN, GOLD, ASSET).unwrap();
+    contract
+}
+
+#[test]
+fn assets_are_mapped_once() {
+    let mut contract = setup();
+    assert!(contract.is_wrap_operator(BRIDGE));
+    assert_eq!(contract.wrapped_asset(GOLD).as_deref(), Some(ASSET));
+    assert_eq!(contract.wrapped_token(ASSET), Some(GOLD));
+
+    let err = contract.map_wrapped_asset(BRIDGE, 8, "erc20:0xsilver").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Unauthorized);
+    let err = contract.map_wrapped_asset(ADMIN, 8, ASSET).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    let err = contract.map_wrapped_asset(ADMIN, GOLD, "erc20:0xsilver").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    contract.mint(ADMIN, 9, 1).unwrap();
+    let err = contract.map_wrapped_asset(ADMIN, 9, "erc20:0xsilver").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+}
+
+#[test]
+fn deposits_mint_once_per_reference() {
+    let mut contract = setup();
+    let err = contract.deposit(ALICE, GOLD, ALICE, 100, "0xtx1").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Unauthorized);
+    let err = contract.deposit(BRIDGE, 8, ALICE, 100, "0xtx1").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+
+    let receipt = contract.deposit(BRIDGE, GOLD, ALICE, 100, "0xtx1").unwrap();
+    let deposited = receipt.events.last().unwrap();
+    assert_eq!(deposited.kind, EventKind::Deposited);
+    assert_eq!(deposited.value.as_deref(), Some("0xtx1"));
+    assert_eq!(contract.balance_of(ALICE, GOLD), 100);
+
+    let err = contract.deposit(BRIDGE, GOLD, ALICE, 100, "0xtx1").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    // Wrapped tokens are backed by deposits only.
+    let err = contract.mint(ADMIN, GOLD, 1).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+
+    contract.set_wrap_operator(ADMIN, BRIDGE, false).unwrap();
+    let err = contract.deposit(BRIDGE, GOLD, ALICE, 1, "0xtx2").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Unauthorized);
+}
+
+#[test]
+fn withdrawals_burn_and_notify_bridge() {
+    let mut contract = setup();
+    contract.deposit(BRIDGE, GOLD, ALICE, 100, "0xtx1").unwrap();
+
+    let err = contract.withdraw(ALICE, GOLD, 101, "0xalice").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InsufficientBalance);
+    let err = contract.withdraw(ALICE, GOLD, 10, " ").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+
+    let receipt = contract.withdraw(ALICE, GOLD, 40, "0xalice").unwrap();
+    assert_eq!(receipt.balances[0].after, 60);
+    let withdrawal = receipt.events.last().unwrap();
+    assert_eq!(withdrawal.kind, EventKind::WithdrawalRequested);
+    assert_eq!(withdrawal.from.as_deref(), Some(ALICE));
+    assert_eq!(withdrawal.amount, Some(40));
+    assert_eq!(withdrawal.value.as_deref(), Some("0xalice"));
+    assert_eq!(contract.total_supply(GOLD), 60);
+}